@@ -0,0 +1,164 @@
+use crate::ArrayStack;
+
+#[derive(Debug, Clone)]
+pub struct AdjacencyLists {
+    adj: Box<[ArrayStack<usize>]>,
+}
+
+impl AdjacencyLists {
+    pub fn new(n: usize) -> Self {
+        Self {
+            adj: std::iter::repeat_with(|| ArrayStack::new(0))
+                .take(n)
+                .collect(),
+        }
+    }
+
+    pub fn n_vertices(&self) -> usize {
+        self.adj.len()
+    }
+
+    fn check(&self, j: usize) {
+        assert!(j < self.adj.len(), "vertex out of range");
+    }
+
+    // Adding an edge that is already there does nothing. Looking for it takes
+    // time proportional to the out-degree of `i`, where a plain append to
+    // `adj[i]` would take constant time.
+    pub fn add_edge(&mut self, i: usize, j: usize) {
+        self.check(j);
+        if !self.has_edge(i, j) {
+            let edges = &mut self.adj[i];
+            edges.add(edges.size(), j);
+        }
+    }
+
+    pub fn remove_edge(&mut self, i: usize, j: usize) {
+        self.check(j);
+        if let Some(k) = self.adj[i].iter().position(|&k| k == j) {
+            self.adj[i].remove(k);
+        }
+    }
+
+    pub fn has_edge(&self, i: usize, j: usize) -> bool {
+        self.check(j);
+        self.adj[i].iter().any(|&k| k == j)
+    }
+
+    // Unlike the matrix, this takes time proportional to the degree of `i`.
+    pub fn out_edges(&self, i: usize) -> ArrayStack<usize> {
+        let mut edges = ArrayStack::new(self.adj[i].size());
+        for (k, &j) in self.adj[i].iter().enumerate() {
+            edges.add(k, j);
+        }
+        edges
+    }
+
+    pub fn in_edges(&self, j: usize) -> ArrayStack<usize> {
+        self.check(j);
+        let mut edges = ArrayStack::new(0);
+        for i in (0..self.adj.len()).filter(|&i| self.adj[i].iter().any(|&k| k == j)) {
+            edges.add(edges.size(), i);
+        }
+        edges
+    }
+
+    // Visits the out-neighbours of `i` without copying them.
+    pub fn neighbours(&self, i: usize) -> crate::array_stack::Iter<'_, usize> {
+        self.adj[i].iter()
+    }
+}
+
+// Two graphs are equal when they have the same vertices and edges, whatever
+// order the edges were added in.
+impl PartialEq for AdjacencyLists {
+    fn eq(&self, other: &Self) -> bool {
+        let sorted = |edges: &ArrayStack<usize>| {
+            let mut edges = edges.iter().copied().collect::<Vec<_>>();
+            edges.sort_unstable();
+            edges
+        };
+        self.adj.len() == other.adj.len()
+            && self
+                .adj
+                .iter()
+                .zip(other.adj.iter())
+                .all(|(a, b)| a.size() == b.size() && sorted(a) == sorted(b))
+    }
+}
+
+impl Eq for AdjacencyLists {}
+
+impl crate::Graph for AdjacencyLists {
+    fn n_vertices(&self) -> usize {
+        AdjacencyLists::n_vertices(self)
+    }
+
+    fn add_edge(&mut self, i: usize, j: usize) {
+        AdjacencyLists::add_edge(self, i, j)
+    }
+
+    fn remove_edge(&mut self, i: usize, j: usize) {
+        AdjacencyLists::remove_edge(self, i, j)
+    }
+
+    fn has_edge(&self, i: usize, j: usize) -> bool {
+        AdjacencyLists::has_edge(self, i, j)
+    }
+
+    fn out_edges(&self, i: usize) -> ArrayStack<usize> {
+        AdjacencyLists::out_edges(self, i)
+    }
+
+    fn in_edges(&self, j: usize) -> ArrayStack<usize> {
+        AdjacencyLists::in_edges(self, j)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scenario() {
+        let mut g = AdjacencyLists::new(3);
+        assert_eq!(g.n_vertices(), 3);
+        g.add_edge(0, 2);
+        g.add_edge(0, 1);
+        g.add_edge(0, 1);
+        g.add_edge(1, 1);
+        assert!(g.has_edge(1, 1));
+        assert!(g.out_edges(0).iter().eq(&[2, 1]));
+        assert!(g.neighbours(0).eq(&[2, 1]));
+        assert!(g.in_edges(1).iter().eq(&[0, 1]));
+
+        g.remove_edge(0, 2);
+        g.remove_edge(0, 2);
+        assert!(g.out_edges(0).iter().eq(&[1]));
+        assert_eq!(g.in_edges(2).size(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "vertex out of range")]
+    fn out_of_range() {
+        AdjacencyLists::new(3).add_edge(0, 3);
+    }
+
+    #[test]
+    fn equality() {
+        let mut g = AdjacencyLists::new(3);
+        let mut h = AdjacencyLists::new(3);
+        for j in [0, 1, 2] {
+            g.add_edge(0, j);
+            h.add_edge(0, 2 - j);
+        }
+        g.add_edge(2, 1);
+        h.add_edge(2, 1);
+        assert!(!g.out_edges(0).iter().eq(h.out_edges(0).iter()));
+        assert_eq!(g, h);
+        h.remove_edge(0, 1);
+        h.add_edge(0, 0);
+        assert_ne!(g, h);
+        assert_ne!(AdjacencyLists::new(3), AdjacencyLists::new(4));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::ArrayStack;
+
+// Two matrices are equal when they have the same vertices and edges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdjacencyMatrix {
+    n: usize,
+    a: Box<[bool]>,
+}
+
+impl AdjacencyMatrix {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            a: vec![false; n * n].into_boxed_slice(),
+        }
+    }
+
+    pub fn n_vertices(&self) -> usize {
+        self.n
+    }
+
+    fn index(&self, i: usize, j: usize) -> usize {
+        assert!(i < self.n && j < self.n, "vertex out of range");
+        i * self.n + j
+    }
+
+    pub fn add_edge(&mut self, i: usize, j: usize) {
+        let k = self.index(i, j);
+        self.a[k] = true;
+    }
+
+    pub fn remove_edge(&mut self, i: usize, j: usize) {
+        let k = self.index(i, j);
+        self.a[k] = false;
+    }
+
+    pub fn has_edge(&self, i: usize, j: usize) -> bool {
+        self.a[self.index(i, j)]
+    }
+
+    pub fn out_edges(&self, i: usize) -> ArrayStack<usize> {
+        let mut edges = ArrayStack::new(0);
+        for j in (0..self.n).filter(|&j| self.has_edge(i, j)) {
+            edges.add(edges.size(), j);
+        }
+        edges
+    }
+
+    pub fn in_edges(&self, j: usize) -> ArrayStack<usize> {
+        let mut edges = ArrayStack::new(0);
+        for i in (0..self.n).filter(|&i| self.has_edge(i, j)) {
+            edges.add(edges.size(), i);
+        }
+        edges
+    }
+}
+
+impl crate::Graph for AdjacencyMatrix {
+    fn n_vertices(&self) -> usize {
+        AdjacencyMatrix::n_vertices(self)
+    }
+
+    fn add_edge(&mut self, i: usize, j: usize) {
+        AdjacencyMatrix::add_edge(self, i, j)
+    }
+
+    fn remove_edge(&mut self, i: usize, j: usize) {
+        AdjacencyMatrix::remove_edge(self, i, j)
+    }
+
+    fn has_edge(&self, i: usize, j: usize) -> bool {
+        AdjacencyMatrix::has_edge(self, i, j)
+    }
+
+    fn out_edges(&self, i: usize) -> ArrayStack<usize> {
+        AdjacencyMatrix::out_edges(self, i)
+    }
+
+    fn in_edges(&self, j: usize) -> ArrayStack<usize> {
+        AdjacencyMatrix::in_edges(self, j)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scenario() {
+        let mut g = AdjacencyMatrix::new(3);
+        assert_eq!(g.n_vertices(), 3);
+        g.add_edge(0, 1);
+        g.add_edge(0, 1);
+        g.add_edge(1, 1);
+        g.add_edge(2, 1);
+        assert!(g.has_edge(1, 1));
+        assert!(g.out_edges(0).iter().eq(&[1]));
+        assert!(g.in_edges(1).iter().eq(&[0, 1, 2]));
+
+        g.remove_edge(1, 1);
+        assert!(!g.has_edge(1, 1));
+        assert!(g.in_edges(1).iter().eq(&[0, 2]));
+        assert_eq!(g.out_edges(1).size(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "vertex out of range")]
+    fn out_of_range() {
+        AdjacencyMatrix::new(3).add_edge(0, 3);
+    }
+
+    #[test]
+    fn equality() {
+        let mut g = AdjacencyMatrix::new(3);
+        let mut h = AdjacencyMatrix::new(3);
+        g.add_edge(0, 1);
+        g.add_edge(2, 0);
+        h.add_edge(2, 0);
+        h.add_edge(0, 1);
+        assert_eq!(g, h);
+        h.add_edge(1, 1);
+        assert_ne!(g, h);
+        h.remove_edge(1, 1);
+        assert_eq!(g, h);
+        assert_ne!(g, AdjacencyMatrix::new(4));
+    }
+}
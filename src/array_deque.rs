@@ -1,37 +1,120 @@
-#[derive(Debug)]
-pub struct ArrayDeque<T> {
+use std::{
+    cmp::Ordering,
+    collections::TryReserveError,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::{Index, IndexMut, RangeBounds},
+};
+
+use crate::{Doubling, Error, GrowthPolicy, error};
+
+pub use crate::ring::{IntoIter, Iter, IterMut};
+
+#[derive(Clone)]
+pub struct ArrayDeque<T, P = Doubling> {
     a: Box<[Option<T>]>,
     j: usize,
     n: usize,
+    policy: P,
 }
 
 impl<T> ArrayDeque<T> {
     pub fn new(length: usize) -> Self {
+        Self::with_policy(length, Doubling)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_policy(capacity, Doubling)
+    }
+
+    pub fn try_with_capacity(length: usize) -> Result<Self, TryReserveError> {
+        Self::try_with_policy(length, Doubling)
+    }
+}
+
+impl<T, P: GrowthPolicy> ArrayDeque<T, P> {
+    pub fn with_policy(length: usize, policy: P) -> Self {
         let a = crate::util::allocate(length);
-        Self { a, j: 0, n: 0 }
+        Self {
+            a,
+            j: 0,
+            n: 0,
+            policy,
+        }
+    }
+
+    pub fn try_with_policy(length: usize, policy: P) -> Result<Self, TryReserveError> {
+        let a = crate::util::try_allocate(length)?;
+        Ok(Self {
+            a,
+            j: 0,
+            n: 0,
+            policy,
+        })
+    }
+
+    pub fn policy(&self) -> &P {
+        &self.policy
     }
 
     pub fn length(&self) -> usize {
         self.a.len()
     }
 
+    pub fn capacity(&self) -> usize {
+        self.a.len()
+    }
+
     pub fn size(&self) -> usize {
         self.n
     }
 
     pub fn get(&self, i: usize) -> Option<&T> {
-        self.a.get((i + self.j) % self.a.len())?.as_ref()
+        if i >= self.n {
+            return None;
+        }
+        self.a[(i + self.j) % self.a.len()].as_ref()
+    }
+
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        if i >= self.n {
+            return None;
+        }
+        let m = self.a.len();
+        self.a[(i + self.j) % m].as_mut()
     }
 
     pub fn set(&mut self, i: usize, x: T) -> Option<T> {
-        self.a.get_mut((i + self.j) % self.a.len())?.replace(x)
+        if i >= self.n {
+            return None;
+        }
+        self.a[(i + self.j) % self.a.len()].replace(x)
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(&self.a, self.j, self.n)
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut::new(&mut self.a, self.j, self.n)
     }
 
     pub fn add(&mut self, i: usize, x: T) {
         if self.n + 1 > self.a.len() {
             self.resize();
         }
+        self.add_no_resize(i, x);
+    }
 
+    pub fn try_add(&mut self, i: usize, x: T) -> Result<(), Error> {
+        error::check_insert(i, self.n)?;
+        self.try_reserve(1)?;
+        self.add_no_resize(i, x);
+        Ok(())
+    }
+
+    pub(crate) fn add_no_resize(&mut self, i: usize, x: T) {
+        error::assert_insert(i, self.n);
         if i < self.n.div_ceil(2) {
             // shift left part to left
             self.j = if self.j == 0 {
@@ -57,7 +140,16 @@ impl<T> ArrayDeque<T> {
     }
 
     pub fn remove(&mut self, i: usize) -> Option<T> {
-        let x = self.a.get_mut((self.j + i) % self.a.len())?.take();
+        let x = self.remove_no_resize(i)?;
+        self.shrink();
+        Some(x)
+    }
+
+    pub(crate) fn remove_no_resize(&mut self, i: usize) -> Option<T> {
+        if i >= self.n {
+            return None;
+        }
+        let x = self.a[(self.j + i) % self.a.len()].take();
 
         if i < self.n.div_ceil(2) {
             // shift left part to right
@@ -75,14 +167,147 @@ impl<T> ArrayDeque<T> {
         }
 
         self.n -= 1;
-        if self.a.len() >= 3 * self.n {
-            self.resize();
-        }
         x
     }
 
+    pub fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for x in iter {
+            self.add(self.n, x);
+        }
+    }
+
+    // Like `add`, but the shorter side moves over by all the new elements at
+    // once.
+    pub fn insert_many<I: IntoIterator<Item = T>>(&mut self, i: usize, iter: I) {
+        error::assert_insert(i, self.n);
+        let xs = iter.into_iter().collect::<Vec<_>>();
+        let k = xs.len();
+        if k == 0 {
+            return;
+        }
+        self.reserve(k);
+        let len = self.a.len();
+        if i < self.n / 2 {
+            // shift left part to left
+            let j = (self.j + len - k) % len;
+            for m in 0..i {
+                self.a[(j + m) % len] = self.a[(self.j + m) % len].take();
+            }
+            self.j = j;
+        } else {
+            // shift right part to right
+            for m in (i..self.n).rev() {
+                self.a[(self.j + m + k) % len] = self.a[(self.j + m) % len].take();
+            }
+        }
+        for (m, x) in xs.into_iter().enumerate() {
+            self.a[(self.j + i + m) % len] = Some(x);
+        }
+        self.n += k;
+    }
+
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> IntoIter<T> {
+        let r = error::assert_range(range, self.n);
+        let k = r.len();
+        if k == 0 {
+            return IntoIter::new(Box::new([]), 0, 0);
+        }
+        let len = self.a.len();
+        let drained = r
+            .clone()
+            .map(|m| self.a[(self.j + m) % len].take())
+            .collect::<Box<[_]>>();
+        if r.start < self.n - r.end {
+            // shift left part to right
+            for m in (0..r.start).rev() {
+                self.a[(self.j + m + k) % len] = self.a[(self.j + m) % len].take();
+            }
+            self.j = (self.j + k) % len;
+        } else {
+            // shift right part to left
+            for m in r.end..self.n {
+                self.a[(self.j + m - k) % len] = self.a[(self.j + m) % len].take();
+            }
+        }
+        self.n -= k;
+        self.shrink();
+        IntoIter::new(drained, 0, k)
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        self.reserve(other.n);
+        let n = std::mem::take(&mut other.n);
+        for m in 0..n {
+            let x = other.a[(other.j + m) % other.a.len()].take();
+            let len = self.a.len();
+            self.a[(self.j + self.n) % len] = x;
+            self.n += 1;
+        }
+        other.j = 0;
+    }
+
+    pub fn split_off(&mut self, i: usize) -> Self
+    where
+        P: Clone,
+    {
+        error::assert_insert(i, self.n);
+        let mut other = Self::with_policy(self.n - i, self.policy.clone());
+        for m in i..self.n {
+            other.a[m - i] = self.a[(self.j + m) % self.a.len()].take();
+        }
+        other.n = self.n - i;
+        self.n = i;
+        self.shrink();
+        other
+    }
+
+    // Ensures that `additional` more elements fit without a resize.
+    pub fn reserve(&mut self, additional: usize) {
+        if let Some(m) = self.reserved_length(additional) {
+            self.move_into(crate::util::allocate(m));
+        }
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        if let Some(m) = self.reserved_length(additional) {
+            self.move_into(crate::util::try_allocate(m)?);
+        }
+        Ok(())
+    }
+
+    fn reserved_length(&self, additional: usize) -> Option<usize> {
+        let m = self.n.saturating_add(additional);
+        (m > self.a.len()).then(|| std::cmp::max(m, self.policy.grow(self.n)))
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        if self.a.len() > self.n {
+            self.move_into(crate::util::allocate(self.n));
+        }
+    }
+
+    // Removes everything, leaving the array for later adds.
+    pub fn clear(&mut self) {
+        let n = std::mem::take(&mut self.n);
+        for k in 0..n {
+            self.a[(self.j + k) % self.a.len()] = None;
+        }
+        self.j = 0;
+    }
+
     fn resize(&mut self) {
-        let mut b = crate::util::allocate(std::cmp::max(2 * self.n, 1));
+        self.move_into(crate::util::allocate(self.policy.grow(self.n)));
+    }
+
+    fn shrink(&mut self) {
+        if let Some(m) = self.policy.shrink(self.n, self.a.len()) {
+            self.move_into(crate::util::allocate(m));
+        }
+    }
+
+    fn move_into(&mut self, mut b: Box<[Option<T>]>) {
         for k in 0..self.n {
             b[k] = self.a.get_mut((self.j + k) % self.a.len()).unwrap().take();
         }
@@ -91,14 +316,178 @@ impl<T> ArrayDeque<T> {
     }
 }
 
+impl<T, P> IntoIterator for ArrayDeque<T, P> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self.a, self.j, self.n)
+    }
+}
+
+impl<'a, T, P: GrowthPolicy> IntoIterator for &'a ArrayDeque<T, P> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, P: GrowthPolicy> IntoIterator for &'a mut ArrayDeque<T, P> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, P: GrowthPolicy + Default> Default for ArrayDeque<T, P> {
+    fn default() -> Self {
+        Self::with_policy(0, P::default())
+    }
+}
+
+impl<T: fmt::Debug, P: GrowthPolicy> fmt::Debug for ArrayDeque<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, P: GrowthPolicy> PartialEq for ArrayDeque<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, P: GrowthPolicy> Eq for ArrayDeque<T, P> {}
+
+impl<T: PartialOrd, P: GrowthPolicy> PartialOrd for ArrayDeque<T, P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord, P: GrowthPolicy> Ord for ArrayDeque<T, P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash, P: GrowthPolicy> Hash for ArrayDeque<T, P> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.n);
+        self.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl<T, P: GrowthPolicy + Default> FromIterator<T> for ArrayDeque<T, P> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut c = Self::default();
+        c.extend(iter);
+        c
+    }
+}
+
+impl<T, P: GrowthPolicy> Extend<T> for ArrayDeque<T, P> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        ArrayDeque::extend(self, iter)
+    }
+}
+
+impl<T, P: GrowthPolicy> Index<usize> for ArrayDeque<T, P> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        self.get(i)
+            .unwrap_or_else(|| error::index_out_of_bounds(i, self.n))
+    }
+}
+
+impl<T, P: GrowthPolicy> IndexMut<usize> for ArrayDeque<T, P> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        let n = self.n;
+        self.get_mut(i)
+            .unwrap_or_else(|| error::index_out_of_bounds(i, n))
+    }
+}
+
+impl<T, P: GrowthPolicy> crate::List<T> for ArrayDeque<T, P> {
+    fn size(&self) -> usize {
+        ArrayDeque::size(self)
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        ArrayDeque::get(self, i)
+    }
+
+    fn set(&mut self, i: usize, x: T) -> Option<T> {
+        ArrayDeque::set(self, i, x)
+    }
+
+    fn add(&mut self, i: usize, x: T) {
+        ArrayDeque::add(self, i, x)
+    }
+
+    fn remove(&mut self, i: usize) -> Option<T> {
+        ArrayDeque::remove(self, i)
+    }
+}
+
+impl<T, P: GrowthPolicy> crate::Deque<T> for ArrayDeque<T, P> {
+    fn size(&self) -> usize {
+        ArrayDeque::size(self)
+    }
+
+    fn add_first(&mut self, x: T) {
+        self.add(0, x)
+    }
+
+    fn add_last(&mut self, x: T) {
+        self.add(self.n, x)
+    }
+
+    fn remove_first(&mut self) -> Option<T> {
+        if self.n == 0 {
+            return None;
+        }
+        self.remove(0)
+    }
+
+    fn remove_last(&mut self) -> Option<T> {
+        if self.n == 0 {
+            return None;
+        }
+        self.remove(self.n - 1)
+    }
+}
+
+impl<T, P: GrowthPolicy> crate::sort::Sortable<T> for ArrayDeque<T, P> {
+    fn size(&self) -> usize {
+        ArrayDeque::size(self)
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        ArrayDeque::get(self, i)
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        assert!(i < self.n && j < self.n, "index out of bounds");
+        let m = self.a.len();
+        self.a.swap((self.j + i) % m, (self.j + j) % m);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::hash_code;
 
     fn setup(deque: &mut ArrayDeque<char>, s: &str, offset: usize) {
         assert!(s.len() <= deque.length());
         for (i, c) in s.chars().enumerate() {
-            deque.set((i + offset) % deque.length(), c);
+            deque.a[(i + offset) % deque.length()] = Some(c);
         }
         deque.j = offset;
         deque.n = s.len();
@@ -151,9 +540,9 @@ mod tests {
         assert_eq!(deque.get(3), None);
         assert_eq!(deque.get(4), None);
         assert_eq!(deque.get(5), None);
-        assert_eq!(deque.get(6), Some(&'a'));
-        assert_eq!(deque.get(7), Some(&'b'));
-        assert_eq!(deque.get(8), Some(&'c'));
+        assert_eq!(deque.get(6), None);
+        assert_eq!(deque.get(7), None);
+        assert_eq!(deque.get(8), None);
         assert_eq!(deque.get(9), None);
         assert_eq!(deque.get(10), None);
         assert_eq!(deque.get(11), None);
@@ -165,14 +554,13 @@ mod tests {
         setup(&mut deque, "abc", 2);
         check(&deque, "__abc_", 2);
 
-        deque.set(0, 'A');
+        assert_eq!(deque.set(0, 'A'), Some('a'));
         check(&deque, "__Abc_", 2);
 
-        deque.set(6, 'B');
-        check(&deque, "__Bbc_", 2);
-
-        deque.set(12, 'C');
-        check(&deque, "__Cbc_", 2);
+        assert_eq!(deque.set(3, 'B'), None);
+        assert_eq!(deque.set(6, 'B'), None);
+        assert_eq!(deque.set(12, 'C'), None);
+        check(&deque, "__Abc_", 2);
     }
 
     #[test]
@@ -227,4 +615,210 @@ mod tests {
         assert_eq!(deque.remove(0), Some('a'));
         check(&deque, "ABCbdyzexfgh____________", 0);
     }
+
+    #[test]
+    fn iter() {
+        for j in 0..6 {
+            let mut deque = ArrayDeque::new(6);
+            setup(&mut deque, "abcd", j);
+
+            let mut iter = deque.iter();
+            assert_eq!(iter.len(), 4);
+            assert_eq!(iter.next(), Some(&'a'));
+            assert_eq!(iter.next_back(), Some(&'d'));
+            assert_eq!(iter.len(), 2);
+            assert_eq!(iter.collect::<String>(), "bc");
+
+            assert_eq!((&deque).into_iter().rev().collect::<String>(), "dcba");
+        }
+    }
+
+    #[test]
+    fn iter_mut() {
+        for j in 0..6 {
+            let mut deque = ArrayDeque::new(6);
+            setup(&mut deque, "abcd", j);
+
+            for c in deque.iter_mut() {
+                *c = c.to_ascii_uppercase();
+            }
+            for (i, c) in "ABCD".chars().enumerate() {
+                assert_eq!(deque.get(i), Some(&c));
+            }
+
+            for c in &mut deque {
+                *c = c.to_ascii_lowercase();
+            }
+            for (i, c) in "abcd".chars().enumerate() {
+                assert_eq!(deque.get(i), Some(&c));
+            }
+        }
+    }
+
+    #[test]
+    fn into_iter() {
+        for j in 0..6 {
+            let mut deque = ArrayDeque::new(6);
+            setup(&mut deque, "abcd", j);
+
+            let mut iter = deque.into_iter();
+            assert_eq!(iter.len(), 4);
+            assert_eq!(iter.next_back(), Some('d'));
+            assert_eq!(iter.collect::<String>(), "abc");
+        }
+    }
+
+    #[test]
+    fn iter_empty() {
+        let deque = ArrayDeque::<char>::new(0);
+        assert_eq!(deque.iter().next(), None);
+        assert_eq!(deque.into_iter().next_back(), None);
+    }
+
+    #[test]
+    fn try_reserve() {
+        let mut deque = ArrayDeque::try_with_capacity(4).unwrap();
+        setup(&mut deque, "abcd", 1);
+        check(&deque, "dabc", 1);
+        deque.try_add(1, 'x').unwrap();
+        check(&deque, "xbcd___a", 7);
+        deque.try_reserve(5).unwrap();
+        check(&deque, "axbcd_____", 0);
+
+        assert!(deque.try_reserve(usize::MAX).is_err());
+        assert_eq!(deque.size(), 5);
+        assert!(ArrayDeque::<char>::try_with_capacity(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn zero_length() {
+        let mut deque = ArrayDeque::new(0);
+        assert_eq!(deque.get(0), None);
+        assert_eq!(deque.set(0, 'a'), None);
+        assert_eq!(deque.remove(0), None);
+        check(&deque, "", 0);
+        assert_eq!(
+            deque.try_add(1, 'a'),
+            Err(Error::IndexOutOfBounds { index: 1, size: 0 })
+        );
+        deque.add(0, 'a');
+        check(&deque, "a", 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "the size is 3 but the index is 4")]
+    fn add_out_of_bounds() {
+        let mut deque = ArrayDeque::new(6);
+        setup(&mut deque, "abc", 4);
+        deque.add(4, 'x');
+    }
+
+    #[test]
+    fn never_shrink() {
+        let mut deque = ArrayDeque::with_policy(0, crate::NeverShrink(crate::Doubling));
+        for x in 0..8 {
+            deque.add(0, x);
+        }
+        assert_eq!(deque.length(), 8);
+        while deque.remove(0).is_some() {}
+        assert_eq!(deque.length(), 8);
+
+        deque.try_reserve(9).unwrap();
+        assert_eq!(deque.length(), 9);
+        deque.add(0, 8);
+        assert_eq!(deque.length(), 9);
+    }
+
+    #[test]
+    fn capacity() {
+        let mut deque = ArrayDeque::with_capacity(6);
+        setup(&mut deque, "abcd", 3);
+        check(&deque, "d__abc", 3);
+        deque.shrink_to_fit();
+        check(&deque, "abcd", 0);
+        deque.reserve(2);
+        check(&deque, "abcd____", 0);
+
+        deque.clear();
+        check(&deque, "________", 0);
+        deque.reserve(8);
+        assert_eq!(deque.capacity(), 8);
+    }
+
+    #[test]
+    fn bulk_operations() {
+        let mut rng = crate::util::Rng::with_seed(22);
+        let mut list = ArrayDeque::new(0);
+        let mut expected = Vec::new();
+        for _ in 0..300 {
+            let n = expected.len();
+            let i = rng.next_u64() as usize % (n + 1);
+            let xs = (0..rng.next_u64() % 8).map(|x| x * 10).collect::<Vec<_>>();
+            match rng.next_u64() % 4 {
+                0 => {
+                    list.insert_many(i, xs.clone());
+                    expected.splice(i..i, xs);
+                }
+                1 => {
+                    let j = i + rng.next_u64() as usize % (n - i + 1);
+                    assert!(list.drain(i..j).eq(expected.drain(i..j)));
+                }
+                2 => {
+                    let mut tail = list.split_off(i);
+                    assert!(tail.iter().eq(&expected[i..]));
+                    tail.extend(xs.clone());
+                    list.append(&mut tail);
+                    assert_eq!(tail.size(), 0);
+                    expected.extend(xs);
+                }
+                _ => {
+                    list.extend(xs.clone());
+                    expected.extend(xs);
+                }
+            }
+            assert_eq!(list.size(), expected.len());
+            assert!(list.iter().eq(&expected));
+        }
+    }
+
+    #[test]
+    fn insert_many_and_drain() {
+        let mut deque = ArrayDeque::new(8);
+        setup(&mut deque, "abcdef", 0);
+        deque.insert_many(1, "xy".chars());
+        check(&deque, "ybcdefax", 6);
+        assert!(deque.drain(5..7).eq("de".chars()));
+        check(&deque, "ybcf__ax", 6);
+    }
+
+    #[test]
+    fn standard_traits() {
+        let mut a = ArrayDeque::new(6);
+        setup(&mut a, "abc", 0);
+        let mut b = ArrayDeque::new(4);
+        setup(&mut b, "abc", 3);
+        check(&b, "bc_a", 3);
+
+        assert_eq!(a, b);
+        assert_eq!(hash_code(&a), hash_code(&b));
+        assert_eq!(format!("{b:?}"), "['a', 'b', 'c']");
+
+        b[1] = 'x';
+        assert_eq!(b[1], 'x');
+        assert!(a < b);
+        assert_eq!(a.cmp(&a.clone()), Ordering::Equal);
+
+        let c: ArrayDeque<char> = "abc".chars().collect();
+        assert_eq!(c, a);
+        let mut d = ArrayDeque::<char>::default();
+        d.extend("ab".chars());
+        assert!(d < a);
+    }
+
+    #[test]
+    #[should_panic(expected = "the size is 3 but the index is 3")]
+    fn index_out_of_bounds() {
+        let deque: ArrayDeque<char> = "abc".chars().collect();
+        let _ = deque[3];
+    }
 }
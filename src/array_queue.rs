@@ -1,53 +1,176 @@
-#[derive(Debug)]
-pub struct ArrayQueue<T> {
+use std::{
+    cmp::Ordering,
+    collections::TryReserveError,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::{Index, IndexMut},
+};
+
+use crate::{Doubling, Error, GrowthPolicy, error};
+
+pub use crate::ring::{IntoIter, Iter, IterMut};
+
+#[derive(Clone)]
+pub struct ArrayQueue<T, P = Doubling> {
     a: Box<[Option<T>]>,
     j: usize,
     n: usize,
+    policy: P,
 }
 
 impl<T> ArrayQueue<T> {
     pub fn new(length: usize) -> Self {
+        Self::with_policy(length, Doubling)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_policy(capacity, Doubling)
+    }
+
+    pub fn try_with_capacity(length: usize) -> Result<Self, TryReserveError> {
+        Self::try_with_policy(length, Doubling)
+    }
+}
+
+impl<T, P: GrowthPolicy> ArrayQueue<T, P> {
+    pub fn with_policy(length: usize, policy: P) -> Self {
         let a = crate::util::allocate(length);
-        Self { a, j: 0, n: 0 }
+        Self {
+            a,
+            j: 0,
+            n: 0,
+            policy,
+        }
+    }
+
+    pub fn try_with_policy(length: usize, policy: P) -> Result<Self, TryReserveError> {
+        let a = crate::util::try_allocate(length)?;
+        Ok(Self {
+            a,
+            j: 0,
+            n: 0,
+            policy,
+        })
+    }
+
+    pub fn policy(&self) -> &P {
+        &self.policy
     }
 
     pub fn length(&self) -> usize {
         self.a.len()
     }
 
+    pub fn capacity(&self) -> usize {
+        self.a.len()
+    }
+
     pub fn size(&self) -> usize {
         self.n
     }
 
     pub fn get(&self, i: usize) -> Option<&T> {
-        self.a.get((i + self.j) % self.a.len())?.as_ref()
+        if i >= self.n {
+            return None;
+        }
+        self.a[(i + self.j) % self.a.len()].as_ref()
+    }
+
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        if i >= self.n {
+            return None;
+        }
+        let m = self.a.len();
+        self.a[(i + self.j) % m].as_mut()
     }
 
     pub fn set(&mut self, i: usize, x: T) -> Option<T> {
-        self.a.get_mut((i + self.j) % self.a.len())?.replace(x)
+        if i >= self.n {
+            return None;
+        }
+        self.a[(i + self.j) % self.a.len()].replace(x)
     }
 
-    pub fn add(&mut self, x: T) -> bool {
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(&self.a, self.j, self.n)
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut::new(&mut self.a, self.j, self.n)
+    }
+
+    pub fn add(&mut self, x: T) {
         if self.n + 1 > self.a.len() {
             self.resize();
         }
         self.a[(self.j + self.n) % self.a.len()] = Some(x);
         self.n += 1;
-        true
+    }
+
+    // Drops `x` and reports the failure if the array is full and cannot grow.
+    pub fn try_add(&mut self, x: T) -> Result<(), Error> {
+        self.try_reserve(1)?;
+        self.add(x);
+        Ok(())
     }
 
     pub fn remove(&mut self) -> Option<T> {
-        let x = self.a.get_mut(self.j)?.take();
+        if self.n == 0 {
+            return None;
+        }
+        let x = self.a[self.j].take();
         self.j = (self.j + 1) % self.a.len();
         self.n -= 1;
-        if self.a.len() >= 3 * self.n {
-            self.resize();
-        }
+        self.shrink();
         x
     }
 
+    // Grows the array, if needed, so that `additional` more elements fit.
+    pub fn reserve(&mut self, additional: usize) {
+        if let Some(m) = self.reserved_length(additional) {
+            self.move_into(crate::util::allocate(m));
+        }
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        if let Some(m) = self.reserved_length(additional) {
+            self.move_into(crate::util::try_allocate(m)?);
+        }
+        Ok(())
+    }
+
+    fn reserved_length(&self, additional: usize) -> Option<usize> {
+        let m = self.n.saturating_add(additional);
+        (m > self.a.len()).then(|| std::cmp::max(m, self.policy.grow(self.n)))
+    }
+
+    // Reallocates to exactly `size()` slots.
+    pub fn shrink_to_fit(&mut self) {
+        if self.a.len() > self.n {
+            self.move_into(crate::util::allocate(self.n));
+        }
+    }
+
+    // Empties the queue without giving up its array.
+    pub fn clear(&mut self) {
+        let n = std::mem::take(&mut self.n);
+        for k in 0..n {
+            self.a[(self.j + k) % self.a.len()] = None;
+        }
+        self.j = 0;
+    }
+
     fn resize(&mut self) {
-        let mut b = crate::util::allocate(std::cmp::max(2 * self.n, 1));
+        self.move_into(crate::util::allocate(self.policy.grow(self.n)));
+    }
+
+    fn shrink(&mut self) {
+        if let Some(m) = self.policy.shrink(self.n, self.a.len()) {
+            self.move_into(crate::util::allocate(m));
+        }
+    }
+
+    fn move_into(&mut self, mut b: Box<[Option<T>]>) {
         for k in 0..self.n {
             b[k] = self.a.get_mut((self.j + k) % self.a.len()).unwrap().take();
         }
@@ -56,6 +179,117 @@ impl<T> ArrayQueue<T> {
     }
 }
 
+impl<T, P> IntoIterator for ArrayQueue<T, P> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self.a, self.j, self.n)
+    }
+}
+
+impl<'a, T, P: GrowthPolicy> IntoIterator for &'a ArrayQueue<T, P> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, P: GrowthPolicy> IntoIterator for &'a mut ArrayQueue<T, P> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, P: GrowthPolicy + Default> Default for ArrayQueue<T, P> {
+    fn default() -> Self {
+        Self::with_policy(0, P::default())
+    }
+}
+
+impl<T: fmt::Debug, P: GrowthPolicy> fmt::Debug for ArrayQueue<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, P: GrowthPolicy> PartialEq for ArrayQueue<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, P: GrowthPolicy> Eq for ArrayQueue<T, P> {}
+
+impl<T: PartialOrd, P: GrowthPolicy> PartialOrd for ArrayQueue<T, P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord, P: GrowthPolicy> Ord for ArrayQueue<T, P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash, P: GrowthPolicy> Hash for ArrayQueue<T, P> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.n);
+        self.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl<T, P: GrowthPolicy + Default> FromIterator<T> for ArrayQueue<T, P> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut c = Self::default();
+        c.extend(iter);
+        c
+    }
+}
+
+impl<T, P: GrowthPolicy> Extend<T> for ArrayQueue<T, P> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|x| self.add(x));
+    }
+}
+
+impl<T, P: GrowthPolicy> Index<usize> for ArrayQueue<T, P> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        self.get(i)
+            .unwrap_or_else(|| error::index_out_of_bounds(i, self.n))
+    }
+}
+
+impl<T, P: GrowthPolicy> IndexMut<usize> for ArrayQueue<T, P> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        let n = self.n;
+        self.get_mut(i)
+            .unwrap_or_else(|| error::index_out_of_bounds(i, n))
+    }
+}
+
+impl<T, P: GrowthPolicy> crate::Queue<T> for ArrayQueue<T, P> {
+    fn size(&self) -> usize {
+        ArrayQueue::size(self)
+    }
+
+    fn add(&mut self, x: T) {
+        ArrayQueue::add(self, x)
+    }
+
+    fn remove(&mut self) -> Option<T> {
+        ArrayQueue::remove(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -63,7 +297,7 @@ mod tests {
     fn setup(queue: &mut ArrayQueue<char>, s: &str, offset: usize) {
         assert!(s.len() <= queue.length());
         for (i, c) in s.chars().enumerate() {
-            queue.set((i + offset) % queue.length(), c);
+            queue.a[(i + offset) % queue.length()] = Some(c);
         }
         queue.j = offset;
         queue.n = s.len();
@@ -116,9 +350,9 @@ mod tests {
         assert_eq!(queue.get(3), None);
         assert_eq!(queue.get(4), None);
         assert_eq!(queue.get(5), None);
-        assert_eq!(queue.get(6), Some(&'a'));
-        assert_eq!(queue.get(7), Some(&'b'));
-        assert_eq!(queue.get(8), Some(&'c'));
+        assert_eq!(queue.get(6), None);
+        assert_eq!(queue.get(7), None);
+        assert_eq!(queue.get(8), None);
         assert_eq!(queue.get(9), None);
         assert_eq!(queue.get(10), None);
         assert_eq!(queue.get(11), None);
@@ -130,14 +364,13 @@ mod tests {
         setup(&mut queue, "abc", 2);
         check(&queue, "__abc_", 2);
 
-        queue.set(0, 'A');
+        assert_eq!(queue.set(0, 'A'), Some('a'));
         check(&queue, "__Abc_", 2);
 
-        queue.set(6, 'B');
-        check(&queue, "__Bbc_", 2);
-
-        queue.set(12, 'C');
-        check(&queue, "__Cbc_", 2);
+        assert_eq!(queue.set(3, 'B'), None);
+        assert_eq!(queue.set(6, 'B'), None);
+        assert_eq!(queue.set(12, 'C'), None);
+        check(&queue, "__Abc_", 2);
     }
 
     #[test]
@@ -189,4 +422,143 @@ mod tests {
         assert_eq!(queue.remove(), Some('b'));
         check(&queue, "_cdefgh_____", 1);
     }
+
+    #[test]
+    fn iter() {
+        for j in 0..6 {
+            let mut queue = ArrayQueue::new(6);
+            setup(&mut queue, "abcd", j);
+
+            let mut iter = queue.iter();
+            assert_eq!(iter.len(), 4);
+            assert_eq!(iter.next(), Some(&'a'));
+            assert_eq!(iter.next_back(), Some(&'d'));
+            assert_eq!(iter.len(), 2);
+            assert_eq!(iter.collect::<String>(), "bc");
+
+            assert_eq!((&queue).into_iter().rev().collect::<String>(), "dcba");
+        }
+    }
+
+    #[test]
+    fn iter_mut() {
+        for j in 0..6 {
+            let mut queue = ArrayQueue::new(6);
+            setup(&mut queue, "abcd", j);
+
+            for c in queue.iter_mut() {
+                *c = c.to_ascii_uppercase();
+            }
+            for (i, c) in "ABCD".chars().enumerate() {
+                assert_eq!(queue.get(i), Some(&c));
+            }
+
+            for c in &mut queue {
+                *c = c.to_ascii_lowercase();
+            }
+            for (i, c) in "abcd".chars().enumerate() {
+                assert_eq!(queue.get(i), Some(&c));
+            }
+        }
+    }
+
+    #[test]
+    fn into_iter() {
+        for j in 0..6 {
+            let mut queue = ArrayQueue::new(6);
+            setup(&mut queue, "abcd", j);
+
+            let mut iter = queue.into_iter();
+            assert_eq!(iter.len(), 4);
+            assert_eq!(iter.next_back(), Some('d'));
+            assert_eq!(iter.collect::<String>(), "abc");
+        }
+    }
+
+    #[test]
+    fn iter_empty() {
+        let queue = ArrayQueue::<char>::new(0);
+        assert_eq!(queue.iter().next(), None);
+        assert_eq!(queue.into_iter().next_back(), None);
+    }
+
+    #[test]
+    fn try_reserve() {
+        let mut queue = ArrayQueue::try_with_capacity(4).unwrap();
+        setup(&mut queue, "abc", 2);
+        check(&queue, "c_ab", 2);
+        queue.try_add('d').unwrap();
+        check(&queue, "cdab", 2);
+        queue.try_add('e').unwrap();
+        check(&queue, "abcde___", 0);
+        queue.try_reserve(4).unwrap();
+        check(&queue, "abcde_____", 0);
+
+        assert!(queue.try_reserve(usize::MAX).is_err());
+        assert_eq!(queue.size(), 5);
+        assert!(ArrayQueue::<char>::try_with_capacity(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn zero_length() {
+        let mut queue = ArrayQueue::new(0);
+        assert_eq!(queue.get(0), None);
+        assert_eq!(queue.set(0, 'a'), None);
+        assert_eq!(queue.remove(), None);
+        check(&queue, "", 0);
+        queue.add('a');
+        check(&queue, "a", 0);
+    }
+
+    #[test]
+    fn hysteresis() {
+        let mut queue = ArrayQueue::with_policy(0, crate::Hysteresis::new(crate::Doubling, 8));
+        for x in 0..16 {
+            queue.add(x);
+        }
+        assert_eq!(queue.length(), 16);
+        for x in 0..13 {
+            assert_eq!(queue.remove(), Some(x));
+        }
+        assert_eq!(queue.length(), 16);
+        assert_eq!(queue.remove(), Some(13));
+        assert_eq!(queue.length(), 4);
+        assert!(queue.iter().eq(&[14, 15]));
+    }
+
+    #[test]
+    fn capacity() {
+        let mut queue = ArrayQueue::with_capacity(6);
+        setup(&mut queue, "abc", 4);
+        check(&queue, "c___ab", 4);
+        queue.reserve(4);
+        check(&queue, "abc____", 0);
+        queue.shrink_to_fit();
+        check(&queue, "abc", 0);
+        assert_eq!(queue.capacity(), 3);
+
+        queue.clear();
+        check(&queue, "___", 0);
+        queue.add('d');
+        check(&queue, "d__", 0);
+    }
+
+    #[test]
+    fn standard_traits() {
+        let mut a = ArrayQueue::new(5);
+        setup(&mut a, "abc", 4);
+        let b: ArrayQueue<char> = "abc".chars().collect();
+        assert_eq!(a, b);
+        assert_eq!(crate::util::hash_code(&a), crate::util::hash_code(&b));
+        assert_eq!(format!("{a:?}"), "['a', 'b', 'c']");
+
+        let mut c = a.clone();
+        check(&c, "bc__a", 4);
+        c[2] = 'd';
+        assert_eq!(c[2], 'd');
+        assert!(a < c);
+        c.extend("e".chars());
+        assert_eq!(c.size(), 4);
+        assert_eq!(ArrayQueue::<char>::default(), ArrayQueue::new(3));
+    }
 }
@@ -1,48 +1,121 @@
-#[derive(Debug)]
-pub struct ArrayStack<T> {
+use std::{
+    cmp::Ordering,
+    collections::TryReserveError,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::{Index, IndexMut, RangeBounds},
+};
+
+use crate::{Doubling, Error, GrowthPolicy, error};
+
+#[derive(Clone)]
+pub struct ArrayStack<T, P = Doubling> {
     a: Box<[Option<T>]>,
     n: usize,
+    policy: P,
 }
 
 impl<T> ArrayStack<T> {
     pub fn new(length: usize) -> Self {
+        Self::with_policy(length, Doubling)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_policy(capacity, Doubling)
+    }
+
+    pub fn try_with_capacity(length: usize) -> Result<Self, TryReserveError> {
+        Self::try_with_policy(length, Doubling)
+    }
+}
+
+impl<T, P: GrowthPolicy> ArrayStack<T, P> {
+    pub fn with_policy(length: usize, policy: P) -> Self {
         let a = crate::util::allocate(length);
-        Self { a, n: 0 }
+        Self { a, n: 0, policy }
+    }
+
+    pub fn try_with_policy(length: usize, policy: P) -> Result<Self, TryReserveError> {
+        let a = crate::util::try_allocate(length)?;
+        Ok(Self { a, n: 0, policy })
     }
 
-    pub(crate) fn from_raw(a: Box<[Option<T>]>, n: usize) -> Self {
-        Self { a, n }
+    pub(crate) fn from_raw(a: Box<[Option<T>]>, n: usize, policy: P) -> Self {
+        Self { a, n, policy }
+    }
+
+    pub fn policy(&self) -> &P {
+        &self.policy
     }
 
     pub fn length(&self) -> usize {
         self.a.len()
     }
 
+    pub fn capacity(&self) -> usize {
+        self.a.len()
+    }
+
     pub fn size(&self) -> usize {
         self.n
     }
 
     pub fn get(&self, i: usize) -> Option<&T> {
-        self.a.get(i)?.as_ref()
+        self.as_slice().get(i)?.as_ref()
+    }
+
+    pub(crate) fn as_slice(&self) -> &[Option<T>] {
+        &self.a[..self.n]
+    }
+
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [Option<T>] {
+        &mut self.a[..self.n]
     }
 
-    pub(crate) fn get_mut(&mut self, i: usize) -> Option<&mut T> {
-        self.a.get_mut(i)?.as_mut()
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.as_slice().iter(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            inner: self.as_mut_slice().iter_mut(),
+        }
+    }
+
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        self.as_mut_slice().get_mut(i)?.as_mut()
     }
 
     pub(crate) fn take(&mut self, i: usize) -> Option<T> {
-        self.a.get_mut(i)?.take()
+        self.as_mut_slice().get_mut(i)?.take()
     }
 
     pub fn set(&mut self, i: usize, x: T) -> Option<T> {
-        self.a.get_mut(i)?.replace(x)
+        self.as_mut_slice().get_mut(i)?.replace(x)
     }
 
     pub fn add(&mut self, i: usize, x: T) {
+        error::assert_insert(i, self.n);
         if self.n + 1 > self.a.len() {
             self.resize();
         }
+        self.add_no_resize(i, x);
+    }
 
+    // Like `add`, but fails instead of panicking or aborting, in which case
+    // `x` is dropped.
+    pub fn try_add(&mut self, i: usize, x: T) -> Result<(), Error> {
+        error::check_insert(i, self.n)?;
+        if self.n + 1 > self.a.len() {
+            self.try_reserve(1)?;
+        }
+        self.add_no_resize(i, x);
+        Ok(())
+    }
+
+    fn add_no_resize(&mut self, i: usize, x: T) {
         for j in (i + 1..=self.n).rev() {
             self.a.swap(j, j - 1);
         }
@@ -51,19 +124,113 @@ impl<T> ArrayStack<T> {
     }
 
     pub fn remove(&mut self, i: usize) -> Option<T> {
-        let x = self.a.get_mut(i)?.take();
+        let x = self.as_mut_slice().get_mut(i)?.take();
         for j in i..self.n - 1 {
             self.a.swap(j, j + 1);
         }
         self.n -= 1;
-        if self.a.len() >= 3 * self.n {
-            self.resize();
-        }
+        self.shrink();
         x
     }
 
+    pub fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for x in iter {
+            self.add(self.n, x);
+        }
+    }
+
+    // Adds the new elements at the end and rotates them into place, which
+    // moves the old tail once however many there are.
+    pub fn insert_many<I: IntoIterator<Item = T>>(&mut self, i: usize, iter: I) {
+        error::assert_insert(i, self.n);
+        let n = self.n;
+        self.extend(iter);
+        self.a[i..self.n].rotate_left(n - i);
+    }
+
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> IntoIter<T> {
+        let r = error::assert_range(range, self.n);
+        let drained = r.clone().map(|j| self.a[j].take()).collect::<Vec<_>>();
+        self.a[r.start..self.n].rotate_left(r.len());
+        self.n -= r.len();
+        self.shrink();
+        IntoIter {
+            inner: drained.into_iter(),
+        }
+    }
+
+    // Moves every element of `other` to the end, leaving `other` empty but
+    // with its array.
+    pub fn append(&mut self, other: &mut Self) {
+        self.reserve(other.n);
+        let n = std::mem::take(&mut other.n);
+        for x in &mut other.a[..n] {
+            self.a[self.n] = x.take();
+            self.n += 1;
+        }
+    }
+
+    pub fn split_off(&mut self, i: usize) -> Self
+    where
+        P: Clone,
+    {
+        error::assert_insert(i, self.n);
+        let mut other = Self::with_policy(self.n - i, self.policy.clone());
+        for j in i..self.n {
+            other.a[j - i] = self.a[j].take();
+        }
+        other.n = self.n - i;
+        self.n = i;
+        self.shrink();
+        other
+    }
+
+    // Makes room for at least `additional` more elements, growing the array
+    // as `add` would if it grows at all. A later `remove` may shrink it again.
+    pub fn reserve(&mut self, additional: usize) {
+        if let Some(m) = self.reserved_length(additional) {
+            self.move_into(crate::util::allocate(m));
+        }
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        if let Some(m) = self.reserved_length(additional) {
+            self.move_into(crate::util::try_allocate(m)?);
+        }
+        Ok(())
+    }
+
+    fn reserved_length(&self, additional: usize) -> Option<usize> {
+        let m = self.n.saturating_add(additional);
+        (m > self.a.len()).then(|| std::cmp::max(m, self.policy.grow(self.n)))
+    }
+
+    // Gives up every unused slot; the next `add` grows the array again.
+    pub fn shrink_to_fit(&mut self) {
+        if self.a.len() > self.n {
+            self.move_into(crate::util::allocate(self.n));
+        }
+    }
+
+    // Drops the elements but keeps the array.
+    pub fn clear(&mut self) {
+        let n = std::mem::take(&mut self.n);
+        self.a[..n].fill_with(|| None);
+    }
+
     fn resize(&mut self) {
-        let b = crate::util::allocate(std::cmp::max(2 * self.n, 1));
+        self.move_into(crate::util::allocate(self.policy.grow(self.n)));
+    }
+
+    fn shrink(&mut self) {
+        if let Some(m) = self.policy.shrink(self.n, self.a.len()) {
+            self.move_into(crate::util::allocate(m));
+        }
+    }
+
+    fn move_into(&mut self, b: Box<[Option<T>]>) {
         let old_a = std::mem::replace(&mut self.a, b);
         for (i, v) in old_a.into_iter().enumerate().take(self.n) {
             self.a[i] = v;
@@ -71,6 +238,232 @@ impl<T> ArrayStack<T> {
     }
 }
 
+pub struct Iter<'a, T> {
+    inner: std::slice::Iter<'a, Option<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|x| x.as_ref().unwrap())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|x| x.as_ref().unwrap())
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    inner: std::slice::IterMut<'a, Option<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|x| x.as_mut().unwrap())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|x| x.as_mut().unwrap())
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+pub struct IntoIter<T> {
+    inner: std::vec::IntoIter<Option<T>>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(Option::unwrap)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(Option::unwrap)
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T, P> IntoIterator for ArrayStack<T, P> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let mut inner = Vec::from(self.a);
+        inner.truncate(self.n);
+        IntoIter {
+            inner: inner.into_iter(),
+        }
+    }
+}
+
+impl<'a, T, P: GrowthPolicy> IntoIterator for &'a ArrayStack<T, P> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, P: GrowthPolicy> IntoIterator for &'a mut ArrayStack<T, P> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, P: GrowthPolicy + Default> Default for ArrayStack<T, P> {
+    fn default() -> Self {
+        Self::with_policy(0, P::default())
+    }
+}
+
+impl<T: fmt::Debug, P: GrowthPolicy> fmt::Debug for ArrayStack<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, P: GrowthPolicy> PartialEq for ArrayStack<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, P: GrowthPolicy> Eq for ArrayStack<T, P> {}
+
+impl<T: PartialOrd, P: GrowthPolicy> PartialOrd for ArrayStack<T, P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord, P: GrowthPolicy> Ord for ArrayStack<T, P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash, P: GrowthPolicy> Hash for ArrayStack<T, P> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.n);
+        self.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl<T, P: GrowthPolicy + Default> FromIterator<T> for ArrayStack<T, P> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut c = Self::default();
+        c.extend(iter);
+        c
+    }
+}
+
+impl<T, P: GrowthPolicy> Extend<T> for ArrayStack<T, P> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        ArrayStack::extend(self, iter)
+    }
+}
+
+impl<T, P: GrowthPolicy> Index<usize> for ArrayStack<T, P> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        self.get(i)
+            .unwrap_or_else(|| error::index_out_of_bounds(i, self.n))
+    }
+}
+
+impl<T, P: GrowthPolicy> IndexMut<usize> for ArrayStack<T, P> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        let n = self.n;
+        self.get_mut(i)
+            .unwrap_or_else(|| error::index_out_of_bounds(i, n))
+    }
+}
+
+impl<T, P: GrowthPolicy> crate::List<T> for ArrayStack<T, P> {
+    fn size(&self) -> usize {
+        ArrayStack::size(self)
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        ArrayStack::get(self, i)
+    }
+
+    fn set(&mut self, i: usize, x: T) -> Option<T> {
+        ArrayStack::set(self, i, x)
+    }
+
+    fn add(&mut self, i: usize, x: T) {
+        ArrayStack::add(self, i, x)
+    }
+
+    fn remove(&mut self, i: usize) -> Option<T> {
+        ArrayStack::remove(self, i)
+    }
+}
+
+impl<T, P: GrowthPolicy> crate::Stack<T> for ArrayStack<T, P> {
+    fn size(&self) -> usize {
+        ArrayStack::size(self)
+    }
+
+    fn push(&mut self, x: T) {
+        self.add(self.n, x)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        if self.n == 0 {
+            return None;
+        }
+        self.remove(self.n - 1)
+    }
+}
+
+impl<T, P: GrowthPolicy> crate::sort::Sortable<T> for ArrayStack<T, P> {
+    fn size(&self) -> usize {
+        ArrayStack::size(self)
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        ArrayStack::get(self, i)
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.as_mut_slice().swap(i, j)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -111,4 +504,232 @@ mod tests {
         stack.set(2, 'i');
         check(&stack, "brie", 8);
     }
+
+    fn create(s: &str) -> ArrayStack<char> {
+        let mut stack = ArrayStack::new(s.len() * 2);
+        for (i, c) in s.chars().enumerate() {
+            stack.add(i, c);
+        }
+        stack
+    }
+
+    #[test]
+    fn iter() {
+        let stack = create("abcd");
+
+        let mut iter = stack.iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next(), Some(&'a'));
+        assert_eq!(iter.next_back(), Some(&'d'));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next(), Some(&'b'));
+        assert_eq!(iter.next_back(), Some(&'c'));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        assert_eq!((&stack).into_iter().collect::<String>(), "abcd");
+        assert_eq!(stack.iter().rev().collect::<String>(), "dcba");
+        assert_eq!(ArrayStack::<char>::new(3).iter().next(), None);
+    }
+
+    #[test]
+    fn iter_mut() {
+        let mut stack = create("abcd");
+        for c in stack.iter_mut() {
+            *c = c.to_ascii_uppercase();
+        }
+        check(&stack, "ABCD", 8);
+
+        for c in &mut stack {
+            *c = c.to_ascii_lowercase();
+        }
+        check(&stack, "abcd", 8);
+    }
+
+    #[test]
+    fn into_iter() {
+        let stack = create("abcd");
+        let mut iter = stack.into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next_back(), Some('d'));
+        assert_eq!(iter.collect::<String>(), "abc");
+    }
+
+    #[test]
+    fn try_reserve() {
+        let mut stack = ArrayStack::try_with_capacity(2).unwrap();
+        check(&stack, "", 2);
+        for (i, c) in "abc".chars().enumerate() {
+            stack.try_add(i, c).unwrap();
+        }
+        check(&stack, "abc", 4);
+        stack.try_reserve(1).unwrap();
+        check(&stack, "abc", 4);
+        stack.try_reserve(5).unwrap();
+        check(&stack, "abc", 8);
+
+        assert!(stack.try_reserve(usize::MAX).is_err());
+        assert_eq!(
+            stack.try_add(4, 'x'),
+            Err(Error::IndexOutOfBounds { index: 4, size: 3 })
+        );
+        check(&stack, "abc", 8);
+        assert!(ArrayStack::<char>::try_with_capacity(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let mut stack = create("ab");
+        assert_eq!(stack.get(2), None);
+        assert_eq!(stack.set(2, 'x'), None);
+        assert_eq!(stack.remove(3), None);
+        check(&stack, "ab", 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "the size is 2 but the index is 3")]
+    fn add_out_of_bounds() {
+        create("ab").add(3, 'x');
+    }
+
+    #[test]
+    fn growth_policies() {
+        let mut stack = ArrayStack::with_policy(0, crate::NeverShrink(crate::HalfAgain));
+        let lengths = (0..10)
+            .map(|i| {
+                stack.add(i, i);
+                stack.length()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(lengths, [1, 2, 3, 4, 6, 6, 9, 9, 9, 13]);
+        while stack.remove(0).is_some() {}
+        assert_eq!(stack.length(), 13);
+
+        let mut stack = ArrayStack::with_policy(0, crate::Increment::new(4));
+        for i in 0..10 {
+            stack.add(i, i);
+        }
+        assert_eq!(stack.length(), 12);
+        for _ in 0..5 {
+            stack.remove(0);
+        }
+        assert_eq!(stack.length(), 12);
+        stack.remove(0);
+        assert_eq!(stack.length(), 8);
+        assert!(stack.iter().eq(&[6, 7, 8, 9]));
+    }
+
+    #[test]
+    fn capacity() {
+        let mut stack = ArrayStack::with_capacity(4);
+        stack.reserve(3);
+        assert_eq!(stack.capacity(), 4);
+        for i in 0..3 {
+            stack.add(i, i);
+        }
+        stack.reserve(5);
+        assert_eq!(stack.capacity(), 8);
+        stack.shrink_to_fit();
+        assert_eq!(stack.capacity(), 3);
+        assert!(stack.iter().eq(&[0, 1, 2]));
+
+        stack.clear();
+        assert_eq!(stack.size(), 0);
+        assert_eq!(stack.capacity(), 3);
+        assert_eq!(stack.get(0), None);
+        stack.shrink_to_fit();
+        assert_eq!(stack.capacity(), 0);
+        stack.add(0, 7);
+        assert_eq!(stack.capacity(), 1);
+    }
+
+    #[test]
+    fn clear_drops_elements() {
+        let x = std::rc::Rc::new(());
+        let mut stack = ArrayStack::new(0);
+        for i in 0..5 {
+            stack.add(i, x.clone());
+        }
+        stack.clear();
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+    }
+
+    #[test]
+    fn bulk_operations() {
+        let mut rng = crate::util::Rng::with_seed(21);
+        let mut list = ArrayStack::new(0);
+        let mut expected = Vec::new();
+        for _ in 0..300 {
+            let n = expected.len();
+            let i = rng.next_u64() as usize % (n + 1);
+            let xs = (0..rng.next_u64() % 8).map(|x| x * 10).collect::<Vec<_>>();
+            match rng.next_u64() % 4 {
+                0 => {
+                    list.insert_many(i, xs.clone());
+                    expected.splice(i..i, xs);
+                }
+                1 => {
+                    let j = i + rng.next_u64() as usize % (n - i + 1);
+                    assert!(list.drain(i..j).eq(expected.drain(i..j)));
+                }
+                2 => {
+                    let mut tail = list.split_off(i);
+                    assert!(tail.iter().eq(&expected[i..]));
+                    tail.extend(xs.clone());
+                    list.append(&mut tail);
+                    assert_eq!(tail.size(), 0);
+                    expected.extend(xs);
+                }
+                _ => {
+                    list.extend(xs.clone());
+                    expected.extend(xs);
+                }
+            }
+            assert_eq!(list.size(), expected.len());
+            assert!(list.iter().eq(&expected));
+        }
+    }
+
+    #[test]
+    fn drain_drops_elements() {
+        let x = std::rc::Rc::new(());
+        let mut stack = ArrayStack::new(0);
+        stack.extend(std::iter::repeat_n(x.clone(), 10));
+        let mut drain = stack.drain(2..8);
+        drain.next();
+        drop(drain);
+        assert_eq!(stack.size(), 4);
+        assert_eq!(std::rc::Rc::strong_count(&x), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "range end 4 is out of bounds for size 3")]
+    fn drain_out_of_bounds() {
+        create("abc").drain(1..4);
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a: ArrayStack<char> = "abc".chars().collect();
+        let mut b = ArrayStack::with_capacity(10);
+        b.extend("abc".chars());
+        assert_eq!(a, b);
+        assert_eq!(crate::util::hash_code(&a), crate::util::hash_code(&b));
+        assert_eq!(format!("{a:?}"), "['a', 'b', 'c']");
+
+        let mut c = b.clone();
+        check(&c, "abc", 10);
+        c[0] = 'z';
+        assert_eq!(c[0], 'z');
+        assert!(a < c);
+        assert_eq!(a.partial_cmp(&b), Some(Ordering::Equal));
+        assert!(ArrayStack::<char>::default() < a);
+    }
+
+    #[test]
+    #[should_panic(expected = "the size is 3 but the index is 5")]
+    fn index_out_of_bounds() {
+        let mut stack: ArrayStack<char> = "abc".chars().collect();
+        stack[5] = 'x';
+    }
 }
@@ -0,0 +1,410 @@
+use std::{cmp::Ordering, fmt};
+
+use crate::{Compare, Min};
+
+#[derive(Clone)]
+pub struct BinaryHeap<T, C = Min> {
+    a: Box<[Option<T>]>,
+    n: usize,
+    c: C,
+}
+
+fn left(i: usize) -> usize {
+    2 * i + 1
+}
+
+fn right(i: usize) -> usize {
+    2 * i + 2
+}
+
+fn parent(i: usize) -> usize {
+    (i - 1) / 2
+}
+
+impl<T, C: Compare<T> + Default> Default for BinaryHeap<T, C> {
+    fn default() -> Self {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T: fmt::Debug, C> fmt::Debug for BinaryHeap<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Ord> BinaryHeap<T> {
+    pub fn new() -> Self {
+        Self::with_comparator(Min)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut heap = Self::new();
+        heap.reserve(capacity);
+        heap
+    }
+}
+
+impl<T, C> BinaryHeap<T, C> {
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.a.len()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.a.first()?.as_ref()
+    }
+
+    // Visits the elements in heap order, which is not sorted.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.a[..self.n].iter(),
+        }
+    }
+
+    // Grows the array, if needed, so that `additional` more elements fit
+    // without a resize.
+    pub fn reserve(&mut self, additional: usize) {
+        let m = self.n.saturating_add(additional);
+        if m > self.a.len() {
+            self.reallocate(std::cmp::max(m, 2 * self.n));
+        }
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        if self.a.len() > self.n {
+            self.reallocate(self.n);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        let n = std::mem::take(&mut self.n);
+        self.a[..n].fill_with(|| None);
+    }
+
+    fn resize(&mut self) {
+        self.reallocate(std::cmp::max(2 * self.n, 1));
+    }
+
+    fn reallocate(&mut self, length: usize) {
+        let b = crate::util::allocate(length);
+        let old_a = std::mem::replace(&mut self.a, b);
+        for (i, v) in old_a.into_iter().enumerate().take(self.n) {
+            self.a[i] = v;
+        }
+    }
+}
+
+impl<T, C: Compare<T>> BinaryHeap<T, C> {
+    pub fn with_comparator(c: C) -> Self {
+        Self {
+            a: crate::util::allocate(0),
+            n: 0,
+            c,
+        }
+    }
+
+    fn less(&self, i: usize, j: usize) -> bool {
+        let (x, y) = (self.a[i].as_ref().unwrap(), self.a[j].as_ref().unwrap());
+        self.c.compare(x, y) == Ordering::Less
+    }
+
+    fn bubble_up(&mut self, mut i: usize) {
+        while i > 0 && self.less(i, parent(i)) {
+            self.a.swap(i, parent(i));
+            i = parent(i);
+        }
+    }
+
+    fn trickle_down(&mut self, mut i: usize) {
+        loop {
+            let (l, r) = (left(i), right(i));
+            let j = if r < self.n && self.less(r, i) {
+                if self.less(l, r) { l } else { r }
+            } else if l < self.n && self.less(l, i) {
+                l
+            } else {
+                return;
+            };
+            self.a.swap(i, j);
+            i = j;
+        }
+    }
+
+    pub fn push(&mut self, x: T) {
+        if self.n + 1 > self.a.len() {
+            self.resize();
+        }
+        self.a[self.n] = Some(x);
+        self.n += 1;
+        self.bubble_up(self.n - 1);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.n == 0 {
+            return None;
+        }
+        self.n -= 1;
+        self.a.swap(0, self.n);
+        let x = self.a[self.n].take();
+        self.trickle_down(0);
+        if self.a.len() >= 3 * self.n {
+            self.resize();
+        }
+        x
+    }
+
+    // Returns the elements in the order `pop` would, sorting them in place.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let n = self.n;
+        while self.n > 1 {
+            self.n -= 1;
+            self.a.swap(0, self.n);
+            self.trickle_down(0);
+        }
+        let mut v = Vec::from(self.a);
+        v.truncate(n);
+        v.into_iter().rev().map(Option::unwrap).collect()
+    }
+}
+
+impl<T, C: Compare<T> + Default> FromIterator<T> for BinaryHeap<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut h = Self::with_comparator(C::default());
+        h.extend(iter);
+        h
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for BinaryHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl<T, C: Compare<T>> crate::Queue<T> for BinaryHeap<T, C> {
+    fn size(&self) -> usize {
+        BinaryHeap::size(self)
+    }
+
+    fn add(&mut self, x: T) {
+        self.push(x)
+    }
+
+    fn remove(&mut self) -> Option<T> {
+        self.pop()
+    }
+}
+
+pub struct Iter<'a, T> {
+    inner: std::slice::Iter<'a, Option<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|x| x.as_ref().unwrap())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IntoIter<T> {
+    inner: std::vec::IntoIter<Option<T>>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(Option::unwrap)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T, C> IntoIterator for BinaryHeap<T, C> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let mut inner = Vec::from(self.a);
+        inner.truncate(self.n);
+        IntoIter {
+            inner: inner.into_iter(),
+        }
+    }
+}
+
+impl<'a, T, C> IntoIterator for &'a BinaryHeap<T, C> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Max, util::Rng};
+
+    fn check<T, C: Compare<T>>(heap: &BinaryHeap<T, C>) {
+        assert!(heap.n <= heap.a.len());
+        for i in 1..heap.n {
+            assert!(!heap.less(i, parent(i)));
+        }
+        assert!(heap.a[heap.n..].iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn scenario() {
+        let mut heap = BinaryHeap::new();
+        assert_eq!(heap.peek(), None);
+        assert_eq!(heap.pop(), None);
+
+        for x in [5, 2, 8, 1, 9, 2] {
+            heap.push(x);
+            check(&heap);
+        }
+        assert_eq!(heap.size(), 6);
+        assert_eq!(heap.peek(), Some(&1));
+        assert_eq!(heap.pop(), Some(1));
+        assert_eq!(heap.pop(), Some(2));
+        assert_eq!(heap.pop(), Some(2));
+        check(&heap);
+        assert_eq!(heap.size(), 3);
+        assert_eq!(heap.iter().len(), 3);
+        assert_eq!(format!("{heap:?}"), "[5, 9, 8]");
+    }
+
+    #[test]
+    fn comparators() {
+        let mut heap = BinaryHeap::with_comparator(Max);
+        for x in [3, 1, 4, 1, 5] {
+            heap.push(x);
+        }
+        assert_eq!(heap.peek(), Some(&5));
+        assert_eq!(heap.into_sorted_vec(), [5, 4, 3, 1, 1]);
+
+        let mut heap = BinaryHeap::with_comparator(|a: &&str, b: &&str| a.len().cmp(&b.len()));
+        for s in ["ccc", "a", "bb"] {
+            heap.push(s);
+        }
+        assert_eq!(heap.pop(), Some("a"));
+        assert_eq!(heap.pop(), Some("bb"));
+    }
+
+    #[test]
+    fn random_operations() {
+        let mut rng = Rng::with_seed(1);
+        let mut heap = BinaryHeap::new();
+        let mut expected = std::collections::BinaryHeap::new();
+        for _ in 0..3000 {
+            if rng.next_u64() % 3 < 2 {
+                let x = (rng.next_u64() % 100) as i32;
+                heap.push(x);
+                expected.push(std::cmp::Reverse(x));
+            } else {
+                assert_eq!(heap.pop(), expected.pop().map(|x| x.0));
+            }
+            assert_eq!(heap.peek(), expected.peek().map(|x| &x.0));
+            check(&heap);
+        }
+        while let Some(x) = expected.pop() {
+            assert_eq!(heap.pop(), Some(x.0));
+        }
+        assert_eq!(heap.a.len(), 1);
+    }
+
+    #[test]
+    fn into_sorted_vec() {
+        let mut rng = Rng::with_seed(2);
+        let mut heap = BinaryHeap::new();
+        let mut expected = Vec::new();
+        for _ in 0..500 {
+            let x = rng.next_u64() % 100;
+            heap.push(x);
+            expected.push(x);
+        }
+        expected.sort();
+        assert_eq!(heap.into_sorted_vec(), expected);
+        assert_eq!(BinaryHeap::<i32>::new().into_sorted_vec(), []);
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut heap = BinaryHeap::new();
+        for x in [4, 2, 8, 6] {
+            heap.push(x);
+        }
+        let iter = heap.into_iter();
+        assert_eq!(iter.len(), 4);
+        let mut v = iter.collect::<Vec<_>>();
+        v.sort();
+        assert_eq!(v, [2, 4, 6, 8]);
+    }
+
+    #[test]
+    fn drop_elements() {
+        let x = std::rc::Rc::new(0);
+        let mut heap = BinaryHeap::new();
+        for i in 0..10 {
+            heap.push((i, x.clone()));
+        }
+        drop(heap.pop());
+        assert_eq!(std::rc::Rc::strong_count(&x), 10);
+        drop(heap);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+    }
+
+    #[test]
+    fn capacity() {
+        let mut heap = BinaryHeap::with_capacity(5);
+        assert_eq!(heap.capacity(), 5);
+        for x in [3, 1, 2] {
+            heap.push(x);
+        }
+        heap.shrink_to_fit();
+        assert_eq!(heap.capacity(), 3);
+        heap.reserve(1);
+        assert_eq!(heap.capacity(), 6);
+        assert_eq!(heap.peek(), Some(&1));
+
+        heap.clear();
+        assert_eq!(heap.size(), 0);
+        assert_eq!(heap.peek(), None);
+        assert_eq!(heap.pop(), None);
+        heap.push(4);
+        assert_eq!(heap.pop(), Some(4));
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a: BinaryHeap<i32> = [5, 1, 4, 2, 3].into_iter().collect();
+        let mut b = a.clone();
+        b.extend([0, 6]);
+        assert_eq!(a.size(), 5);
+        assert_eq!(b.peek(), Some(&0));
+        assert_eq!(b.into_sorted_vec(), [0, 1, 2, 3, 4, 5, 6]);
+
+        let c: BinaryHeap<i32, Max> = (1..=5).collect();
+        assert_eq!(c.peek(), Some(&5));
+        assert_eq!(BinaryHeap::<i32, Max>::default().size(), 0);
+    }
+}
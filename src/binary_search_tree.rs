@@ -0,0 +1,363 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use crate::{
+    BinaryTree,
+    binary_tree::{self, Node},
+};
+
+#[derive(Clone)]
+pub struct BinarySearchTree<T> {
+    tree: BinaryTree<T>,
+    n: usize,
+}
+
+impl<T> Default for BinarySearchTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for BinarySearchTree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T> BinarySearchTree<T> {
+    pub fn new() -> Self {
+        Self {
+            tree: BinaryTree::new(),
+            n: 0,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    pub fn clear(&mut self) {
+        self.tree = BinaryTree::new();
+        self.n = 0;
+    }
+
+    pub fn as_tree(&self) -> &BinaryTree<T> {
+        &self.tree
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.tree.iter(),
+            len: self.n,
+        }
+    }
+}
+
+impl<T: Ord> BinarySearchTree<T> {
+    pub fn find(&self, x: &T) -> Option<&T> {
+        self.tree.find(x)
+    }
+
+    pub fn contains(&self, x: &T) -> bool {
+        // SAFETY: the node belongs to the tree, which is borrowed shared.
+        self.tree
+            .find_last(x)
+            .is_some_and(|u| unsafe { Node::x(u) } == x)
+    }
+
+    pub fn depth(&self, x: &T) -> Option<usize> {
+        let u = self.tree.find_last(x)?;
+        // SAFETY: the node belongs to the tree, which is borrowed shared.
+        unsafe { (Node::x(u) == x).then(|| self.tree.depth_at(u)) }
+    }
+
+    pub fn add(&mut self, x: T) -> bool {
+        let p = self.tree.find_last(&x);
+        // SAFETY: `p` belongs to the tree and the new node is linked in below it.
+        unsafe {
+            if p.is_some_and(|p| Node::x(p) == &x) {
+                return false;
+            }
+            self.tree.add_child(p, Node::alloc(x, ()));
+        }
+        self.n += 1;
+        true
+    }
+
+    pub fn remove(&mut self, x: &T) -> Option<T> {
+        let u = self.tree.find_last(x)?;
+        // SAFETY: `u` belongs to the tree, and the node `remove_node` unlinks
+        // is freed exactly once.
+        unsafe {
+            if Node::x(u) != x {
+                return None;
+            }
+            let w = self.tree.remove_node(u);
+            self.n -= 1;
+            Some(Node::into_x(w))
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for BinarySearchTree<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for BinarySearchTree<T> {}
+
+impl<T: PartialOrd> PartialOrd for BinarySearchTree<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for BinarySearchTree<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for BinarySearchTree<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.size());
+        self.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl<T: Ord> FromIterator<T> for BinarySearchTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut c = Self::new();
+        c.extend(iter);
+        c
+    }
+}
+
+impl<T: Ord> Extend<T> for BinarySearchTree<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.add(x);
+        }
+    }
+}
+
+impl<T: Ord> crate::SSet<T> for BinarySearchTree<T> {
+    fn size(&self) -> usize {
+        BinarySearchTree::size(self)
+    }
+
+    fn add(&mut self, x: T) -> bool {
+        BinarySearchTree::add(self, x)
+    }
+
+    fn remove(&mut self, x: &T) -> Option<T> {
+        BinarySearchTree::remove(self, x)
+    }
+
+    fn find(&self, x: &T) -> Option<&T> {
+        BinarySearchTree::find(self, x)
+    }
+}
+
+pub struct Iter<'a, T> {
+    inner: binary_tree::Iter<'a, T>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.inner.next()?;
+        self.len -= 1;
+        Some(x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IntoIter<T> {
+    inner: binary_tree::IntoIter<T>,
+    len: usize,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.inner.next()?;
+        self.len -= 1;
+        Some(x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for BinarySearchTree<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.tree.into_iter(),
+            len: self.n,
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a BinarySearchTree<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::Rng;
+    use std::collections::BTreeSet;
+
+    fn check(set: &BinarySearchTree<i32>, expected: &BTreeSet<i32>) {
+        assert_eq!(set.size(), expected.len());
+        assert_eq!(set.tree.size(), expected.len());
+        assert!(set.iter().eq(expected.iter()));
+        binary_tree::check_links(&set.tree);
+    }
+
+    #[test]
+    fn scenario() {
+        let mut set = BinarySearchTree::new();
+        assert_eq!(set.find(&0), None);
+
+        for x in [5, 2, 8, 1, 3, 7, 9] {
+            assert!(set.add(x));
+        }
+        assert!(!set.add(3));
+        assert_eq!(set.size(), 7);
+        assert_eq!(set.as_tree().height(), Some(2));
+
+        assert_eq!(set.find(&0), Some(&1));
+        assert_eq!(set.find(&4), Some(&5));
+        assert_eq!(set.find(&6), Some(&7));
+        assert_eq!(set.find(&10), None);
+        assert!(set.contains(&8));
+        assert!(!set.contains(&4));
+        assert_eq!(set.depth(&5), Some(0));
+        assert_eq!(set.depth(&3), Some(2));
+        assert_eq!(set.depth(&4), None);
+
+        assert_eq!(set.remove(&5), Some(5));
+        assert_eq!(set.remove(&5), None);
+        assert_eq!(set.depth(&7), Some(0));
+        assert_eq!(set.remove(&1), Some(1));
+        assert_eq!(set.remove(&8), Some(8));
+        check(&set, &BTreeSet::from([2, 3, 7, 9]));
+        assert_eq!(format!("{set:?}"), "{2, 3, 7, 9}");
+    }
+
+    #[test]
+    fn random_operations() {
+        let mut rng = Rng::with_seed(1);
+        let mut set = BinarySearchTree::new();
+        let mut expected = BTreeSet::new();
+        for _ in 0..3000 {
+            let x = (rng.next_u64() % 300) as i32;
+            match rng.next_u64() % 3 {
+                0 | 1 => assert_eq!(set.add(x), expected.insert(x)),
+                _ => assert_eq!(set.remove(&x), expected.take(&x)),
+            }
+            assert_eq!(set.find(&x), expected.range(x..).next());
+        }
+        check(&set, &expected);
+
+        while let Some(&x) = expected.iter().next() {
+            assert_eq!(set.remove(&x), expected.take(&x));
+        }
+        check(&set, &expected);
+        assert!(set.as_tree().is_empty());
+    }
+
+    #[test]
+    fn sorted_input_degenerates() {
+        let mut set = BinarySearchTree::new();
+        for x in 0..1000 {
+            set.add(x);
+        }
+        assert_eq!(set.as_tree().height(), Some(999));
+        assert_eq!(set.depth(&999), Some(999));
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut set = BinarySearchTree::new();
+        for x in [4, 2, 8, 6] {
+            set.add(x);
+        }
+        let mut iter = set.into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.collect::<Vec<_>>(), [4, 6, 8]);
+    }
+
+    #[test]
+    fn drop_elements() {
+        let x = std::rc::Rc::new(0);
+        let mut set = BinarySearchTree::new();
+        for i in 0..10 {
+            set.add((i, x.clone()));
+        }
+        drop(set.remove(&(3, x.clone())));
+        assert_eq!(std::rc::Rc::strong_count(&x), 10);
+
+        let mut iter = set.into_iter();
+        iter.next();
+        drop(iter);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+    }
+
+    #[test]
+    fn clear() {
+        let x = std::rc::Rc::new(());
+        let mut tree = BinarySearchTree::new();
+        for i in 0..10 {
+            tree.add((i, x.clone()));
+        }
+        tree.clear();
+        assert_eq!(tree.size(), 0);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+        assert!(tree.add((0, x.clone())));
+        assert_eq!(tree.depth(&(0, x.clone())), Some(0));
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a: BinarySearchTree<char> = "dbfaceg".chars().collect();
+        let mut b = BinarySearchTree::new();
+        b.extend("abcdefg".chars());
+        assert_eq!(a, b);
+        assert_eq!(crate::util::hash_code(&a), crate::util::hash_code(&b));
+        assert_eq!(format!("{a:?}"), "{'a', 'b', 'c', 'd', 'e', 'f', 'g'}");
+
+        let mut c = a.clone();
+        assert_eq!(c.as_tree().height(), a.as_tree().height());
+        c.remove(&'a');
+        assert!(a.contains(&'a'));
+        assert!(a < c);
+        assert_eq!(c.cmp(&c.clone()), Ordering::Equal);
+    }
+}
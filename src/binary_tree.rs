@@ -0,0 +1,1062 @@
+use std::{cmp::Ordering, fmt, marker::PhantomData, mem::ManuallyDrop, ptr::NonNull};
+
+use crate::{ArrayQueue, ArrayStack};
+
+pub(crate) type Link<T, X> = Option<NonNull<Node<T, X>>>;
+
+// `X` is the data balanced trees keep in each node next to the element.
+pub struct BinaryTree<T, X = ()> {
+    r: Link<T, X>,
+    marker: PhantomData<Box<Node<T, X>>>,
+}
+
+pub(crate) struct Node<T, X> {
+    pub(crate) x: T,
+    extra: X,
+    left: Link<T, X>,
+    right: Link<T, X>,
+    parent: Link<T, X>,
+}
+
+// SAFETY: `BinaryTree` owns its nodes exclusively, like `Box<Node<T, X>>` would.
+unsafe impl<T: Send, X: Send> Send for BinaryTree<T, X> {}
+unsafe impl<T: Sync, X: Sync> Sync for BinaryTree<T, X> {}
+
+impl<T, X> Node<T, X> {
+    pub(crate) fn alloc(x: T, extra: X) -> NonNull<Self> {
+        NonNull::from(Box::leak(Box::new(Self {
+            x,
+            extra,
+            left: None,
+            right: None,
+            parent: None,
+        })))
+    }
+
+    // SAFETY: the caller must guarantee that `u` is a live node that is no
+    // longer linked into any tree.
+    pub(crate) unsafe fn into_x(u: NonNull<Self>) -> T {
+        unsafe { Box::from_raw(u.as_ptr()).x }
+    }
+
+    // SAFETY: the caller must guarantee that `u` is a live node.
+    pub(crate) unsafe fn left(u: NonNull<Self>) -> Link<T, X> {
+        unsafe { (*u.as_ptr()).left }
+    }
+
+    // SAFETY: the caller must guarantee that `u` is a live node.
+    pub(crate) unsafe fn right(u: NonNull<Self>) -> Link<T, X> {
+        unsafe { (*u.as_ptr()).right }
+    }
+
+    // SAFETY: the caller must guarantee that `u` is a live node.
+    pub(crate) unsafe fn parent(u: NonNull<Self>) -> Link<T, X> {
+        unsafe { (*u.as_ptr()).parent }
+    }
+
+    // SAFETY: the caller must guarantee that `u` is a live node that is not
+    // borrowed elsewhere.
+    pub(crate) unsafe fn set_left(u: NonNull<Self>, v: Link<T, X>) {
+        unsafe { (*u.as_ptr()).left = v }
+    }
+
+    // SAFETY: the caller must guarantee that `u` is a live node that is not
+    // borrowed elsewhere.
+    pub(crate) unsafe fn set_right(u: NonNull<Self>, v: Link<T, X>) {
+        unsafe { (*u.as_ptr()).right = v }
+    }
+
+    // SAFETY: the caller must guarantee that `u` is a live node that is not
+    // borrowed elsewhere.
+    pub(crate) unsafe fn set_parent(u: NonNull<Self>, v: Link<T, X>) {
+        unsafe { (*u.as_ptr()).parent = v }
+    }
+
+    // SAFETY: the caller must guarantee that `u` outlives `'a` and is not
+    // mutated during it.
+    pub(crate) unsafe fn x<'a>(u: NonNull<Self>) -> &'a T {
+        unsafe { &(*u.as_ptr()).x }
+    }
+
+    // SAFETY: the caller must guarantee that `u` outlives `'a` and is not
+    // borrowed elsewhere during it.
+    pub(crate) unsafe fn x_mut<'a>(u: NonNull<Self>) -> &'a mut T {
+        unsafe { &mut (*u.as_ptr()).x }
+    }
+
+    // SAFETY: the caller must guarantee that `u` outlives `'a` and is not
+    // mutated during it.
+    pub(crate) unsafe fn extra<'a>(u: NonNull<Self>) -> &'a X {
+        unsafe { &(*u.as_ptr()).extra }
+    }
+
+    // SAFETY: the caller must guarantee that `u` outlives `'a` and is not
+    // borrowed elsewhere during it.
+    pub(crate) unsafe fn extra_mut<'a>(u: NonNull<Self>) -> &'a mut X {
+        unsafe { &mut (*u.as_ptr()).extra }
+    }
+}
+
+// The recursive definitions. They use a stack frame per level, so a deep
+// enough tree overflows the call stack; the tests also check the walks below
+// against them.
+// SAFETY (for the recursive helpers below): `u` must be the root of a live
+// subtree that is not mutated during the call.
+unsafe fn preorder<T, X>(u: Link<T, X>, f: &mut impl FnMut(&T)) {
+    if let Some(u) = u {
+        unsafe {
+            f(Node::x(u));
+            preorder(Node::left(u), f);
+            preorder(Node::right(u), f);
+        }
+    }
+}
+
+unsafe fn inorder<T, X>(u: Link<T, X>, f: &mut impl FnMut(&T)) {
+    if let Some(u) = u {
+        unsafe {
+            inorder(Node::left(u), f);
+            f(Node::x(u));
+            inorder(Node::right(u), f);
+        }
+    }
+}
+
+unsafe fn postorder<T, X>(u: Link<T, X>, f: &mut impl FnMut(&T)) {
+    if let Some(u) = u {
+        unsafe {
+            postorder(Node::left(u), f);
+            postorder(Node::right(u), f);
+            f(Node::x(u));
+        }
+    }
+}
+
+#[cfg(test)]
+unsafe fn size<T, X>(u: Link<T, X>) -> usize {
+    match u {
+        None => 0,
+        Some(u) => unsafe { 1 + size(Node::left(u)) + size(Node::right(u)) },
+    }
+}
+
+#[cfg(test)]
+unsafe fn height<T, X>(u: Link<T, X>) -> Option<usize> {
+    let u = u?;
+    unsafe {
+        let l = height(Node::left(u)).map_or(0, |h| h + 1);
+        let r = height(Node::right(u)).map_or(0, |h| h + 1);
+        Some(l.max(r))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Visit {
+    Pre,
+    In,
+    Post,
+}
+
+// Walks the subtree at `r` by following parent pointers, so it needs neither
+// recursion nor a stack. `f` is called three times for each node: on arrival
+// from its parent, after its left subtree and after its right subtree.
+// SAFETY (for the walks below): `r` must be the root of a live subtree that
+// is not mutated during the call.
+unsafe fn traverse2<T, X>(r: Link<T, X>, mut f: impl FnMut(NonNull<Node<T, X>>, Visit)) {
+    let Some(r) = r else {
+        return;
+    };
+    unsafe {
+        let mut u = r;
+        let mut prev = Node::parent(r);
+        loop {
+            let (left, right, parent) = (Node::left(u), Node::right(u), Node::parent(u));
+            let next = if prev == parent {
+                f(u, Visit::Pre);
+                if left.is_some() {
+                    left
+                } else {
+                    f(u, Visit::In);
+                    if right.is_some() {
+                        right
+                    } else {
+                        f(u, Visit::Post);
+                        parent
+                    }
+                }
+            } else if prev == left {
+                f(u, Visit::In);
+                if right.is_some() {
+                    right
+                } else {
+                    f(u, Visit::Post);
+                    parent
+                }
+            } else {
+                f(u, Visit::Post);
+                parent
+            };
+            if u == r && next == parent {
+                return;
+            }
+            prev = Some(u);
+            u = next.unwrap();
+        }
+    }
+}
+
+unsafe fn size2<T, X>(r: Link<T, X>) -> usize {
+    let mut n = 0;
+    unsafe { traverse2(r, |_, v| n += (v == Visit::Pre) as usize) };
+    n
+}
+
+// Depth-first with an explicit stack of (node, depth) pairs.
+unsafe fn height2<T, X>(r: Link<T, X>) -> Option<usize> {
+    let mut s = ArrayStack::new(0);
+    s.add(0, (r?, 0));
+    let mut h = 0;
+    unsafe {
+        while s.size() > 0 {
+            let (u, d) = s.remove(s.size() - 1).unwrap();
+            h = h.max(d);
+            for w in [Node::left(u), Node::right(u)].into_iter().flatten() {
+                s.add(s.size(), (w, d + 1));
+            }
+        }
+    }
+    Some(h)
+}
+
+// SAFETY: the caller must guarantee that `u` is a live node.
+pub(crate) unsafe fn leftmost<T, X>(mut u: NonNull<Node<T, X>>) -> NonNull<Node<T, X>> {
+    unsafe {
+        while let Some(w) = Node::left(u) {
+            u = w;
+        }
+    }
+    u
+}
+
+// The in-order successor, found by following parent pointers instead of
+// keeping a stack.
+// SAFETY: the caller must guarantee that `u` is a live node.
+pub(crate) unsafe fn successor<T, X>(mut u: NonNull<Node<T, X>>) -> Link<T, X> {
+    unsafe {
+        if let Some(w) = Node::right(u) {
+            return Some(leftmost(w));
+        }
+        while let Some(p) = Node::parent(u) {
+            if Node::left(p) == Some(u) {
+                return Some(p);
+            }
+            u = p;
+        }
+    }
+    None
+}
+
+// Links the nodes `a[i..i + ns]`, which are in sorted order, into a perfectly
+// balanced subtree and returns its root.
+// SAFETY: the caller must guarantee that the nodes are live and that their
+// old links may be overwritten.
+unsafe fn build_balanced<T, X>(
+    a: &ArrayStack<NonNull<Node<T, X>>>,
+    i: usize,
+    ns: usize,
+) -> Link<T, X> {
+    if ns == 0 {
+        return None;
+    }
+    let m = ns / 2;
+    let u = *a.get(i + m).unwrap();
+    unsafe {
+        let l = build_balanced(a, i, m);
+        let r = build_balanced(a, i + m + 1, ns - m - 1);
+        Node::set_left(u, l);
+        Node::set_right(u, r);
+        for w in [l, r].into_iter().flatten() {
+            Node::set_parent(w, Some(u));
+        }
+    }
+    Some(u)
+}
+
+impl<T, X> Default for BinaryTree<T, X> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, X> Drop for BinaryTree<T, X> {
+    fn drop(&mut self) {
+        // Rotates left children up until the root has none and frees it, so
+        // that even a degenerate tree is freed without recursion.
+        // SAFETY: every node is reachable from the root exactly once and was
+        // allocated by `Node::alloc`.
+        unsafe {
+            let mut u = self.r;
+            while let Some(w) = u {
+                if let Some(l) = Node::left(w) {
+                    Node::set_left(w, Node::right(l));
+                    Node::set_right(l, Some(w));
+                    u = Some(l);
+                } else {
+                    u = Node::right(w);
+                    drop(Box::from_raw(w.as_ptr()));
+                }
+            }
+        }
+    }
+}
+
+impl<T: Clone, X: Clone> Clone for BinaryTree<T, X> {
+    fn clone(&self) -> Self {
+        // Copies the shape breadth-first with a queue of (original, copy) pairs.
+        // Each copy is linked in before its element is cloned further down,
+        // so a panicking `clone` leaves a tree that still frees everything.
+        let mut c = Self::new();
+        let Some(r) = self.r else {
+            return c;
+        };
+        // SAFETY: `self` is borrowed shared, and the copies are new nodes
+        // owned by `c` alone.
+        unsafe {
+            let copy =
+                |u: NonNull<Node<T, X>>| Node::alloc(Node::x(u).clone(), Node::extra(u).clone());
+            c.r = Some(copy(r));
+            let mut q = ArrayQueue::new(0);
+            q.add((r, c.r.unwrap()));
+            while let Some((u, v)) = q.remove() {
+                if let Some(l) = Node::left(u) {
+                    let w = copy(l);
+                    Node::set_left(v, Some(w));
+                    Node::set_parent(w, Some(v));
+                    q.add((l, w));
+                }
+                if let Some(r) = Node::right(u) {
+                    let w = copy(r);
+                    Node::set_right(v, Some(w));
+                    Node::set_parent(w, Some(v));
+                    q.add((r, w));
+                }
+            }
+        }
+        c
+    }
+}
+
+impl<T: fmt::Debug, X> fmt::Debug for BinaryTree<T, X> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, X> BinaryTree<T, X> {
+    pub fn new() -> Self {
+        Self {
+            r: None,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn node(left: Self, x: T, right: Self) -> Self
+    where
+        X: Default,
+    {
+        let u = Node::alloc(x, X::default());
+        let (left, right) = (ManuallyDrop::new(left), ManuallyDrop::new(right));
+        // SAFETY: `u` is new, and the subtrees move into it whole.
+        unsafe {
+            Node::set_left(u, left.r);
+            Node::set_right(u, right.r);
+            for w in [left.r, right.r].into_iter().flatten() {
+                Node::set_parent(w, Some(u));
+            }
+        }
+        Self {
+            r: Some(u),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.r.is_none()
+    }
+
+    pub fn size(&self) -> usize {
+        // SAFETY: the tree is borrowed shared.
+        unsafe { size2(self.r) }
+    }
+
+    // The number of edges on the longest root-to-leaf path, or `None` for the
+    // empty tree.
+    pub fn height(&self) -> Option<usize> {
+        // SAFETY: the tree is borrowed shared.
+        unsafe { height2(self.r) }
+    }
+
+    pub fn traverse_preorder(&self, mut f: impl FnMut(&T)) {
+        // SAFETY: the tree is borrowed shared.
+        unsafe {
+            traverse2(self.r, |u, v| {
+                if v == Visit::Pre {
+                    f(Node::x(u))
+                }
+            })
+        }
+    }
+
+    pub fn traverse_inorder(&self, mut f: impl FnMut(&T)) {
+        // SAFETY: the tree is borrowed shared.
+        unsafe {
+            traverse2(self.r, |u, v| {
+                if v == Visit::In {
+                    f(Node::x(u))
+                }
+            })
+        }
+    }
+
+    pub fn traverse_postorder(&self, mut f: impl FnMut(&T)) {
+        // SAFETY: the tree is borrowed shared.
+        unsafe {
+            traverse2(self.r, |u, v| {
+                if v == Visit::Post {
+                    f(Node::x(u))
+                }
+            })
+        }
+    }
+
+    // The recursive traversals visit the same elements in the same order as
+    // the walks above, but recurse once per level.
+    pub fn traverse_preorder_recursive(&self, mut f: impl FnMut(&T)) {
+        // SAFETY: the tree is borrowed shared.
+        unsafe { preorder(self.r, &mut f) }
+    }
+
+    pub fn traverse_inorder_recursive(&self, mut f: impl FnMut(&T)) {
+        // SAFETY: the tree is borrowed shared.
+        unsafe { inorder(self.r, &mut f) }
+    }
+
+    pub fn traverse_postorder_recursive(&self, mut f: impl FnMut(&T)) {
+        // SAFETY: the tree is borrowed shared.
+        unsafe { postorder(self.r, &mut f) }
+    }
+
+    pub fn traverse_breadth_first(&self, mut f: impl FnMut(&T)) {
+        let mut q = ArrayQueue::new(0);
+        if let Some(r) = self.r {
+            q.add(r);
+        }
+        // SAFETY: the tree is borrowed shared.
+        unsafe {
+            while q.size() > 0 {
+                let u = q.remove().unwrap();
+                f(Node::x(u));
+                if let Some(l) = Node::left(u) {
+                    q.add(l);
+                }
+                if let Some(r) = Node::right(u) {
+                    q.add(r);
+                }
+            }
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T, X> {
+        Iter {
+            next: self.first_node(),
+            marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn root(&self) -> Link<T, X> {
+        self.r
+    }
+
+    // Replaces the root without freeing the old one, which the caller must
+    // now own.
+    // SAFETY: the caller must guarantee that `r` is the root of a subtree no
+    // other tree owns.
+    pub(crate) unsafe fn set_root(&mut self, r: Link<T, X>) {
+        self.r = r;
+        if let Some(r) = r {
+            unsafe { Node::set_parent(r, None) };
+        }
+    }
+
+    pub(crate) fn first_node(&self) -> Link<T, X> {
+        // SAFETY: the tree is borrowed shared.
+        self.r.map(|r| unsafe { leftmost(r) })
+    }
+
+    pub(crate) fn last_node(&self) -> Link<T, X> {
+        let mut u = self.r?;
+        // SAFETY: the tree is borrowed shared.
+        unsafe {
+            while let Some(w) = Node::right(u) {
+                u = w;
+            }
+        }
+        Some(u)
+    }
+
+    // SAFETY: the caller must guarantee that `u` belongs to this tree and has
+    // a right child.
+    pub(crate) unsafe fn rotate_left(&mut self, u: NonNull<Node<T, X>>) {
+        unsafe {
+            let w = Node::right(u).unwrap();
+            self.replace_child(u, w);
+            Node::set_right(u, Node::left(w));
+            if let Some(c) = Node::right(u) {
+                Node::set_parent(c, Some(u));
+            }
+            Node::set_parent(u, Some(w));
+            Node::set_left(w, Some(u));
+        }
+    }
+
+    // SAFETY: the caller must guarantee that `u` belongs to this tree and has
+    // a left child.
+    pub(crate) unsafe fn rotate_right(&mut self, u: NonNull<Node<T, X>>) {
+        unsafe {
+            let w = Node::left(u).unwrap();
+            self.replace_child(u, w);
+            Node::set_left(u, Node::right(w));
+            if let Some(c) = Node::left(u) {
+                Node::set_parent(c, Some(u));
+            }
+            Node::set_parent(u, Some(w));
+            Node::set_right(w, Some(u));
+        }
+    }
+
+    // Puts `w` where `u` hangs from its parent, or at the root.
+    // SAFETY: the caller must guarantee that both nodes belong to this tree.
+    unsafe fn replace_child(&mut self, u: NonNull<Node<T, X>>, w: NonNull<Node<T, X>>) {
+        unsafe {
+            let p = Node::parent(u);
+            Node::set_parent(w, p);
+            match p {
+                None => self.r = Some(w),
+                Some(p) if Node::left(p) == Some(u) => Node::set_left(p, Some(w)),
+                Some(p) => Node::set_right(p, Some(w)),
+            }
+        }
+    }
+
+    // SAFETY: the caller must guarantee that `u` belongs to this tree.
+    pub(crate) unsafe fn size_at(&self, u: NonNull<Node<T, X>>) -> usize {
+        unsafe { size2(Some(u)) }
+    }
+
+    // Replaces the subtree at `u` with a perfectly balanced one made of the
+    // same nodes, and returns its size.
+    // SAFETY: the caller must guarantee that `u` belongs to this tree.
+    pub(crate) unsafe fn rebuild(&mut self, u: NonNull<Node<T, X>>) -> usize {
+        unsafe {
+            let ns = size2(Some(u));
+            let mut a = ArrayStack::new(ns);
+            let mut w = Some(leftmost(u));
+            for i in 0..ns {
+                let v = w.unwrap();
+                w = successor(v);
+                a.add(i, v);
+            }
+            let p = Node::parent(u);
+            let b = build_balanced(&a, 0, ns);
+            match p {
+                None => self.r = b,
+                Some(p) if Node::left(p) == Some(u) => Node::set_left(p, b),
+                Some(p) => Node::set_right(p, b),
+            }
+            Node::set_parent(b.unwrap(), p);
+            ns
+        }
+    }
+
+    // The depth of the first node in pre-order holding `x`, counted in edges
+    // from the root.
+    pub fn depth(&self, x: &T) -> Option<usize>
+    where
+        T: PartialEq,
+    {
+        let mut found = None;
+        // SAFETY: the tree is borrowed shared.
+        unsafe {
+            traverse2(self.r, |u, v| {
+                if v == Visit::Pre && found.is_none() && Node::x(u) == x {
+                    found = Some(u);
+                }
+            });
+            found.map(|u| self.depth_at(u))
+        }
+    }
+
+    // SAFETY: the caller must guarantee that `u` belongs to this tree.
+    pub(crate) unsafe fn depth_at(&self, mut u: NonNull<Node<T, X>>) -> usize {
+        let mut d = 0;
+        unsafe {
+            while let Some(p) = Node::parent(u) {
+                u = p;
+                d += 1;
+            }
+        }
+        d
+    }
+
+    // Unlinks `u`, which must have at most one child, and puts that child in
+    // its place.
+    // SAFETY: the caller must guarantee that `u` belongs to this tree.
+    pub(crate) unsafe fn splice(&mut self, u: NonNull<Node<T, X>>) {
+        unsafe {
+            let s = Node::left(u).or(Node::right(u));
+            let p = Node::parent(u);
+            match p {
+                None => self.r = s,
+                Some(p) if Node::left(p) == Some(u) => Node::set_left(p, s),
+                Some(p) => Node::set_right(p, s),
+            }
+            if let Some(s) = s {
+                Node::set_parent(s, p);
+            }
+            Node::set_parent(u, None);
+            Node::set_left(u, None);
+            Node::set_right(u, None);
+        }
+    }
+
+    // Unlinks the node holding `u`'s element, moving the in-order successor's
+    // element into `u` when `u` has two children, and returns the unlinked
+    // node.
+    // SAFETY: the caller must guarantee that `u` belongs to this tree.
+    pub(crate) unsafe fn remove_node(&mut self, u: NonNull<Node<T, X>>) -> NonNull<Node<T, X>> {
+        unsafe {
+            match Node::right(u) {
+                Some(r) if Node::left(u).is_some() => {
+                    let w = leftmost(r);
+                    std::mem::swap(&mut (*u.as_ptr()).x, &mut (*w.as_ptr()).x);
+                    self.splice(w);
+                    w
+                }
+                _ => {
+                    self.splice(u);
+                    u
+                }
+            }
+        }
+    }
+}
+
+impl<T: Ord, X> BinaryTree<T, X> {
+    // The node holding `x`, or else the node that would become its parent.
+    pub(crate) fn find_last(&self, x: &T) -> Link<T, X> {
+        let mut w = self.r;
+        let mut prev = None;
+        // SAFETY: the tree is borrowed shared.
+        unsafe {
+            while let Some(u) = w {
+                prev = w;
+                match x.cmp(Node::x(u)) {
+                    Ordering::Less => w = Node::left(u),
+                    Ordering::Greater => w = Node::right(u),
+                    Ordering::Equal => return w,
+                }
+            }
+        }
+        prev
+    }
+
+    // The smallest element that is not less than `x`.
+    pub(crate) fn find(&self, x: &T) -> Option<&T> {
+        let mut w = self.r;
+        let mut z = None;
+        // SAFETY: the tree is borrowed shared.
+        unsafe {
+            while let Some(u) = w {
+                match x.cmp(Node::x(u)) {
+                    Ordering::Less => {
+                        z = w;
+                        w = Node::left(u);
+                    }
+                    Ordering::Greater => w = Node::right(u),
+                    Ordering::Equal => return Some(Node::x(u)),
+                }
+            }
+            z.map(|z| Node::x(z))
+        }
+    }
+
+    // Links `u` in as a child of `p`, which must come from `find_last` for an
+    // element not in the tree.
+    // SAFETY: the caller must guarantee that `p` belongs to this tree and `u`
+    // is a new node.
+    pub(crate) unsafe fn add_child(&mut self, p: Link<T, X>, u: NonNull<Node<T, X>>) {
+        unsafe {
+            match p {
+                None => self.r = Some(u),
+                Some(p) if Node::x(u) < Node::x(p) => Node::set_left(p, Some(u)),
+                Some(p) => Node::set_right(p, Some(u)),
+            }
+            Node::set_parent(u, p);
+        }
+    }
+}
+
+pub struct Iter<'a, T, X = ()> {
+    next: Link<T, X>,
+    marker: PhantomData<&'a Node<T, X>>,
+}
+
+impl<'a, T, X> Iterator for Iter<'a, T, X> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let u = self.next?;
+        // SAFETY: the tree keeps its nodes alive and unmodified for `'a`.
+        unsafe {
+            self.next = successor(u);
+            Some(Node::x(u))
+        }
+    }
+}
+
+pub struct IntoIter<T, X = ()> {
+    tree: BinaryTree<T, X>,
+    next: Link<T, X>,
+}
+
+impl<T, X> Iterator for IntoIter<T, X> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let u = self.next?;
+        // SAFETY: `u` is the leftmost node, so it has no left child and can be
+        // spliced out without disturbing its successor.
+        unsafe {
+            self.next = successor(u);
+            self.tree.splice(u);
+            Some(Node::into_x(u))
+        }
+    }
+}
+
+impl<T, X> IntoIterator for BinaryTree<T, X> {
+    type Item = T;
+    type IntoIter = IntoIter<T, X>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            // SAFETY: the tree is owned.
+            next: self.r.map(|r| unsafe { leftmost(r) }),
+            tree: self,
+        }
+    }
+}
+
+impl<'a, T, X> IntoIterator for &'a BinaryTree<T, X> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, X>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn check_links<T, X>(tree: &BinaryTree<T, X>) {
+    // SAFETY: test-only walk over live nodes.
+    unsafe fn check<T, X>(u: NonNull<Node<T, X>>) {
+        unsafe {
+            for w in [Node::left(u), Node::right(u)].into_iter().flatten() {
+                assert_eq!(Node::parent(w), Some(u));
+                check(w);
+            }
+        }
+    }
+    if let Some(r) = tree.r {
+        unsafe {
+            assert_eq!(Node::parent(r), None);
+            check(r);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(x: char) -> BinaryTree<char> {
+        BinaryTree::node(BinaryTree::new(), x, BinaryTree::new())
+    }
+
+    //       d
+    //     /   \
+    //    b     e
+    //   / \     \
+    //  a   c     g
+    //           /
+    //          f
+    fn create() -> BinaryTree<char> {
+        let b = BinaryTree::node(leaf('a'), 'b', leaf('c'));
+        let g = BinaryTree::node(leaf('f'), 'g', BinaryTree::new());
+        let e = BinaryTree::node(BinaryTree::new(), 'e', g);
+        BinaryTree::node(b, 'd', e)
+    }
+
+    fn collect(traverse: impl FnOnce(&mut dyn FnMut(&char))) -> String {
+        let mut s = String::new();
+        traverse(&mut |&x| s.push(x));
+        s
+    }
+
+    #[test]
+    fn empty() {
+        let tree = BinaryTree::<char>::new();
+        assert!(tree.is_empty());
+        assert_eq!(tree.size(), 0);
+        assert_eq!(tree.height(), None);
+        assert_eq!(tree.iter().next(), None);
+        assert_eq!(collect(|f| tree.traverse_breadth_first(f)), "");
+    }
+
+    #[test]
+    fn shape() {
+        let tree = create();
+        check_links(&tree);
+        assert!(!tree.is_empty());
+        assert_eq!(tree.size(), 7);
+        assert_eq!(tree.height(), Some(3));
+        assert_eq!(leaf('x').height(), Some(0));
+
+        let f = tree.find_last(&'f').unwrap();
+        // SAFETY: the nodes belong to `tree`.
+        unsafe {
+            assert_eq!(Node::x(f), &'f');
+            assert_eq!(tree.depth_at(f), 3);
+            assert_eq!(tree.depth_at(tree.root().unwrap()), 0);
+        }
+        assert_eq!(tree.depth(&'f'), Some(3));
+        assert_eq!(tree.depth(&'d'), Some(0));
+        assert_eq!(tree.depth(&'z'), None);
+    }
+
+    #[test]
+    fn traversals() {
+        let tree = create();
+        assert_eq!(collect(|f| tree.traverse_preorder(f)), "dbacegf");
+        assert_eq!(collect(|f| tree.traverse_inorder(f)), "abcdefg");
+        assert_eq!(collect(|f| tree.traverse_postorder(f)), "acbfged");
+        assert_eq!(collect(|f| tree.traverse_preorder_recursive(f)), "dbacegf");
+        assert_eq!(collect(|f| tree.traverse_inorder_recursive(f)), "abcdefg");
+        assert_eq!(collect(|f| tree.traverse_postorder_recursive(f)), "acbfged");
+        assert_eq!(collect(|f| tree.traverse_breadth_first(f)), "dbeacgf");
+        assert_eq!(tree.iter().collect::<String>(), "abcdefg");
+        assert_eq!(format!("{tree:?}"), "['a', 'b', 'c', 'd', 'e', 'f', 'g']");
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut iter = create().into_iter();
+        assert_eq!(iter.next(), Some('a'));
+        assert_eq!(iter.next(), Some('b'));
+        assert_eq!(iter.collect::<String>(), "cdefg");
+    }
+
+    #[test]
+    fn find() {
+        let tree = create();
+        assert_eq!(tree.find(&'0'), Some(&'a'));
+        assert_eq!(tree.find(&'c'), Some(&'c'));
+        assert_eq!(tree.find(&'h'), None);
+        // SAFETY: the nodes belong to `tree`.
+        unsafe {
+            assert_eq!(tree.find_last(&'h').map(|u| Node::x(u)), Some(&'g'));
+            assert_eq!(tree.find_last(&'0').map(|u| Node::x(u)), Some(&'a'));
+        }
+    }
+
+    #[test]
+    fn remove_node() {
+        let mut tree = create();
+        // SAFETY: the nodes belong to `tree` and are freed once unlinked.
+        unsafe {
+            let d = tree.root().unwrap();
+            let w = tree.remove_node(d);
+            assert_eq!(Node::into_x(w), 'd');
+            check_links(&tree);
+            assert_eq!(tree.iter().collect::<String>(), "abcefg");
+            assert_eq!(Node::x(tree.root().unwrap()), &'e');
+
+            let g = tree.find_last(&'g').unwrap();
+            assert_eq!(Node::into_x(tree.remove_node(g)), 'g');
+            check_links(&tree);
+            assert_eq!(tree.iter().collect::<String>(), "abcef");
+        }
+    }
+
+    #[test]
+    fn rotations() {
+        let mut tree = create();
+        // SAFETY: the nodes belong to `tree`.
+        unsafe {
+            let d = tree.root().unwrap();
+            tree.rotate_left(d);
+            check_links(&tree);
+            assert_eq!(Node::x(tree.root().unwrap()), &'e');
+            assert_eq!(collect(|f| tree.traverse_preorder(f)), "edbacgf");
+
+            let g = tree.find_last(&'g').unwrap();
+            tree.rotate_right(g);
+            check_links(&tree);
+            assert_eq!(collect(|f| tree.traverse_preorder(f)), "edbacfg");
+
+            let e = tree.root().unwrap();
+            tree.rotate_right(e);
+            tree.rotate_right(tree.root().unwrap());
+            check_links(&tree);
+            assert_eq!(collect(|f| tree.traverse_preorder(f)), "badcefg");
+            assert_eq!(tree.iter().collect::<String>(), "abcdefg");
+        }
+    }
+
+    #[test]
+    fn rebuild() {
+        let mut tree = BinaryTree::<i32>::new();
+        for x in 0..10 {
+            tree = BinaryTree::node(tree, x, BinaryTree::new());
+        }
+        // SAFETY: the nodes belong to `tree`.
+        unsafe {
+            let u = tree.find_last(&5).unwrap();
+            assert_eq!(tree.size_at(u), 6);
+            assert_eq!(tree.rebuild(u), 6);
+            check_links(&tree);
+            assert_eq!(tree.height(), Some(6));
+            assert!(tree.iter().copied().eq(0..10));
+
+            assert_eq!(tree.rebuild(tree.root().unwrap()), 10);
+            check_links(&tree);
+            assert_eq!(tree.height(), Some(3));
+            assert!(tree.iter().copied().eq(0..10));
+            assert_eq!(Node::x(tree.root().unwrap()), &5);
+        }
+    }
+
+    #[test]
+    fn drop_degenerate() {
+        let mut tree = BinaryTree::<u32>::new();
+        for x in 0..100_000 {
+            tree = BinaryTree::node(tree, x, BinaryTree::new());
+        }
+        assert_eq!(tree.iter().count(), 100_000);
+    }
+
+    #[test]
+    fn clone() {
+        let tree = create();
+        let copy = tree.clone();
+        check_links(&copy);
+        assert_eq!(copy.height(), Some(3));
+        assert_eq!(collect(|f| copy.traverse_preorder(f)), "dbacegf");
+        assert_eq!(collect(|f| copy.traverse_breadth_first(f)), "dbeacgf");
+        assert!(BinaryTree::<char>::new().clone().is_empty());
+
+        let mut tree = BinaryTree::<u32>::new();
+        for x in 0..100_000 {
+            tree = BinaryTree::node(tree, x, BinaryTree::new());
+        }
+        assert!(tree.clone().iter().eq(tree.iter()));
+    }
+
+    // A random shape with the values 0..n in order.
+    fn random_tree(rng: &mut crate::util::Rng, lo: u32, hi: u32) -> BinaryTree<u32> {
+        if lo == hi {
+            return BinaryTree::new();
+        }
+        let m = lo + (rng.next_u64() % u64::from(hi - lo)) as u32;
+        BinaryTree::node(random_tree(rng, lo, m), m, random_tree(rng, m + 1, hi))
+    }
+
+    #[test]
+    fn walks_match_recursion() {
+        let mut rng = crate::util::Rng::with_seed(11);
+        for n in 0..60 {
+            let tree = random_tree(&mut rng, 0, n);
+            let (mut pre, mut ino, mut post) = (Vec::new(), Vec::new(), Vec::new());
+            tree.traverse_preorder(|&x| pre.push(x));
+            tree.traverse_inorder(|&x| ino.push(x));
+            tree.traverse_postorder(|&x| post.push(x));
+            assert!(ino.iter().copied().eq(0..n));
+
+            let (mut v, mut w, mut z) = (Vec::new(), Vec::new(), Vec::new());
+            tree.traverse_preorder_recursive(|&x| v.push(x));
+            tree.traverse_inorder_recursive(|&x| w.push(x));
+            tree.traverse_postorder_recursive(|&x| z.push(x));
+            assert_eq!((pre, ino, post), (v, w, z));
+
+            // SAFETY: `tree` is borrowed shared.
+            unsafe {
+                assert_eq!(tree.size(), size(tree.r));
+                assert_eq!(tree.height(), height(tree.r));
+            }
+        }
+    }
+
+    const DEGENERATE: u32 = 200_000;
+
+    // A path going down to the left and one going down to the right, both
+    // holding 0..DEGENERATE in order.
+    fn degenerate() -> [BinaryTree<u32>; 2] {
+        let mut left = BinaryTree::new();
+        let mut right = BinaryTree::new();
+        for x in 0..DEGENERATE {
+            left = BinaryTree::node(left, x, BinaryTree::new());
+            right = BinaryTree::node(BinaryTree::new(), DEGENERATE - 1 - x, right);
+        }
+        [left, right]
+    }
+
+    #[test]
+    fn traverse_degenerate() {
+        let [left, right] = degenerate();
+        for tree in [&left, &right] {
+            let mut next = 0;
+            tree.traverse_inorder(|&x| {
+                assert_eq!(x, next);
+                next += 1;
+            });
+            assert_eq!(next, DEGENERATE);
+        }
+
+        let mut v = Vec::new();
+        left.traverse_preorder(|&x| v.push(x));
+        assert!(v.iter().copied().eq((0..DEGENERATE).rev()));
+        v.clear();
+        left.traverse_postorder(|&x| v.push(x));
+        assert!(v.iter().copied().eq(0..DEGENERATE));
+        v.clear();
+        right.traverse_preorder(|&x| v.push(x));
+        assert!(v.iter().copied().eq(0..DEGENERATE));
+        v.clear();
+        right.traverse_postorder(|&x| v.push(x));
+        assert!(v.iter().copied().eq((0..DEGENERATE).rev()));
+    }
+
+    #[test]
+    fn size_degenerate() {
+        for tree in degenerate() {
+            assert_eq!(tree.size(), DEGENERATE as usize);
+        }
+    }
+
+    #[test]
+    fn height_degenerate() {
+        for tree in degenerate() {
+            assert_eq!(tree.height(), Some(DEGENERATE as usize - 1));
+        }
+    }
+}
@@ -0,0 +1,554 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ptr::NonNull,
+};
+
+use crate::{
+    Unsigned,
+    unsigned::{bit, prefix},
+};
+
+type Link = Option<NonNull<Node>>;
+
+// A node at depth `i` holds the first `i` bits of the keys below it. A node
+// missing a child jumps to the leaf nearest that side: the smallest leaf of
+// its subtree if it has no left child, and the largest if it has no right.
+// Leaves and the dummy form a circular doubly linked list in key order.
+pub(crate) struct Node {
+    x: u64,
+    child: [Link; 2],
+    parent: Link,
+    jump: Link,
+    prev: Link,
+    next: Link,
+}
+
+pub struct BinaryTrie<T> {
+    r: NonNull<Node>,
+    dummy: NonNull<Node>,
+    n: usize,
+    marker: PhantomData<(Box<Node>, T)>,
+}
+
+// SAFETY: `BinaryTrie` owns its nodes exclusively, like `Box<Node>` would.
+unsafe impl<T: Send> Send for BinaryTrie<T> {}
+unsafe impl<T: Sync> Sync for BinaryTrie<T> {}
+
+impl Node {
+    fn alloc(x: u64) -> NonNull<Self> {
+        NonNull::from(Box::leak(Box::new(Self {
+            x,
+            child: [None, None],
+            parent: None,
+            jump: None,
+            prev: None,
+            next: None,
+        })))
+    }
+
+    // SAFETY: the caller must guarantee that `u` is live.
+    pub(crate) unsafe fn parent(u: NonNull<Self>) -> Link {
+        unsafe { (*u.as_ptr()).parent }
+    }
+}
+
+impl<T> Default for BinaryTrie<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for BinaryTrie<T> {
+    fn drop(&mut self) {
+        // SAFETY (for `free`): every node is reachable from the root exactly
+        // once and was allocated by `Node::alloc`. The trie is only as deep as
+        // the key is wide, so the recursion is bounded.
+        unsafe fn free(u: NonNull<Node>) {
+            unsafe {
+                for c in (*u.as_ptr()).child.into_iter().flatten() {
+                    free(c);
+                }
+                drop(Box::from_raw(u.as_ptr()));
+            }
+        }
+        unsafe {
+            free(self.r);
+            drop(Box::from_raw(self.dummy.as_ptr()));
+        }
+    }
+}
+
+impl<T: Unsigned> fmt::Debug for BinaryTrie<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T> BinaryTrie<T> {
+    pub fn new() -> Self {
+        let r = Node::alloc(0);
+        let dummy = Node::alloc(0);
+        // SAFETY: both nodes are new.
+        unsafe {
+            (*dummy.as_ptr()).prev = Some(dummy);
+            (*dummy.as_ptr()).next = Some(dummy);
+            (*r.as_ptr()).jump = Some(dummy);
+        }
+        Self {
+            r,
+            dummy,
+            n: 0,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            // SAFETY: the dummy is live.
+            next: unsafe { (*self.dummy.as_ptr()).next.unwrap() },
+            dummy: self.dummy,
+            len: self.n,
+            marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn root(&self) -> NonNull<Node> {
+        self.r
+    }
+}
+
+impl<T: Unsigned> BinaryTrie<T> {
+    // The deepest node on the path to `x`, and its depth.
+    fn walk(&self, x: u64) -> (NonNull<Node>, u32) {
+        let (mut u, mut i) = (self.r, 0);
+        // SAFETY: the trie is borrowed shared.
+        unsafe {
+            while i < T::BITS {
+                match (*u.as_ptr()).child[bit::<T>(x, i)] {
+                    Some(w) => (u, i) = (w, i + 1),
+                    None => break,
+                }
+            }
+        }
+        (u, i)
+    }
+
+    // The key of the leaf holding the smallest key not less than `x`, given
+    // the deepest node `u` on the path to `x` and its depth `i`.
+    // SAFETY: the caller must guarantee that `u` is that node of this trie.
+    pub(crate) unsafe fn successor_from(&self, u: NonNull<Node>, i: u32, x: u64) -> Option<u64> {
+        unsafe {
+            let v = if i == T::BITS {
+                u
+            } else {
+                let j = (*u.as_ptr()).jump.unwrap();
+                if bit::<T>(x, i) == 0 {
+                    j
+                } else {
+                    (*j.as_ptr()).next.unwrap()
+                }
+            };
+            (v != self.dummy).then(|| (*v.as_ptr()).x)
+        }
+    }
+
+    // Adds a leaf for `x` and returns it, or `None` if `x` is already there.
+    pub(crate) fn add_leaf(&mut self, x: u64) -> Option<NonNull<Node>> {
+        let (mut u, mut i) = self.walk(x);
+        if i == T::BITS {
+            return None;
+        }
+        // SAFETY: the trie is borrowed exclusively, and the new nodes are
+        // linked in below `u`.
+        unsafe {
+            let j = (*u.as_ptr()).jump.unwrap();
+            let pred = if bit::<T>(x, i) == 1 {
+                j
+            } else {
+                (*j.as_ptr()).prev.unwrap()
+            };
+            (*u.as_ptr()).jump = None;
+            while i < T::BITS {
+                let v = Node::alloc(prefix::<T>(x, i + 1));
+                (*u.as_ptr()).child[bit::<T>(x, i)] = Some(v);
+                (*v.as_ptr()).parent = Some(u);
+                (u, i) = (v, i + 1);
+            }
+
+            let succ = (*pred.as_ptr()).next.unwrap();
+            (*u.as_ptr()).prev = Some(pred);
+            (*u.as_ptr()).next = Some(succ);
+            (*pred.as_ptr()).next = Some(u);
+            (*succ.as_ptr()).prev = Some(u);
+
+            let mut v = (*u.as_ptr()).parent;
+            while let Some(w) = v {
+                let w = &mut *w.as_ptr();
+                let j = w.jump.map(|j| (*j.as_ptr()).x);
+                if (w.child[0].is_none() && j.is_none_or(|j| j > x))
+                    || (w.child[1].is_none() && j.is_none_or(|j| j < x))
+                {
+                    w.jump = Some(u);
+                }
+                v = w.parent;
+            }
+            self.n += 1;
+            Some(u)
+        }
+    }
+
+    // Removes the leaf for `x` along with the nodes only it needed, and
+    // returns the depth of the deepest node left on its path, or `None` if
+    // `x` is not there.
+    pub(crate) fn remove_leaf(&mut self, x: u64) -> Option<u32> {
+        let (u, mut i) = self.walk(x);
+        if i < T::BITS {
+            return None;
+        }
+        // SAFETY: the trie is borrowed exclusively, and each freed node is
+        // unlinked from its parent first.
+        unsafe {
+            let (pred, succ) = ((*u.as_ptr()).prev.unwrap(), (*u.as_ptr()).next.unwrap());
+            (*pred.as_ptr()).next = Some(succ);
+            (*succ.as_ptr()).prev = Some(pred);
+
+            let mut v = u;
+            loop {
+                i -= 1;
+                let w = (*v.as_ptr()).parent.unwrap();
+                drop(Box::from_raw(v.as_ptr()));
+                let c = bit::<T>(x, i);
+                (*w.as_ptr()).child[c] = None;
+                v = w;
+                if i == 0 || (*w.as_ptr()).child[1 - c].is_some() {
+                    break;
+                }
+            }
+            let depth = i;
+
+            // `v` now misses the child toward `x`, and every ancestor that
+            // jumped to the removed leaf jumps to its neighbour instead.
+            (*v.as_ptr()).jump = Some(if bit::<T>(x, i) == 0 { succ } else { pred });
+            let mut w = (*v.as_ptr()).parent;
+            while let Some(a) = w {
+                i -= 1;
+                let a = &mut *a.as_ptr();
+                if a.jump == Some(u) {
+                    a.jump = Some(if bit::<T>(x, i) == 1 { succ } else { pred });
+                }
+                w = a.parent;
+            }
+            self.n -= 1;
+            Some(depth)
+        }
+    }
+
+    // The smallest element that is not less than `x`.
+    pub fn find(&self, x: T) -> Option<T> {
+        let x = x.to_u64();
+        let (u, i) = self.walk(x);
+        // SAFETY: `u` and `i` come from walking this trie.
+        unsafe { self.successor_from(u, i, x) }.map(T::from_u64)
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        self.walk(x.to_u64()).1 == T::BITS
+    }
+
+    pub fn add(&mut self, x: T) -> bool {
+        self.add_leaf(x.to_u64()).is_some()
+    }
+
+    pub fn remove(&mut self, x: T) -> bool {
+        self.remove_leaf(x.to_u64()).is_some()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn node_count(&self) -> usize {
+        // SAFETY: test-only walk over live nodes.
+        unsafe fn count(u: NonNull<Node>) -> usize {
+            unsafe {
+                let u = &*u.as_ptr();
+                1 + u
+                    .child
+                    .into_iter()
+                    .flatten()
+                    .map(|c| count(c))
+                    .sum::<usize>()
+            }
+        }
+        unsafe { count(self.r) }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn check(&self) {
+        // SAFETY: test-only walk over live nodes. Returns the smallest and
+        // largest leaves below `u`.
+        unsafe fn check<T: Unsigned>(
+            u: NonNull<Node>,
+            i: u32,
+        ) -> Option<(NonNull<Node>, NonNull<Node>)> {
+            unsafe {
+                let node = &*u.as_ptr();
+                if i == T::BITS {
+                    return Some((u, u));
+                }
+                let mut ends = [None, None];
+                for (c, w) in node.child.into_iter().enumerate() {
+                    if let Some(w) = w {
+                        assert_eq!((*w.as_ptr()).parent, Some(u));
+                        assert_eq!((*w.as_ptr()).x, node.x << 1 | c as u64);
+                        ends[c] = check::<T>(w, i + 1);
+                    }
+                }
+                match ends {
+                    [Some(l), Some(r)] => {
+                        assert_eq!(node.jump, None);
+                        Some((l.0, r.1))
+                    }
+                    [None, Some(r)] => {
+                        assert_eq!(node.jump, Some(r.0));
+                        Some(r)
+                    }
+                    [Some(l), None] => {
+                        assert_eq!(node.jump, Some(l.1));
+                        Some(l)
+                    }
+                    [None, None] => None,
+                }
+            }
+        }
+        unsafe {
+            if check::<T>(self.r, 0).is_none() {
+                assert_eq!((*self.r.as_ptr()).jump, Some(self.dummy));
+            }
+            let mut u = self.dummy;
+            let mut keys = Vec::new();
+            loop {
+                let v = (*u.as_ptr()).next.unwrap();
+                assert_eq!((*v.as_ptr()).prev, Some(u));
+                if v == self.dummy {
+                    break;
+                }
+                keys.push((*v.as_ptr()).x);
+                u = v;
+            }
+            assert_eq!(keys.len(), self.n);
+            assert!(keys.is_sorted_by(|a, b| a < b));
+        }
+    }
+}
+
+impl<T: Unsigned> Clone for BinaryTrie<T> {
+    fn clone(&self) -> Self {
+        self.iter().collect()
+    }
+}
+
+impl<T: Unsigned> PartialEq for BinaryTrie<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Unsigned> Eq for BinaryTrie<T> {}
+
+impl<T: Unsigned> PartialOrd for BinaryTrie<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Unsigned> Ord for BinaryTrie<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Unsigned> Hash for BinaryTrie<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.size());
+        self.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl<T: Unsigned> FromIterator<T> for BinaryTrie<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut c = Self::new();
+        c.extend(iter);
+        c
+    }
+}
+
+impl<T: Unsigned> Extend<T> for BinaryTrie<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.add(x);
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    next: NonNull<Node>,
+    dummy: NonNull<Node>,
+    len: usize,
+    marker: PhantomData<&'a (Node, T)>,
+}
+
+impl<T: Unsigned> Iterator for Iter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next == self.dummy {
+            return None;
+        }
+        // SAFETY: the trie keeps its leaves alive and unmodified for `'a`.
+        unsafe {
+            let u = &*self.next.as_ptr();
+            self.next = u.next.unwrap();
+            self.len -= 1;
+            Some(T::from_u64(u.x))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T: Unsigned> ExactSizeIterator for Iter<'_, T> {}
+
+impl<'a, T: Unsigned> IntoIterator for &'a BinaryTrie<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::Rng;
+    use std::collections::BTreeSet;
+
+    fn check<T: Unsigned>(trie: &BinaryTrie<T>, expected: &BTreeSet<T>) {
+        trie.check();
+        assert_eq!(trie.size(), expected.len());
+        assert!(trie.iter().eq(expected.iter().copied()));
+    }
+
+    #[test]
+    fn scenario() {
+        let mut trie = BinaryTrie::<u8>::new();
+        assert_eq!(trie.find(0), None);
+        assert_eq!(trie.node_count(), 1);
+
+        for x in [3, 9, 12, 255, 0] {
+            assert!(trie.add(x));
+        }
+        assert!(!trie.add(9));
+        assert_eq!(trie.size(), 5);
+
+        assert_eq!(trie.find(1), Some(3));
+        assert_eq!(trie.find(9), Some(9));
+        assert_eq!(trie.find(10), Some(12));
+        assert_eq!(trie.find(13), Some(255));
+        assert!(trie.contains(0));
+        assert!(!trie.contains(4));
+
+        assert!(trie.remove(255));
+        assert!(!trie.remove(255));
+        assert_eq!(trie.find(13), None);
+        check(&trie, &BTreeSet::from([0, 3, 9, 12]));
+        assert_eq!(format!("{trie:?}"), "{0, 3, 9, 12}");
+
+        for x in [0, 3, 9, 12] {
+            assert!(trie.remove(x));
+        }
+        check(&trie, &BTreeSet::new());
+        assert_eq!(trie.node_count(), 1);
+    }
+
+    fn random_operations<T: Unsigned>(seed: u64, range: u64) {
+        let mut rng = Rng::with_seed(seed);
+        let mut trie = BinaryTrie::<T>::new();
+        let mut expected = BTreeSet::new();
+        for i in 0..2000 {
+            let x = T::from_u64(rng.next_u64() % range);
+            match rng.next_u64() % 3 {
+                0 | 1 => assert_eq!(trie.add(x), expected.insert(x)),
+                _ => assert_eq!(trie.remove(x), expected.remove(&x)),
+            }
+            let y = T::from_u64(rng.next_u64() % range);
+            assert_eq!(trie.find(y), expected.range(y..).next().copied());
+            if i % 50 == 0 {
+                check(&trie, &expected);
+            }
+        }
+        check(&trie, &expected);
+    }
+
+    #[test]
+    fn random_operations_u8() {
+        random_operations::<u8>(1, 256);
+    }
+
+    #[test]
+    fn random_operations_u32() {
+        random_operations::<u32>(2, 1 << 32);
+    }
+
+    #[test]
+    fn random_operations_u64() {
+        random_operations::<u64>(3, u64::MAX);
+        let mut trie = BinaryTrie::new();
+        assert!(trie.add(u64::MAX));
+        assert!(trie.add(0));
+        assert_eq!(trie.find(1), Some(u64::MAX));
+        assert!(trie.iter().eq([0, u64::MAX]));
+    }
+
+    #[test]
+    fn clear() {
+        let mut trie = BinaryTrie::<u8>::new();
+        for x in [3, 200, 17] {
+            trie.add(x);
+        }
+        trie.clear();
+        assert_eq!(trie.size(), 0);
+        assert_eq!(trie.find(0), None);
+        trie.add(9);
+        trie.check();
+        assert!(trie.iter().eq([9]));
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a: BinaryTrie<u32> = [9, 3, 7, 1].into_iter().collect();
+        let mut b = BinaryTrie::new();
+        b.extend([1, 3, 7, 9]);
+        assert_eq!(a, b);
+        assert_eq!(crate::util::hash_code(&a), crate::util::hash_code(&b));
+        assert_eq!(format!("{a:?}"), "{1, 3, 7, 9}");
+
+        let mut c = a.clone();
+        c.remove(1);
+        c.add(2);
+        assert!(a.contains(1));
+        assert!(c.iter().eq([2, 3, 7, 9]));
+        assert!(a < c);
+    }
+}
@@ -0,0 +1,415 @@
+use std::{
+    cell::Cell,
+    fs::File,
+    io::{self, Read, Seek, SeekFrom, Write},
+    marker::PhantomData,
+    path::Path,
+};
+
+// Storage for fixed-size blocks, addressed by index. Reads hand out a copy
+// of the block, as a read from disk would, and changes only reach the store
+// through `write_block`. Using a block that is not in use panics; failures
+// of the storage itself are returned.
+pub trait BlockStore<B> {
+    fn read_block(&self, i: usize) -> io::Result<B>;
+    fn write_block(&mut self, i: usize, b: B) -> io::Result<()>;
+    fn place_block(&mut self, b: B) -> io::Result<usize>;
+    fn free_block(&mut self, i: usize) -> io::Result<()>;
+    fn io_stats(&self) -> IoStats;
+    fn reset_io_stats(&self);
+
+    // Whether every block of up to `n` elements fits. Only stores with a
+    // fixed block size can refuse.
+    fn fits(&self, _n: usize) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct IoStats {
+    pub reads: u64,
+    pub writes: u64,
+}
+
+fn record(stats: &Cell<IoStats>, reads: u64, writes: u64) {
+    let mut s = stats.get();
+    s.reads += reads;
+    s.writes += writes;
+    stats.set(s);
+}
+
+// The byte encoding of blocks kept in a `FileStore`. `decode` consumes the
+// bytes it reads from the front of `input`, and fails with `InvalidData` if
+// they are not an encoding. `max_len(n)` bounds the length
+// of the encoding of a value with `n` elements; values that are not
+// collections ignore `n`.
+pub trait Codec: Sized {
+    fn encode(&self, out: &mut Vec<u8>);
+    fn decode(input: &mut &[u8]) -> io::Result<Self>;
+    fn max_len(n: usize) -> usize;
+}
+
+pub(crate) fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+macro_rules! impl_codec {
+    ($($t:ty => $u:ty)*) => {$(
+        impl Codec for $t {
+            fn encode(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&(*self as $u).to_le_bytes());
+            }
+
+            fn decode(input: &mut &[u8]) -> io::Result<Self> {
+                let (head, rest) = input
+                    .split_at_checked(size_of::<$u>())
+                    .ok_or_else(|| invalid_data("block is truncated"))?;
+                *input = rest;
+                Ok(<$u>::from_le_bytes(head.try_into().unwrap()) as $t)
+            }
+
+            fn max_len(_: usize) -> usize {
+                size_of::<$u>()
+            }
+        }
+    )*};
+}
+
+impl_codec!(
+    u8 => u8 u16 => u16 u32 => u32 u64 => u64 usize => u64
+    i8 => i8 i16 => i16 i32 => i32 i64 => i64 isize => i64
+);
+
+#[derive(Clone)]
+pub struct MemoryStore<B> {
+    blocks: Vec<Option<B>>,
+    free: Vec<usize>,
+    stats: Cell<IoStats>,
+}
+
+impl<B> Default for MemoryStore<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B> MemoryStore<B> {
+    pub fn new() -> Self {
+        Self {
+            blocks: Vec::new(),
+            free: Vec::new(),
+            stats: Cell::new(IoStats::default()),
+        }
+    }
+
+    // The number of blocks in use.
+    pub fn size(&self) -> usize {
+        self.blocks.len() - self.free.len()
+    }
+}
+
+impl<B: Clone> BlockStore<B> for MemoryStore<B> {
+    fn read_block(&self, i: usize) -> io::Result<B> {
+        record(&self.stats, 1, 0);
+        Ok(self.blocks[i].clone().expect("block is not in use"))
+    }
+
+    fn write_block(&mut self, i: usize, b: B) -> io::Result<()> {
+        record(&self.stats, 0, 1);
+        assert!(self.blocks[i].is_some(), "block is not in use");
+        self.blocks[i] = Some(b);
+        Ok(())
+    }
+
+    fn place_block(&mut self, b: B) -> io::Result<usize> {
+        record(&self.stats, 0, 1);
+        Ok(match self.free.pop() {
+            Some(i) => {
+                self.blocks[i] = Some(b);
+                i
+            }
+            None => {
+                self.blocks.push(Some(b));
+                self.blocks.len() - 1
+            }
+        })
+    }
+
+    fn free_block(&mut self, i: usize) -> io::Result<()> {
+        self.blocks[i].take().expect("block is not in use");
+        self.free.push(i);
+        Ok(())
+    }
+
+    fn io_stats(&self) -> IoStats {
+        self.stats.get()
+    }
+
+    fn reset_io_stats(&self) {
+        self.stats.set(IoStats::default());
+    }
+}
+
+// Keeps block `i` at byte `i * block_size` of a file, as its encoded length
+// followed by the encoding. Writing a block whose encoding does not fit
+// fails with `InvalidInput`, and reading one whose bytes are corrupt fails
+// with `InvalidData`.
+pub struct FileStore<B> {
+    file: File,
+    block_size: usize,
+    in_use: Vec<bool>,
+    free: Vec<usize>,
+    stats: Cell<IoStats>,
+    marker: PhantomData<fn(B) -> B>,
+}
+
+impl<B> FileStore<B> {
+    // Creates the file at `path`, truncating any file already there.
+    pub fn create<P: AsRef<Path>>(path: P, block_size: usize) -> io::Result<Self> {
+        assert!(block_size > 4, "blocks must hold more than their length");
+        let file = File::options()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        Ok(Self {
+            file,
+            block_size,
+            in_use: Vec::new(),
+            free: Vec::new(),
+            stats: Cell::new(IoStats::default()),
+            marker: PhantomData,
+        })
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    // The number of blocks in use.
+    pub fn size(&self) -> usize {
+        self.in_use.len() - self.free.len()
+    }
+
+    fn check(&self, i: usize) {
+        assert!(self.in_use[i], "block is not in use");
+    }
+
+    fn seek(&self, i: usize) -> io::Result<()> {
+        (&self.file).seek(SeekFrom::Start((i * self.block_size) as u64))?;
+        Ok(())
+    }
+}
+
+impl<B: Codec> FileStore<B> {
+    fn write(&self, i: usize, b: &B) -> io::Result<()> {
+        let mut buf = vec![0; 4];
+        b.encode(&mut buf);
+        let len = buf.len() - 4;
+        if buf.len() > self.block_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "block does not fit",
+            ));
+        }
+        buf[..4].copy_from_slice(&(len as u32).to_le_bytes());
+        buf.resize(self.block_size, 0);
+        self.seek(i)?;
+        (&self.file).write_all(&buf)?;
+        record(&self.stats, 0, 1);
+        Ok(())
+    }
+}
+
+impl<B: Codec> BlockStore<B> for FileStore<B> {
+    fn read_block(&self, i: usize) -> io::Result<B> {
+        self.check(i);
+        let mut buf = vec![0; self.block_size];
+        self.seek(i)?;
+        (&self.file).read_exact(&mut buf)?;
+        record(&self.stats, 1, 0);
+        let len = u32::from_le_bytes(buf[..4].try_into().unwrap()) as usize;
+        let mut input = buf
+            .get(4..4 + len)
+            .ok_or_else(|| invalid_data("block length is corrupt"))?;
+        B::decode(&mut input)
+    }
+
+    fn write_block(&mut self, i: usize, b: B) -> io::Result<()> {
+        self.check(i);
+        self.write(i, &b)
+    }
+
+    // A new block only counts as in use once it has been written.
+    fn place_block(&mut self, b: B) -> io::Result<usize> {
+        let i = self.free.last().copied().unwrap_or(self.in_use.len());
+        self.write(i, &b)?;
+        match self.free.pop() {
+            Some(i) => self.in_use[i] = true,
+            None => self.in_use.push(true),
+        }
+        Ok(i)
+    }
+
+    fn free_block(&mut self, i: usize) -> io::Result<()> {
+        self.check(i);
+        self.in_use[i] = false;
+        self.free.push(i);
+        Ok(())
+    }
+
+    fn io_stats(&self) -> IoStats {
+        self.stats.get()
+    }
+
+    fn reset_io_stats(&self) {
+        self.stats.set(IoStats::default());
+    }
+
+    fn fits(&self, n: usize) -> bool {
+        4 + B::max_len(n) <= self.block_size
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("ods-{}-{name}", std::process::id()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exercise<S: BlockStore<u64>>(store: &mut S) -> io::Result<()> {
+        let a = store.place_block(10)?;
+        let b = store.place_block(20)?;
+        assert_ne!(a, b);
+        assert_eq!(store.read_block(a)?, 10);
+        store.write_block(a, 11)?;
+        assert_eq!(store.read_block(a)?, 11);
+        assert_eq!(store.read_block(b)?, 20);
+        store.free_block(a)?;
+        assert_eq!(store.place_block(30)?, a);
+        assert_eq!(store.read_block(a)?, 30);
+        assert_eq!(
+            store.io_stats(),
+            IoStats {
+                reads: 4,
+                writes: 4
+            }
+        );
+        store.reset_io_stats();
+        assert_eq!(store.io_stats(), IoStats::default());
+        Ok(())
+    }
+
+    #[test]
+    fn memory_store() {
+        let mut store = MemoryStore::new();
+        exercise(&mut store).unwrap();
+        assert_eq!(store.size(), 2);
+        assert!(store.fits(usize::MAX));
+    }
+
+    #[test]
+    fn file_store() {
+        let path = temp_path("file_store");
+        let mut store = FileStore::create(&path, 16).unwrap();
+        exercise(&mut store).unwrap();
+        assert!(store.fits(1));
+        assert_eq!(store.size(), 2);
+        assert_eq!(store.block_size(), 16);
+        drop(store);
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 32);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "block is not in use")]
+    fn file_store_double_free() {
+        let path = temp_path("file_store_double_free");
+        let mut store = FileStore::create(&path, 16).unwrap();
+        std::fs::remove_file(path).unwrap();
+        let a = store.place_block(1u64).unwrap();
+        store.free_block(a).unwrap();
+        let _ = store.free_block(a);
+    }
+
+    #[test]
+    #[should_panic(expected = "block is not in use")]
+    fn file_store_read_freed() {
+        let path = temp_path("file_store_read_freed");
+        let mut store = FileStore::create(&path, 16).unwrap();
+        std::fs::remove_file(path).unwrap();
+        let a = store.place_block(1u64).unwrap();
+        store.place_block(2).unwrap();
+        store.free_block(a).unwrap();
+        let _ = store.read_block(a);
+    }
+
+    #[test]
+    fn file_store_overflow() {
+        let path = temp_path("file_store_overflow");
+        let mut store = FileStore::create(&path, 8).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert!(!store.fits(1));
+        let e = store.place_block(0u64).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(store.size(), 0);
+        assert_eq!(store.io_stats(), IoStats::default());
+    }
+
+    #[test]
+    fn file_store_read_only() {
+        let path = temp_path("file_store_read_only");
+        drop(FileStore::<u64>::create(&path, 16).unwrap());
+        let mut store = FileStore::<u64> {
+            file: File::open(&path).unwrap(),
+            block_size: 16,
+            in_use: Vec::new(),
+            free: Vec::new(),
+            stats: Cell::new(IoStats::default()),
+            marker: PhantomData,
+        };
+        std::fs::remove_file(path).unwrap();
+        assert!(store.place_block(1).is_err());
+        assert_eq!(store.size(), 0);
+    }
+
+    #[test]
+    fn codec() {
+        let mut out = Vec::new();
+        (-5i32).encode(&mut out);
+        usize::MAX.encode(&mut out);
+        200u8.encode(&mut out);
+        assert_eq!(out.len(), 13);
+        let mut input = &out[..];
+        assert_eq!(i32::decode(&mut input).unwrap(), -5);
+        assert_eq!(usize::decode(&mut input).unwrap(), usize::MAX);
+        assert_eq!(u8::decode(&mut input).unwrap(), 200);
+        assert!(input.is_empty());
+        let e = u16::decode(&mut &[1][..]).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn file_store_corrupt_length() {
+        let path = temp_path("file_store_corrupt_length");
+        let mut store = FileStore::create(&path, 16).unwrap();
+        let a = store.place_block(7u64).unwrap();
+        let mut file = File::options().write(true).open(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        // A length that runs past the end of the block.
+        file.write_all(&100u32.to_le_bytes()).unwrap();
+        let e = store.read_block(a).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+
+        // A length that is too short for the block's contents.
+        file.seek(SeekFrom::Start(0)).unwrap();
+        file.write_all(&2u32.to_le_bytes()).unwrap();
+        let e = store.read_block(a).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,753 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    io,
+    marker::PhantomData,
+    vec,
+};
+
+use crate::{
+    BlockStore, Codec, MemoryStore,
+    block_store::{IoStats, invalid_data},
+};
+
+// A block of the tree: the keys of a node in increasing order, and the block
+// indices of its children, of which a leaf has none.
+#[derive(Clone)]
+pub struct Node<T> {
+    keys: Vec<T>,
+    children: Vec<usize>,
+}
+
+impl<T> Node<T> {
+    fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+impl<T: Codec> Codec for Node<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (self.keys.len() as u32).encode(out);
+        (self.children.len() as u32).encode(out);
+        self.keys.iter().for_each(|x| x.encode(out));
+        self.children.iter().for_each(|c| c.encode(out));
+    }
+
+    fn decode(input: &mut &[u8]) -> io::Result<Self> {
+        let k = u32::decode(input)? as usize;
+        let c = u32::decode(input)? as usize;
+        if c != 0 && c != k + 1 {
+            return Err(invalid_data("node has the wrong number of children"));
+        }
+        Ok(Self {
+            keys: (0..k)
+                .map(|_| T::decode(input))
+                .collect::<io::Result<_>>()?,
+            children: (0..c)
+                .map(|_| usize::decode(input))
+                .collect::<io::Result<_>>()?,
+        })
+    }
+
+    // A node of `n` keys has at most `n + 1` children.
+    fn max_len(n: usize) -> usize {
+        2 * u32::max_len(0) + n * T::max_len(0) + (n + 1) * usize::max_len(0)
+    }
+}
+
+// The `b` of a tree made by `default` or `collect`.
+const DEFAULT_B: usize = 16;
+
+// Every node but the root has between `b - 1` and `2b - 1` keys, and all
+// leaves are at the same depth.
+#[derive(Clone)]
+pub struct BTree<T, S = MemoryStore<Node<T>>> {
+    b: usize,
+    ri: usize,
+    n: usize,
+    store: S,
+    marker: PhantomData<T>,
+}
+
+enum Added<T> {
+    Duplicate,
+    Done,
+    Split(T, usize),
+}
+
+impl<T: Ord + Clone> Default for BTree<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_B)
+    }
+}
+
+impl<T: Ord + Clone> BTree<T> {
+    pub fn new(b: usize) -> Self {
+        Self::with_store(b, MemoryStore::new()).unwrap()
+    }
+}
+
+// The methods without a `try_` prefix panic if the store fails.
+fn unwrap_io<R>(r: io::Result<R>) -> R {
+    r.unwrap_or_else(|e| panic!("block store failed: {e}"))
+}
+
+impl<T: Ord + fmt::Debug, S: BlockStore<Node<T>>> fmt::Debug for BTree<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T, S> BTree<T, S> {
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+}
+
+impl<T: Ord, S: BlockStore<Node<T>>> BTree<T, S> {
+    // Fails with `InvalidInput` if a full node of `2b - 1` keys does not fit
+    // in a block of `store`.
+    pub fn with_store(b: usize, mut store: S) -> io::Result<Self> {
+        assert!(b >= 2, "b must be at least 2");
+        if !store.fits(2 * b - 1) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "a full node does not fit in a block",
+            ));
+        }
+        let ri = store.place_block(Node {
+            keys: Vec::new(),
+            children: Vec::new(),
+        })?;
+        Ok(Self {
+            b,
+            ri,
+            n: 0,
+            store,
+            marker: PhantomData,
+        })
+    }
+
+    pub fn clear(&mut self) {
+        unwrap_io(self.try_clear());
+    }
+
+    // Frees every block below the root and leaves the root empty, so the
+    // tree keeps its place in the store.
+    pub fn try_clear(&mut self) -> io::Result<()> {
+        let mut stack = self.store.read_block(self.ri)?.children;
+        while let Some(i) = stack.pop() {
+            stack.extend(self.store.read_block(i)?.children);
+            self.store.free_block(i)?;
+        }
+        self.store.write_block(
+            self.ri,
+            Node {
+                keys: Vec::new(),
+                children: Vec::new(),
+            },
+        )?;
+        self.n = 0;
+        Ok(())
+    }
+
+    pub fn io_stats(&self) -> IoStats {
+        self.store.io_stats()
+    }
+
+    pub fn reset_io_stats(&self) {
+        self.store.reset_io_stats();
+    }
+
+    // Visits the elements in order, reading each block once. The iterator
+    // panics if the store fails.
+    pub fn iter(&self) -> Iter<'_, T, S> {
+        let mut iter = Iter {
+            store: &self.store,
+            stack: Vec::new(),
+            len: self.n,
+        };
+        iter.descend(self.ri);
+        iter
+    }
+
+    pub fn find(&self, x: &T) -> Option<T> {
+        unwrap_io(self.try_find(x))
+    }
+
+    // The smallest element that is not less than `x`, reading one block per
+    // level.
+    pub fn try_find(&self, x: &T) -> io::Result<Option<T>> {
+        let (mut ui, mut z) = (self.ri, None);
+        loop {
+            let u = self.store.read_block(ui)?;
+            let i = match u.keys.binary_search(x) {
+                Ok(i) => return Ok(u.keys.into_iter().nth(i)),
+                Err(i) => i,
+            };
+            let next = u.children.get(i).copied();
+            if let Some(y) = u.keys.into_iter().nth(i) {
+                z = Some(y);
+            }
+            match next {
+                Some(c) => ui = c,
+                None => return Ok(z),
+            }
+        }
+    }
+
+    pub fn contains(&self, x: &T) -> bool {
+        self.find(x).is_some_and(|y| y == *x)
+    }
+
+    pub fn add(&mut self, x: T) -> bool {
+        unwrap_io(self.try_add(x))
+    }
+
+    pub fn try_add(&mut self, x: T) -> io::Result<bool> {
+        match self.add_recursive(x, self.ri)? {
+            Added::Duplicate => return Ok(false),
+            Added::Done => {}
+            Added::Split(y, wi) => {
+                self.ri = self.store.place_block(Node {
+                    keys: vec![y],
+                    children: vec![self.ri, wi],
+                })?;
+            }
+        }
+        self.n += 1;
+        Ok(true)
+    }
+
+    // Adds `x` below block `ui`. A node that overflows keeps its smallest `b`
+    // keys, and hands its median and a new block with the rest to its
+    // parent.
+    fn add_recursive(&mut self, x: T, ui: usize) -> io::Result<Added<T>> {
+        let mut u = self.store.read_block(ui)?;
+        let i = match u.keys.binary_search(&x) {
+            Ok(_) => return Ok(Added::Duplicate),
+            Err(i) => i,
+        };
+        if u.is_leaf() {
+            u.keys.insert(i, x);
+        } else {
+            match self.add_recursive(x, u.children[i])? {
+                Added::Split(y, wi) => {
+                    u.keys.insert(i, y);
+                    u.children.insert(i + 1, wi);
+                }
+                r => return Ok(r),
+            }
+        }
+        let r = if u.keys.len() == 2 * self.b {
+            let keys = u.keys.split_off(self.b + 1);
+            let y = u.keys.pop().unwrap();
+            let children = if u.is_leaf() {
+                Vec::new()
+            } else {
+                u.children.split_off(self.b + 1)
+            };
+            Added::Split(y, self.store.place_block(Node { keys, children })?)
+        } else {
+            Added::Done
+        };
+        self.store.write_block(ui, u)?;
+        Ok(r)
+    }
+
+    pub fn remove(&mut self, x: &T) -> Option<T> {
+        unwrap_io(self.try_remove(x))
+    }
+
+    pub fn try_remove(&mut self, x: &T) -> io::Result<Option<T>> {
+        let mut r = self.store.read_block(self.ri)?;
+        let Some(y) = self.remove_from(x, &mut r)? else {
+            return Ok(None);
+        };
+        self.n -= 1;
+        if r.keys.is_empty() && !r.is_leaf() {
+            self.store.free_block(self.ri)?;
+            self.ri = r.children[0];
+        } else {
+            self.store.write_block(self.ri, r)?;
+        }
+        Ok(Some(y))
+    }
+
+    // Removes `x` from the subtree of `u`, whose block the caller writes
+    // back. A key in an internal node is replaced by its successor.
+    fn remove_from(&mut self, x: &T, u: &mut Node<T>) -> io::Result<Option<T>> {
+        match u.keys.binary_search(x) {
+            Ok(i) if u.is_leaf() => Ok(Some(u.keys.remove(i))),
+            Ok(i) => {
+                let mut w = self.store.read_block(u.children[i + 1])?;
+                let y = self.remove_smallest(&mut w)?;
+                let y = std::mem::replace(&mut u.keys[i], y);
+                self.fix_child(u, i + 1, w)?;
+                Ok(Some(y))
+            }
+            Err(_) if u.is_leaf() => Ok(None),
+            Err(i) => {
+                let mut w = self.store.read_block(u.children[i])?;
+                let Some(y) = self.remove_from(x, &mut w)? else {
+                    return Ok(None);
+                };
+                self.fix_child(u, i, w)?;
+                Ok(Some(y))
+            }
+        }
+    }
+
+    fn remove_smallest(&mut self, u: &mut Node<T>) -> io::Result<T> {
+        if u.is_leaf() {
+            return Ok(u.keys.remove(0));
+        }
+        let mut w = self.store.read_block(u.children[0])?;
+        let y = self.remove_smallest(&mut w)?;
+        self.fix_child(u, 0, w)?;
+        Ok(y)
+    }
+
+    // Writes back `w`, the `i`th child of `u`, after a removal below it. If
+    // `w` is down to `b - 2` keys, it borrows a key through `u` from a
+    // sibling that can spare one, or else merges with that sibling.
+    fn fix_child(&mut self, u: &mut Node<T>, i: usize, mut w: Node<T>) -> io::Result<()> {
+        if w.keys.len() + 1 >= self.b {
+            self.store.write_block(u.children[i], w)?;
+        } else if i > 0 {
+            let mut v = self.store.read_block(u.children[i - 1])?;
+            if v.keys.len() >= self.b {
+                let y = std::mem::replace(&mut u.keys[i - 1], v.keys.pop().unwrap());
+                w.keys.insert(0, y);
+                if let Some(c) = v.children.pop() {
+                    w.children.insert(0, c);
+                }
+                self.store.write_block(u.children[i - 1], v)?;
+                self.store.write_block(u.children[i], w)?;
+            } else {
+                v.keys.push(u.keys.remove(i - 1));
+                v.keys.append(&mut w.keys);
+                v.children.append(&mut w.children);
+                self.store.free_block(u.children.remove(i))?;
+                self.store.write_block(u.children[i - 1], v)?;
+            }
+        } else {
+            let mut v = self.store.read_block(u.children[1])?;
+            if v.keys.len() >= self.b {
+                let y = std::mem::replace(&mut u.keys[0], v.keys.remove(0));
+                w.keys.push(y);
+                if !v.is_leaf() {
+                    w.children.push(v.children.remove(0));
+                }
+                self.store.write_block(u.children[0], w)?;
+                self.store.write_block(u.children[1], v)?;
+            } else {
+                w.keys.push(u.keys.remove(0));
+                w.keys.append(&mut v.keys);
+                w.children.append(&mut v.children);
+                self.store.free_block(u.children.remove(1))?;
+                self.store.write_block(u.children[0], w)?;
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(test)]
+    fn check(&self) -> usize {
+        // Returns the height of the subtree and the number of its nodes.
+        fn check<T: Ord, S: BlockStore<Node<T>>>(
+            tree: &BTree<T, S>,
+            ui: usize,
+            root: bool,
+        ) -> (usize, usize) {
+            let u = tree.store.read_block(ui).unwrap();
+            assert!(u.keys.len() < 2 * tree.b);
+            assert!(root || u.keys.len() + 1 >= tree.b);
+            assert!(u.keys.is_sorted_by(|x, y| x < y));
+            if u.is_leaf() {
+                return (0, 1);
+            }
+            assert_eq!(u.children.len(), u.keys.len() + 1);
+            let mut nodes = 1;
+            let mut heights = u.children.iter().map(|&c| {
+                let (h, m) = check(tree, c, false);
+                nodes += m;
+                h
+            });
+            let h = heights.next().unwrap();
+            assert!(heights.all(|g| g == h));
+            (h + 1, nodes)
+        }
+        let (_, nodes) = check(self, self.ri, true);
+        assert_eq!(self.iter().count(), self.n);
+        assert!(self.iter().is_sorted_by(|x, y| x < y));
+        nodes
+    }
+}
+
+impl<T: Ord, S: BlockStore<Node<T>>> PartialEq for BTree<T, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Ord, S: BlockStore<Node<T>>> Eq for BTree<T, S> {}
+
+impl<T: Ord, S: BlockStore<Node<T>>> PartialOrd for BTree<T, S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord, S: BlockStore<Node<T>>> Ord for BTree<T, S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Ord + Hash, S: BlockStore<Node<T>>> Hash for BTree<T, S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.size());
+        self.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<T> for BTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut c = Self::default();
+        c.extend(iter);
+        c
+    }
+}
+
+impl<T: Ord, S: BlockStore<Node<T>>> Extend<T> for BTree<T, S> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.add(x);
+        }
+    }
+}
+
+pub struct Iter<'a, T, S> {
+    store: &'a S,
+    stack: Vec<(vec::IntoIter<T>, vec::IntoIter<usize>)>,
+    len: usize,
+}
+
+impl<T, S: BlockStore<Node<T>>> Iter<'_, T, S> {
+    // Pushes the path from block `ui` down to its leftmost leaf.
+    fn descend(&mut self, mut ui: usize) {
+        loop {
+            let u = unwrap_io(self.store.read_block(ui));
+            let mut children = u.children.into_iter();
+            let c = children.next();
+            self.stack.push((u.keys.into_iter(), children));
+            match c {
+                Some(c) => ui = c,
+                None => return,
+            }
+        }
+    }
+}
+
+impl<T, S: BlockStore<Node<T>>> Iterator for Iter<'_, T, S> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (keys, children) = self.stack.last_mut()?;
+            if let Some(x) = keys.next() {
+                if let Some(c) = children.next() {
+                    self.descend(c);
+                }
+                self.len -= 1;
+                return Some(x);
+            }
+            self.stack.pop();
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T, S: BlockStore<Node<T>>> ExactSizeIterator for Iter<'_, T, S> {}
+
+impl<'a, T: Ord, S: BlockStore<Node<T>>> IntoIterator for &'a BTree<T, S> {
+    type Item = T;
+    type IntoIter = Iter<'a, T, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileStore, block_store::temp_path, util::Rng};
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn scenario() {
+        let mut tree = BTree::new(2);
+        assert_eq!(tree.find(&0), None);
+        assert_eq!(tree.remove(&0), None);
+
+        for x in [5, 2, 8, 1, 9, 3, 7] {
+            assert!(tree.add(x));
+            tree.check();
+        }
+        assert!(!tree.add(5));
+        assert_eq!(tree.size(), 7);
+
+        assert_eq!(tree.find(&4), Some(5));
+        assert_eq!(tree.find(&9), Some(9));
+        assert_eq!(tree.find(&10), None);
+        assert!(tree.contains(&3));
+        assert!(!tree.contains(&4));
+
+        assert_eq!(tree.remove(&5), Some(5));
+        assert_eq!(tree.remove(&5), None);
+        tree.check();
+        assert_eq!(format!("{tree:?}"), "{1, 2, 3, 7, 8, 9}");
+        assert_eq!(tree.iter().len(), 6);
+
+        for x in [1, 2, 3, 7, 8, 9] {
+            assert_eq!(tree.remove(&x), Some(x));
+            tree.check();
+        }
+        assert_eq!(tree.store().size(), 1);
+    }
+
+    fn random_operations<S: BlockStore<Node<u64>>>(mut tree: BTree<u64, S>, seed: u64) {
+        let mut rng = Rng::with_seed(seed);
+        let mut expected = BTreeSet::new();
+        for i in 0..2000 {
+            let x = rng.next_u64() % 500;
+            match rng.next_u64() % 3 {
+                0 | 1 => assert_eq!(tree.add(x), expected.insert(x)),
+                _ => assert_eq!(tree.remove(&x), expected.take(&x)),
+            }
+            let y = rng.next_u64() % 500;
+            assert_eq!(tree.find(&y), expected.range(y..).next().copied());
+            if i % 100 == 0 {
+                tree.check();
+            }
+        }
+        tree.check();
+        assert!(tree.iter().eq(expected.iter().copied()));
+    }
+
+    #[test]
+    fn random_operations_memory() {
+        for (b, seed) in [(2, 1), (3, 2), (8, 3)] {
+            let tree = BTree::new(b);
+            random_operations(tree, seed);
+        }
+    }
+
+    #[test]
+    fn random_operations_file() {
+        let path = temp_path("btree_random_operations");
+        let tree = BTree::with_store(4, FileStore::create(&path, 256).unwrap()).unwrap();
+        random_operations(tree, 4);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn io_stats() {
+        let mut tree = BTree::new(4);
+        for x in 0..1000 {
+            tree.add(x);
+        }
+        let nodes = tree.check();
+        assert_eq!(tree.store().size(), nodes);
+
+        // A search reads one block per level.
+        tree.reset_io_stats();
+        tree.find(&500);
+        let height = tree.io_stats().reads;
+        assert!((4..=7).contains(&height));
+        tree.reset_io_stats();
+        assert_eq!(tree.iter().count(), 1000);
+        assert_eq!(
+            tree.io_stats(),
+            IoStats {
+                reads: nodes as u64,
+                writes: 0
+            }
+        );
+
+        tree.reset_io_stats();
+        tree.add(1000);
+        let stats = tree.io_stats();
+        assert_eq!(stats.reads, height);
+        assert!(stats.writes >= 1);
+    }
+
+    #[test]
+    fn drop_elements() {
+        let x = std::rc::Rc::new(0);
+        let mut tree = BTree::new(2);
+        for i in 0..10 {
+            tree.add((i, x.clone()));
+        }
+        // Each read hands out copies, which are dropped along with the
+        // blocks they came from.
+        drop(tree.remove(&(3, x.clone())));
+        assert_eq!(std::rc::Rc::strong_count(&x), 10);
+        drop(tree);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+    }
+
+    #[test]
+    fn clear() {
+        let mut tree = BTree::new(2);
+        for x in 0..100 {
+            tree.add(x);
+        }
+        tree.clear();
+        assert_eq!(tree.size(), 0);
+        assert_eq!(tree.store().size(), 1);
+        assert_eq!(tree.find(&0), None);
+        tree.add(5);
+        assert!(tree.iter().eq([5]));
+    }
+
+    #[test]
+    fn standard_traits() {
+        let mut a = BTree::new(2);
+        a.extend([5, 1, 4, 2, 3]);
+        let mut b = BTree::new(3);
+        b.extend(1..=5);
+        assert_eq!(a, b);
+        assert_eq!(crate::util::hash_code(&a), crate::util::hash_code(&b));
+
+        let mut c = a.clone();
+        c.remove(&1);
+        assert!(a.contains(&1));
+        assert!(c.iter().eq(2..=5));
+        assert!(a < c);
+
+        let d = [3, 1, 5, 2, 4, 1].into_iter().collect::<BTree<_>>();
+        assert_eq!(d, a);
+        d.check();
+        assert_eq!(BTree::<u64>::default().size(), 0);
+    }
+
+    #[test]
+    fn block_size() {
+        // A full node of 7 keys and 8 children takes 4 + 8 + 56 + 64 bytes.
+        let path = temp_path("btree_block_size");
+        let store = FileStore::create(&path, 131).unwrap();
+        let e = BTree::<u64, _>::with_store(4, store).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+
+        let store = FileStore::create(&path, 132).unwrap();
+        let mut tree = BTree::with_store(4, store).unwrap();
+        for x in 0..200u64 {
+            assert!(tree.try_add(x).unwrap());
+        }
+        tree.check();
+        std::fs::remove_file(path).unwrap();
+    }
+
+    // Fails every write once `writes` runs out.
+    struct Failing {
+        inner: MemoryStore<Node<u64>>,
+        writes: usize,
+    }
+
+    impl BlockStore<Node<u64>> for Failing {
+        fn read_block(&self, i: usize) -> io::Result<Node<u64>> {
+            self.inner.read_block(i)
+        }
+
+        fn write_block(&mut self, i: usize, b: Node<u64>) -> io::Result<()> {
+            self.writes = self.writes.checked_sub(1).ok_or(io::ErrorKind::Other)?;
+            self.inner.write_block(i, b)
+        }
+
+        fn place_block(&mut self, b: Node<u64>) -> io::Result<usize> {
+            self.writes = self.writes.checked_sub(1).ok_or(io::ErrorKind::Other)?;
+            self.inner.place_block(b)
+        }
+
+        fn free_block(&mut self, i: usize) -> io::Result<()> {
+            self.inner.free_block(i)
+        }
+
+        fn io_stats(&self) -> IoStats {
+            self.inner.io_stats()
+        }
+
+        fn reset_io_stats(&self) {
+            self.inner.reset_io_stats();
+        }
+    }
+
+    #[test]
+    fn store_failures() {
+        let store = Failing {
+            inner: MemoryStore::new(),
+            writes: 0,
+        };
+        assert!(BTree::with_store(2, store).is_err());
+
+        let store = Failing {
+            inner: MemoryStore::new(),
+            writes: 10,
+        };
+        let mut tree = BTree::with_store(2, store).unwrap();
+        let mut added = 0;
+        let e = loop {
+            match tree.try_add(added) {
+                Ok(_) => added += 1,
+                Err(e) => break e,
+            }
+        };
+        assert_eq!(e.kind(), io::ErrorKind::Other);
+        assert_eq!(tree.size(), added as usize);
+        assert!(tree.try_remove(&0).is_err());
+        assert_eq!(tree.try_find(&0).unwrap(), Some(0));
+        assert!(tree.try_clear().is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "block store failed")]
+    fn store_failure_panics() {
+        let store = Failing {
+            inner: MemoryStore::new(),
+            writes: 1,
+        };
+        BTree::with_store(2, store).unwrap().add(0);
+    }
+
+    #[test]
+    fn corrupt_block() {
+        use std::io::{Seek, SeekFrom, Write};
+
+        let path = temp_path("btree_corrupt_block");
+        let mut tree = BTree::with_store(2, FileStore::create(&path, 128).unwrap()).unwrap();
+        tree.extend([1u64, 2, 3]);
+        // A root that claims 1000 keys but holds none of them.
+        let mut bytes = 8u32.to_le_bytes().to_vec();
+        bytes.extend(1000u32.to_le_bytes());
+        bytes.extend(0u32.to_le_bytes());
+        let mut file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+        file.seek(SeekFrom::Start(tree.ri as u64 * 128)).unwrap();
+        file.write_all(&bytes).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        let e = tree.try_find(&2).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+        assert!(tree.try_add(4).is_err());
+    }
+}
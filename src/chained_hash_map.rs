@@ -0,0 +1,637 @@
+use std::{borrow::Borrow, fmt, hash::Hash, iter::Flatten, ops::Index, slice, vec};
+
+use crate::{
+    ArrayStack,
+    util::{Rng, hash_code},
+};
+
+#[derive(Clone)]
+pub struct ChainedHashMap<K, V> {
+    t: Box<[ArrayStack<(K, V)>]>,
+    n: usize,
+    d: u32,
+    z: u64,
+}
+
+fn allocate_table<T>(d: u32) -> Box<[ArrayStack<T>]> {
+    std::iter::repeat_with(|| ArrayStack::new(0))
+        .take(1 << d)
+        .collect()
+}
+
+impl<K, V> Default for ChainedHashMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for ChainedHashMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V> ChainedHashMap<K, V> {
+    pub fn new() -> Self {
+        Self::with_rng(Rng::new())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(Rng::with_seed(seed))
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self
+    where
+        K: Hash + Eq,
+    {
+        let mut map = Self::new();
+        map.reserve(capacity);
+        map
+    }
+
+    fn with_rng(mut rng: Rng) -> Self {
+        Self {
+            t: allocate_table(1),
+            n: 0,
+            d: 1,
+            z: rng.next_u64() | 1,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    // One entry per bucket before an insertion doubles the table.
+    pub fn capacity(&self) -> usize {
+        self.t.len()
+    }
+
+    // Empties the buckets but keeps as many of them.
+    pub fn clear(&mut self) {
+        self.t.iter_mut().for_each(ArrayStack::clear);
+        self.n = 0;
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.t.iter().flatten(),
+            len: self.n,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            inner: self.t.iter_mut().flatten(),
+            len: self.n,
+        }
+    }
+}
+
+impl<K: Hash + Eq, V: PartialEq> PartialEq for ChainedHashMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size() == other.size() && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+impl<K: Hash + Eq, V: Eq> Eq for ChainedHashMap<K, V> {}
+
+impl<K: Hash + Eq, V> FromIterator<(K, V)> for ChainedHashMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut m = Self::new();
+        m.extend(iter);
+        m
+    }
+}
+
+impl<K: Hash + Eq, V> Extend<(K, V)> for ChainedHashMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<K, V, Q> Index<&Q> for ChainedHashMap<K, V>
+where
+    K: Borrow<Q> + Hash + Eq,
+    Q: Hash + Eq + ?Sized,
+{
+    type Output = V;
+
+    fn index(&self, k: &Q) -> &V {
+        self.get(k).expect("no entry for key")
+    }
+}
+
+impl<K: Hash + Eq, V> ChainedHashMap<K, V> {
+    fn hash<Q: Hash + ?Sized>(&self, k: &Q) -> usize {
+        (self.z.wrapping_mul(hash_code(k)) >> (u64::BITS - self.d)) as usize
+    }
+
+    fn locate<Q>(&self, k: &Q) -> (usize, Option<usize>)
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let j = self.hash(k);
+        (j, self.t[j].iter().position(|(y, _)| y.borrow() == k))
+    }
+
+    fn resize(&mut self) {
+        let mut d = 1;
+        while 1 << d <= self.n {
+            d += 1;
+        }
+        self.rehash(d);
+    }
+
+    fn rehash(&mut self, d: u32) {
+        self.d = d;
+        let old = std::mem::replace(&mut self.t, allocate_table(self.d));
+        for (k, v) in old.into_vec().into_iter().flatten() {
+            let j = self.hash(&k);
+            let bucket = &mut self.t[j];
+            bucket.add(bucket.size(), (k, v));
+        }
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        let len = (self.n.checked_add(additional))
+            .and_then(usize::checked_next_power_of_two)
+            .expect("capacity overflow");
+        if len > self.t.len() {
+            self.rehash(len.trailing_zeros().max(1));
+        }
+    }
+
+    // Rehashes into the fewest buckets that keep the load below one.
+    pub fn shrink_to_fit(&mut self) {
+        self.resize();
+    }
+
+    pub fn get<Q>(&self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_key_value(k).map(|(_, v)| v)
+    }
+
+    pub fn get_key_value<Q>(&self, k: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (j, i) = self.locate(k);
+        let (k, v) = self.t[j].get(i?)?;
+        Some((k, v))
+    }
+
+    pub fn get_mut<Q>(&mut self, k: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (j, i) = self.locate(k);
+        Some(&mut self.t[j].get_mut(i?)?.1)
+    }
+
+    pub fn contains_key<Q>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.locate(k).1.is_some()
+    }
+
+    pub fn insert(&mut self, k: K, v: V) -> Option<V> {
+        match self.entry(k) {
+            Entry::Occupied(mut e) => Some(e.insert(v)),
+            Entry::Vacant(e) => {
+                e.insert(v);
+                None
+            }
+        }
+    }
+
+    pub fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.remove_entry(k).map(|(_, v)| v)
+    }
+
+    pub fn remove_entry<Q>(&mut self, k: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (j, i) = self.locate(k);
+        Some(self.remove_at(j, i?))
+    }
+
+    fn remove_at(&mut self, j: usize, i: usize) -> (K, V) {
+        let entry = self.t[j].remove(i).unwrap();
+        self.n -= 1;
+        if 3 * self.n < self.t.len() {
+            self.resize();
+        }
+        entry
+    }
+
+    pub fn entry(&mut self, k: K) -> Entry<'_, K, V> {
+        match self.locate(&k) {
+            (j, Some(i)) => Entry::Occupied(OccupiedEntry { map: self, j, i }),
+            (_, None) => Entry::Vacant(VacantEntry { map: self, k }),
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn bucket_count(&self) -> usize {
+        self.t.len()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn check_invariants(&self) {
+        assert_eq!(self.t.len(), 1 << self.d);
+        assert_eq!(self.t.iter().map(ArrayStack::size).sum::<usize>(), self.n);
+        assert!(self.n <= self.t.len());
+        assert!(self.t.len() <= 2 || 3 * self.n >= self.t.len());
+        for (j, bucket) in self.t.iter().enumerate() {
+            for (k, _) in bucket {
+                assert_eq!(self.hash(k), j);
+            }
+        }
+    }
+}
+
+pub enum Entry<'a, K, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+pub struct OccupiedEntry<'a, K, V> {
+    map: &'a mut ChainedHashMap<K, V>,
+    j: usize,
+    i: usize,
+}
+
+pub struct VacantEntry<'a, K, V> {
+    map: &'a mut ChainedHashMap<K, V>,
+    k: K,
+}
+
+impl<'a, K: Hash + Eq, V> Entry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(e) => e.key(),
+            Entry::Vacant(e) => e.key(),
+        }
+    }
+
+    pub fn or_insert(self, v: V) -> &'a mut V {
+        self.or_insert_with(|| v)
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, f: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(f()),
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(e) = &mut self {
+            f(e.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K: Hash + Eq, V> OccupiedEntry<'a, K, V> {
+    fn pair(&self) -> &(K, V) {
+        self.map.t[self.j].get(self.i).unwrap()
+    }
+
+    fn pair_mut(&mut self) -> &mut (K, V) {
+        self.map.t[self.j].get_mut(self.i).unwrap()
+    }
+
+    pub fn key(&self) -> &K {
+        &self.pair().0
+    }
+
+    pub fn get(&self) -> &V {
+        &self.pair().1
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.pair_mut().1
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.t[self.j].get_mut(self.i).unwrap().1
+    }
+
+    pub fn insert(&mut self, v: V) -> V {
+        std::mem::replace(self.get_mut(), v)
+    }
+
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    pub fn remove_entry(self) -> (K, V) {
+        self.map.remove_at(self.j, self.i)
+    }
+}
+
+impl<'a, K: Hash + Eq, V> VacantEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.k
+    }
+
+    pub fn into_key(self) -> K {
+        self.k
+    }
+
+    pub fn insert(self, v: V) -> &'a mut V {
+        let map = self.map;
+        if map.n + 1 > map.t.len() {
+            map.resize();
+        }
+        let j = map.hash(&self.k);
+        let bucket = &mut map.t[j];
+        let i = bucket.size();
+        bucket.add(i, (self.k, v));
+        map.n += 1;
+        &mut map.t[j].get_mut(i).unwrap().1
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    inner: Flatten<slice::Iter<'a, ArrayStack<(K, V)>>>,
+    len: usize,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (k, v) = self.inner.next()?;
+        self.len -= 1;
+        Some((k, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+pub struct IterMut<'a, K, V> {
+    inner: Flatten<slice::IterMut<'a, ArrayStack<(K, V)>>>,
+    len: usize,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (k, v) = self.inner.next()?;
+        self.len -= 1;
+        Some((&*k, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+pub struct IntoIter<K, V> {
+    inner: Flatten<vec::IntoIter<ArrayStack<(K, V)>>>,
+    len: usize,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.inner.next()?;
+        self.len -= 1;
+        Some(x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+impl<K, V> IntoIterator for ChainedHashMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.t.into_vec().into_iter().flatten(),
+            len: self.n,
+        }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a ChainedHashMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a mut ChainedHashMap<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn check(map: &ChainedHashMap<i32, i32>, expected: &HashMap<i32, i32>) {
+        assert_eq!(map.size(), expected.len());
+        let pairs = map.iter().map(|(&k, &v)| (k, v)).collect::<HashMap<_, _>>();
+        assert_eq!(pairs, *expected);
+        for (k, v) in expected {
+            assert_eq!(map.get(k), Some(v));
+        }
+        map.check_invariants();
+    }
+
+    #[test]
+    fn scenario() {
+        let mut map = ChainedHashMap::with_seed(0);
+        assert_eq!(map.get("a"), None);
+
+        assert_eq!(map.insert("a".to_string(), 1), None);
+        assert_eq!(map.insert("b".to_string(), 2), None);
+        assert_eq!(map.insert("a".to_string(), 3), Some(1));
+        assert_eq!(map.size(), 2);
+
+        assert_eq!(map.get("a"), Some(&3));
+        assert_eq!(map.get_key_value("b"), Some((&"b".to_string(), &2)));
+        assert!(map.contains_key("b"));
+        assert!(!map.contains_key("c"));
+
+        *map.get_mut("b").unwrap() += 10;
+        assert_eq!(map.get("b"), Some(&12));
+        assert_eq!(map.get_mut("c"), None);
+
+        assert_eq!(map.remove("a"), Some(3));
+        assert_eq!(map.remove("a"), None);
+        assert_eq!(map.size(), 1);
+        assert_eq!(format!("{map:?}"), r#"{"b": 12}"#);
+    }
+
+    #[test]
+    fn entry() {
+        let mut map = ChainedHashMap::with_seed(1);
+        for w in "the quick fox and the lazy dog and the cat".split(' ') {
+            *map.entry(w).or_insert(0) += 1;
+        }
+        assert_eq!(map.get("the"), Some(&3));
+        assert_eq!(map.get("and"), Some(&2));
+        assert_eq!(map.get("fox"), Some(&1));
+        assert_eq!(map.size(), 7);
+
+        match map.entry("the") {
+            Entry::Occupied(mut e) => {
+                assert_eq!(e.key(), &"the");
+                assert_eq!(e.get(), &3);
+                assert_eq!(e.insert(5), 3);
+                assert_eq!(e.remove_entry(), ("the", 5));
+            }
+            Entry::Vacant(_) => unreachable!(),
+        }
+        match map.entry("owl") {
+            Entry::Occupied(_) => unreachable!(),
+            Entry::Vacant(e) => {
+                assert_eq!(e.key(), &"owl");
+                assert_eq!(e.into_key(), "owl");
+            }
+        }
+        assert_eq!(map.size(), 6);
+
+        map.entry("cat").and_modify(|v| *v += 1).or_default();
+        map.entry("emu").and_modify(|v| *v += 1).or_default();
+        assert_eq!(map.get("cat"), Some(&2));
+        assert_eq!(map.get("emu"), Some(&0));
+        assert_eq!(*map.entry("yak").or_insert_with(|| 9), 9);
+    }
+
+    #[test]
+    fn random_operations() {
+        let mut rng = Rng::with_seed(2);
+        let mut map = ChainedHashMap::with_seed(3);
+        let mut expected = HashMap::new();
+        for step in 0..3000 {
+            let k = (rng.next_u64() % 300) as i32;
+            match rng.next_u64() % 4 {
+                0 | 1 => assert_eq!(map.insert(k, step), expected.insert(k, step)),
+                2 => {
+                    *map.entry(k).or_insert(0) += 1;
+                    *expected.entry(k).or_insert(0) += 1;
+                }
+                _ => assert_eq!(map.remove(&k), expected.remove(&k)),
+            }
+            assert_eq!(map.get(&k), expected.get(&k));
+        }
+        check(&map, &expected);
+    }
+
+    #[test]
+    fn iter() {
+        let mut map = ChainedHashMap::with_seed(4);
+        for k in 0..10 {
+            map.insert(k, k);
+        }
+        for (k, v) in &mut map {
+            *v += k * 10;
+        }
+        assert_eq!(map.iter().len(), 10);
+        check(&map, &(0..10).map(|k| (k, k * 11)).collect());
+
+        let iter = map.into_iter();
+        assert_eq!(iter.len(), 10);
+        let mut pairs = iter.collect::<Vec<_>>();
+        pairs.sort();
+        assert_eq!(pairs, (0..10).map(|k| (k, k * 11)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn capacity() {
+        let mut map = ChainedHashMap::with_capacity(100);
+        assert_eq!(map.capacity(), 128);
+        for i in 0..100 {
+            map.insert(i, i);
+        }
+        assert_eq!(map.capacity(), 128);
+        for i in 0..50 {
+            map.remove(&i);
+        }
+        map.shrink_to_fit();
+        assert_eq!(map.capacity(), 64);
+        map.check_invariants();
+
+        map.clear();
+        assert_eq!(map.size(), 0);
+        assert_eq!(map.iter().count(), 0);
+        map.reserve(1000);
+        assert_eq!(map.capacity(), 1024);
+        map.insert(7, 7);
+        assert_eq!(map.get(&7), Some(&7));
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a: ChainedHashMap<&str, i32> = [("a", 1), ("b", 2), ("c", 3)].into_iter().collect();
+        let mut b = ChainedHashMap::with_capacity(64);
+        b.extend([("c", 3), ("a", 1), ("b", 2)]);
+        assert_ne!(a.capacity(), b.capacity());
+        assert_eq!(a, b);
+        assert_eq!(a["b"], 2);
+
+        let mut c = a.clone();
+        *c.get_mut("b").unwrap() = 5;
+        assert_eq!(a["b"], 2);
+        assert_ne!(a, c);
+        c.remove("b");
+        c.insert("d", 2);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    #[should_panic(expected = "no entry for key")]
+    fn index_missing_key() {
+        let map = ChainedHashMap::<&str, i32>::new();
+        let _ = map["a"];
+    }
+}
@@ -0,0 +1,46 @@
+use std::cmp::Ordering;
+
+// The order a priority queue or a sort arranges its elements in; the element
+// that compares least comes first.
+pub trait Compare<T: ?Sized> {
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Min;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Max;
+
+impl<T: Ord + ?Sized> Compare<T> for Min {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+impl<T: Ord + ?Sized> Compare<T> for Max {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        b.cmp(a)
+    }
+}
+
+impl<T: ?Sized, F: Fn(&T, &T) -> Ordering> Compare<T> for F {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self(a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders() {
+        assert_eq!(Min.compare(&1, &2), Ordering::Less);
+        assert_eq!(Max.compare(&1, &2), Ordering::Greater);
+        assert_eq!(Min.compare("b", "b"), Ordering::Equal);
+
+        let by_len = |a: &&str, b: &&str| a.len().cmp(&b.len());
+        assert_eq!(by_len.compare(&"abc", &"z"), Ordering::Greater);
+    }
+}
@@ -1,37 +1,63 @@
 use std::{
-    cell::RefCell,
-    rc::{Rc, Weak},
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::{Index, IndexMut, RangeBounds},
+    ptr::NonNull,
 };
 
-#[derive(Debug)]
 pub struct DLList<T> {
-    dummy_head: Rc<RefCell<Node<T>>>,
-    dummy_tail: Rc<RefCell<Node<T>>>,
+    dummy: NonNull<Node<T>>,
     n: usize,
+    marker: PhantomData<Box<Node<T>>>,
 }
 
-#[derive(Debug)]
-pub struct Node<T> {
+struct Node<T> {
     x: Option<T>,
-    prev: Option<Weak<RefCell<Node<T>>>>,
-    next: Option<Rc<RefCell<Node<T>>>>,
+    prev: NonNull<Node<T>>,
+    next: NonNull<Node<T>>,
 }
 
+// SAFETY: `DLList` owns its nodes exclusively, like `Box<Node<T>>` would.
+unsafe impl<T: Send> Send for DLList<T> {}
+unsafe impl<T: Sync> Sync for DLList<T> {}
+
 impl<T> Node<T> {
-    fn dummy() -> Rc<RefCell<Self>> {
-        Rc::new(RefCell::new(Self {
-            x: None,
-            prev: None,
-            next: None,
-        }))
+    fn alloc(x: Option<T>) -> NonNull<Self> {
+        let u = NonNull::from(Box::leak(Box::new(Self {
+            x,
+            prev: NonNull::dangling(),
+            next: NonNull::dangling(),
+        })));
+        // SAFETY: `u` was just allocated and is not aliased.
+        unsafe {
+            (*u.as_ptr()).prev = u;
+            (*u.as_ptr()).next = u;
+        }
+        u
+    }
+
+    // SAFETY: the caller must guarantee that `u` is a live node.
+    unsafe fn next(u: NonNull<Self>) -> NonNull<Self> {
+        unsafe { (*u.as_ptr()).next }
+    }
+
+    // SAFETY: the caller must guarantee that `u` is a live node.
+    unsafe fn prev(u: NonNull<Self>) -> NonNull<Self> {
+        unsafe { (*u.as_ptr()).prev }
     }
 
-    fn new(x: T) -> Rc<RefCell<Self>> {
-        Rc::new(RefCell::new(Self {
-            x: Some(x),
-            prev: None,
-            next: None,
-        }))
+    // SAFETY: the caller must guarantee that `u` outlives `'a` and that no
+    // mutable reference to its element exists meanwhile.
+    unsafe fn x<'a>(u: NonNull<Self>) -> Option<&'a T> {
+        unsafe { (*u.as_ptr()).x.as_ref() }
+    }
+
+    // SAFETY: the caller must guarantee that `u` outlives `'a` and that no
+    // other reference to its element exists meanwhile.
+    unsafe fn x_mut<'a>(u: NonNull<Self>) -> Option<&'a mut T> {
+        unsafe { (*u.as_ptr()).x.as_mut() }
     }
 }
 
@@ -41,16 +67,34 @@ impl<T> Default for DLList<T> {
     }
 }
 
+impl<T> Drop for DLList<T> {
+    fn drop(&mut self) {
+        // SAFETY: every node reachable from the dummy was allocated by
+        // `Node::alloc` and is owned by this list.
+        unsafe {
+            let mut u = Node::next(self.dummy);
+            while u != self.dummy {
+                let next = Node::next(u);
+                drop(Box::from_raw(u.as_ptr()));
+                u = next;
+            }
+            drop(Box::from_raw(self.dummy.as_ptr()));
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for DLList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
 impl<T> DLList<T> {
     pub fn new() -> Self {
-        let dummy_head = Node::dummy();
-        let dummy_tail = Node::dummy();
-        dummy_head.borrow_mut().next = Some(Rc::clone(&dummy_tail));
-        dummy_tail.borrow_mut().prev = Some(Rc::downgrade(&dummy_head));
         Self {
-            dummy_head,
-            dummy_tail,
+            dummy: Node::alloc(None),
             n: 0,
+            marker: PhantomData,
         }
     }
 
@@ -58,95 +102,698 @@ impl<T> DLList<T> {
         self.n
     }
 
-    pub fn get_node(&self, i: usize) -> Option<Rc<RefCell<Node<T>>>> {
-        if i > self.n {
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    fn get_node(&self, i: usize) -> NonNull<Node<T>> {
+        debug_assert!(i <= self.n);
+
+        // SAFETY: the walk stays within the `n + 1` nodes of the list.
+        unsafe {
+            let mut p;
+            if i < self.n / 2 {
+                p = Node::next(self.dummy);
+                for _ in 0..i {
+                    p = Node::next(p);
+                }
+            } else {
+                p = self.dummy;
+                for _ in i..self.n {
+                    p = Node::prev(p);
+                }
+            }
+            p
+        }
+    }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if i >= self.n {
+            return None;
+        }
+        // SAFETY: the node is owned by the list, which is borrowed shared.
+        unsafe { Node::x(self.get_node(i)) }
+    }
+
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        if i >= self.n {
             return None;
         }
+        // SAFETY: the node is owned by the list, which is borrowed exclusively.
+        unsafe { (*self.get_node(i).as_ptr()).x.as_mut() }
+    }
 
-        let mut p;
-        if i < self.n / 2 {
-            p = self.dummy_head.borrow().next.clone().unwrap();
-            for _ in 0..i {
-                p = p.clone().borrow().next.clone().unwrap();
+    pub fn set(&mut self, i: usize, x: T) -> Option<T> {
+        if i >= self.n {
+            return None;
+        }
+        // SAFETY: the node is owned by the list, which is borrowed exclusively.
+        unsafe { (*self.get_node(i).as_ptr()).x.replace(x) }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        // SAFETY: the dummy is always live.
+        unsafe {
+            Iter {
+                front: Node::next(self.dummy),
+                back: Node::prev(self.dummy),
+                len: self.n,
+                marker: PhantomData,
             }
-        } else {
-            p = self.dummy_tail.clone();
-            for _ in i..self.n {
-                p = p.clone().borrow().prev.as_ref().unwrap().upgrade().unwrap();
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        // SAFETY: the dummy is always live.
+        unsafe {
+            IterMut {
+                front: Node::next(self.dummy),
+                back: Node::prev(self.dummy),
+                len: self.n,
+                marker: PhantomData,
             }
         }
-        Some(p)
     }
 
-    pub fn get(&self, i: usize) -> Option<T>
-    where
-        T: Clone,
-    {
-        self.get_node(i)?.borrow().x.clone()
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        self.cursor_at(0)
     }
 
-    pub fn set(&self, i: usize, x: T) -> Option<T> {
-        self.get_node(i)?.borrow_mut().x.replace(x)
+    pub fn cursor_back(&self) -> Cursor<'_, T> {
+        self.cursor_at(self.n.saturating_sub(1))
     }
 
-    pub fn add_before(
-        &mut self,
-        w: Option<Rc<RefCell<Node<T>>>>,
-        x: T,
-    ) -> Option<Rc<RefCell<Node<T>>>> {
-        let w = w?;
-        let u = Node::new(x);
-        let v = w.borrow_mut().prev.take().unwrap().upgrade().unwrap();
-        v.borrow_mut().next = Some(u.clone());
-        u.borrow_mut().prev = Some(Rc::downgrade(&v));
-        w.borrow_mut().prev = Some(Rc::downgrade(&u));
-        u.borrow_mut().next = Some(w);
+    pub fn cursor_at(&self, i: usize) -> Cursor<'_, T> {
+        crate::error::assert_insert(i, self.n);
+        Cursor {
+            current: self.get_node(i),
+            index: i,
+            list: self,
+        }
+    }
+
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        self.cursor_at_mut(0)
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        self.cursor_at_mut(self.n.saturating_sub(1))
+    }
+
+    pub fn cursor_at_mut(&mut self, i: usize) -> CursorMut<'_, T> {
+        crate::error::assert_insert(i, self.n);
+        CursorMut {
+            current: self.get_node(i),
+            index: i,
+            list: self,
+        }
+    }
+
+    // SAFETY: the caller must guarantee that `w` is a node of this list,
+    // possibly the dummy.
+    unsafe fn add_before(&mut self, w: NonNull<Node<T>>, x: T) -> NonNull<Node<T>> {
+        let u = Node::alloc(Some(x));
+        unsafe {
+            let v = Node::prev(w);
+            (*u.as_ptr()).prev = v;
+            (*u.as_ptr()).next = w;
+            (*v.as_ptr()).next = u;
+            (*w.as_ptr()).prev = u;
+        }
         self.n += 1;
-        Some(u)
+        u
+    }
+
+    pub fn add(&mut self, i: usize, x: T) {
+        crate::error::assert_insert(i, self.n);
+        // SAFETY: `get_node` returns a node of this list.
+        unsafe {
+            self.add_before(self.get_node(i), x);
+        }
+    }
+
+    // SAFETY: the caller must guarantee that `w` is a node of this list other
+    // than the dummy. `w` is deallocated.
+    unsafe fn remove_node(&mut self, w: NonNull<Node<T>>) -> T {
+        debug_assert!(w != self.dummy);
+        unsafe {
+            let p = Node::prev(w);
+            let n = Node::next(w);
+            (*p.as_ptr()).next = n;
+            (*n.as_ptr()).prev = p;
+            self.n -= 1;
+            Box::from_raw(w.as_ptr()).x.unwrap()
+        }
+    }
+
+    pub fn remove(&mut self, i: usize) -> Option<T> {
+        if i >= self.n {
+            return None;
+        }
+        // SAFETY: `get_node` returns an element node of this list.
+        unsafe { Some(self.remove_node(self.get_node(i))) }
     }
 
-    pub fn add(&mut self, i: usize, x: T) -> bool {
-        self.add_before(self.get_node(i), x).is_some()
+    pub fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            // SAFETY: the dummy is a node of this list.
+            unsafe {
+                self.add_before(self.dummy, x);
+            }
+        }
     }
 
-    pub fn remove_node(&mut self, w: Option<Rc<RefCell<Node<T>>>>) {
-        assert!(self.n > 0);
+    // Links the new elements into a list of their own first, then splices
+    // that in, so only the walk to `i` depends on the size.
+    pub fn insert_many<I: IntoIterator<Item = T>>(&mut self, i: usize, iter: I) {
+        crate::error::assert_insert(i, self.n);
+        let mut other = Self::new();
+        other.extend(iter);
+        // SAFETY: `get_node` returns a node of this list.
+        unsafe { self.splice_before(self.get_node(i), &mut other) }
+    }
+
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> IntoIter<T> {
+        let r = crate::error::assert_range(range, self.n);
+        if r.is_empty() {
+            return Self::new().into_iter();
+        }
+        // SAFETY: the nodes from `start` to `end - 1` are a chain of element
+        // nodes.
+        unsafe {
+            let (first, last) = (self.get_node(r.start), self.get_node(r.end - 1));
+            self.split_range(first, last, r.len()).into_iter()
+        }
+    }
 
-        let Some(w) = w else {
+    // Moves all of `other` to the end of this list in O(1).
+    pub fn append(&mut self, other: &mut Self) {
+        // SAFETY: the dummy is a node of this list.
+        unsafe { self.splice_before(self.dummy, other) }
+    }
+
+    pub fn split_off(&mut self, i: usize) -> Self {
+        crate::error::assert_insert(i, self.n);
+        if i == self.n {
+            return Self::new();
+        }
+        // SAFETY: the nodes from `i` to the last element are a chain of
+        // element nodes.
+        unsafe {
+            let last = Node::prev(self.dummy);
+            self.split_range(self.get_node(i), last, self.n - i)
+        }
+    }
+
+    // SAFETY: the caller must guarantee that `w` is a node of this list,
+    // possibly the dummy. `other` is left empty.
+    unsafe fn splice_before(&mut self, w: NonNull<Node<T>>, other: &mut Self) {
+        if other.n == 0 {
             return;
+        }
+        unsafe {
+            let first = Node::next(other.dummy);
+            let last = Node::prev(other.dummy);
+            let v = Node::prev(w);
+            (*v.as_ptr()).next = first;
+            (*first.as_ptr()).prev = v;
+            (*last.as_ptr()).next = w;
+            (*w.as_ptr()).prev = last;
+
+            (*other.dummy.as_ptr()).next = other.dummy;
+            (*other.dummy.as_ptr()).prev = other.dummy;
+        }
+        self.n += std::mem::take(&mut other.n);
+    }
+
+    // SAFETY: the caller must guarantee that `first..=last` is a non-empty
+    // chain of `count` element nodes of this list.
+    unsafe fn split_range(
+        &mut self,
+        first: NonNull<Node<T>>,
+        last: NonNull<Node<T>>,
+        count: usize,
+    ) -> Self {
+        let mut list = Self::new();
+        unsafe {
+            let before = Node::prev(first);
+            let after = Node::next(last);
+            (*before.as_ptr()).next = after;
+            (*after.as_ptr()).prev = before;
+
+            (*first.as_ptr()).prev = list.dummy;
+            (*last.as_ptr()).next = list.dummy;
+            (*list.dummy.as_ptr()).next = first;
+            (*list.dummy.as_ptr()).prev = last;
+        }
+        self.n -= count;
+        list.n = count;
+        list
+    }
+}
+
+pub struct Iter<'a, T> {
+    front: NonNull<Node<T>>,
+    back: NonNull<Node<T>>,
+    len: usize,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // SAFETY: `len` counts the element nodes between `front` and `back`,
+        // which the list keeps alive for `'a`.
+        unsafe {
+            let u = self.front;
+            self.front = Node::next(u);
+            Node::x(u)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // SAFETY: see `next`.
+        unsafe {
+            let u = self.back;
+            self.back = Node::prev(u);
+            Node::x(u)
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    front: NonNull<Node<T>>,
+    back: NonNull<Node<T>>,
+    len: usize,
+    marker: PhantomData<&'a mut Node<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // SAFETY: `len` counts the element nodes between `front` and `back`,
+        // each of which is yielded at most once.
+        unsafe {
+            let u = self.front;
+            self.front = Node::next(u);
+            Node::x_mut(u)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // SAFETY: see `next`.
+        unsafe {
+            let u = self.back;
+            self.back = Node::prev(u);
+            Node::x_mut(u)
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+pub struct IntoIter<T> {
+    list: DLList<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.remove(0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.n, Some(self.list.n))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.remove(self.list.n.checked_sub(1)?)
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for DLList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a DLList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut DLList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+pub struct Cursor<'a, T> {
+    list: &'a DLList<T>,
+    current: NonNull<Node<T>>,
+    index: usize,
+}
+
+impl<'a, T> Cursor<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        (self.index < self.list.n).then_some(self.index)
+    }
+
+    pub fn move_next(&mut self) {
+        // SAFETY: `current` is a node of the list, which outlives the cursor.
+        self.current = unsafe { Node::next(self.current) };
+        self.index = if self.index == self.list.n {
+            0
+        } else {
+            self.index + 1
         };
+    }
 
-        let p = w.borrow_mut().prev.take().unwrap().upgrade().unwrap();
-        let n = w.borrow_mut().next.take().unwrap();
+    pub fn move_prev(&mut self) {
+        // SAFETY: `current` is a node of the list, which outlives the cursor.
+        self.current = unsafe { Node::prev(self.current) };
+        self.index = if self.index == 0 {
+            self.list.n
+        } else {
+            self.index - 1
+        };
+    }
 
-        n.borrow_mut().prev = Some(Rc::downgrade(&p));
-        p.borrow_mut().next = Some(n);
+    pub fn current(&self) -> Option<&'a T> {
+        // SAFETY: the list is borrowed shared for `'a`, so none of its nodes
+        // can be unlinked or mutated meanwhile.
+        unsafe { Node::x(self.current) }
+    }
 
-        self.n -= 1;
+    pub fn peek_next(&self) -> Option<&'a T> {
+        // SAFETY: see `current`.
+        unsafe { Node::x(Node::next(self.current)) }
     }
 
-    pub fn remove(&mut self, i: usize) -> Option<T> {
-        let w = self.get_node(i)?;
-        let x = w.borrow_mut().x.take()?;
-        self.remove_node(Some(w));
-        Some(x)
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        // SAFETY: see `current`.
+        unsafe { Node::x(Node::prev(self.current)) }
+    }
+}
+
+pub struct CursorMut<'a, T> {
+    list: &'a mut DLList<T>,
+    current: NonNull<Node<T>>,
+    index: usize,
+}
+
+impl<T> CursorMut<'_, T> {
+    pub fn index(&self) -> Option<usize> {
+        (self.index < self.list.n).then_some(self.index)
+    }
+
+    pub fn move_next(&mut self) {
+        // SAFETY: `current` is a node of the list, which outlives the cursor.
+        self.current = unsafe { Node::next(self.current) };
+        self.index = if self.index == self.list.n {
+            0
+        } else {
+            self.index + 1
+        };
+    }
+
+    pub fn move_prev(&mut self) {
+        // SAFETY: `current` is a node of the list, which outlives the cursor.
+        self.current = unsafe { Node::prev(self.current) };
+        self.index = if self.index == 0 {
+            self.list.n
+        } else {
+            self.index - 1
+        };
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        // SAFETY: the list is borrowed exclusively by the cursor, and the
+        // returned reference borrows the cursor.
+        unsafe { Node::x_mut(self.current) }
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        // SAFETY: see `current`.
+        unsafe { Node::x_mut(Node::next(self.current)) }
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        // SAFETY: see `current`.
+        unsafe { Node::x_mut(Node::prev(self.current)) }
+    }
+
+    pub fn as_cursor(&self) -> Cursor<'_, T> {
+        Cursor {
+            list: self.list,
+            current: self.current,
+            index: self.index,
+        }
+    }
+
+    pub fn insert_before(&mut self, x: T) {
+        // SAFETY: `current` is a node of the list.
+        unsafe {
+            self.list.add_before(self.current, x);
+        }
+        self.index += 1;
+    }
+
+    pub fn insert_after(&mut self, x: T) {
+        // SAFETY: `current` is a node of the list.
+        unsafe {
+            self.list.add_before(Node::next(self.current), x);
+        }
+        if self.current == self.list.dummy {
+            self.index += 1;
+        }
+    }
+
+    pub fn remove_current(&mut self) -> Option<T> {
+        if self.current == self.list.dummy {
+            return None;
+        }
+        // SAFETY: `current` is an element node of the list.
+        unsafe {
+            let u = self.current;
+            self.current = Node::next(u);
+            Some(self.list.remove_node(u))
+        }
+    }
+
+    pub fn split_before(&mut self) -> DLList<T> {
+        let count = if self.current == self.list.dummy {
+            self.list.n
+        } else {
+            self.index
+        };
+        self.index -= count;
+        if count == 0 {
+            return DLList::new();
+        }
+        // SAFETY: the `count` nodes before `current` form a chain starting at
+        // the first element.
+        unsafe {
+            let first = Node::next(self.list.dummy);
+            let last = Node::prev(self.current);
+            self.list.split_range(first, last, count)
+        }
+    }
+
+    pub fn split_after(&mut self) -> DLList<T> {
+        let count = if self.current == self.list.dummy {
+            self.index = 0;
+            self.list.n
+        } else {
+            self.list.n - self.index - 1
+        };
+        if count == 0 {
+            return DLList::new();
+        }
+        // SAFETY: the `count` nodes after `current` form a chain ending at the
+        // last element.
+        unsafe {
+            let first = Node::next(self.current);
+            let last = Node::prev(self.list.dummy);
+            self.list.split_range(first, last, count)
+        }
+    }
+}
+
+impl<T: Clone> Clone for DLList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: PartialEq> PartialEq for DLList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
     }
 }
 
+impl<T: Eq> Eq for DLList<T> {}
+
+impl<T: PartialOrd> PartialOrd for DLList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for DLList<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for DLList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.n);
+        self.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl<T> FromIterator<T> for DLList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut c = Self::new();
+        c.extend(iter);
+        c
+    }
+}
+
+impl<T> Extend<T> for DLList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        DLList::extend(self, iter)
+    }
+}
+
+impl<T> Index<usize> for DLList<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        self.get(i)
+            .unwrap_or_else(|| crate::error::index_out_of_bounds(i, self.n))
+    }
+}
+
+impl<T> IndexMut<usize> for DLList<T> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        let n = self.n;
+        self.get_mut(i)
+            .unwrap_or_else(|| crate::error::index_out_of_bounds(i, n))
+    }
+}
+
+impl<T> crate::List<T> for DLList<T> {
+    fn size(&self) -> usize {
+        DLList::size(self)
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        DLList::get(self, i)
+    }
+
+    fn set(&mut self, i: usize, x: T) -> Option<T> {
+        DLList::set(self, i, x)
+    }
+
+    fn add(&mut self, i: usize, x: T) {
+        DLList::add(self, i, x)
+    }
+
+    fn remove(&mut self, i: usize) -> Option<T> {
+        DLList::remove(self, i)
+    }
+}
+
+impl<T> crate::Deque<T> for DLList<T> {
+    fn size(&self) -> usize {
+        DLList::size(self)
+    }
+
+    fn add_first(&mut self, x: T) {
+        self.add(0, x)
+    }
+
+    fn add_last(&mut self, x: T) {
+        self.add(self.n, x)
+    }
+
+    fn remove_first(&mut self) -> Option<T> {
+        self.remove(0)
+    }
+
+    fn remove_last(&mut self) -> Option<T> {
+        if self.n == 0 {
+            return None;
+        }
+        self.remove(self.n - 1)
+    }
+}
+
+// The unsafe core is checked under Miri with
+// `cargo +nightly miri test --lib -- doubly_linked_list singly_linked_list`.
 #[cfg(test)]
 mod tests {
     use super::*;
 
     fn check(list: &DLList<char>, expected: &str) {
         for (i, v) in expected.chars().enumerate() {
-            assert_eq!(list.get(i), Some(v));
+            assert_eq!(list.get(i), Some(&v));
         }
         assert_eq!(list.size(), expected.len());
     }
 
     fn setup(list: &mut DLList<char>, s: &str) {
         for (i, v) in s.chars().enumerate() {
-            assert!(list.add(i, v))
+            list.add(i, v);
         }
     }
 
@@ -163,16 +810,16 @@ mod tests {
         let mut list = DLList::new();
         setup(&mut list, "abcde");
 
-        assert!(list.add(0, 'x'));
+        list.add(0, 'x');
         check(&list, "xabcde");
 
-        assert!(list.add(1, 'y'));
+        list.add(1, 'y');
         check(&list, "xyabcde");
 
-        assert!(list.add(7, 'B'));
+        list.add(7, 'B');
         check(&list, "xyabcdeB");
 
-        assert!(list.add(7, 'A'));
+        list.add(7, 'A');
         check(&list, "xyabcdeAB");
 
         assert_eq!(list.remove(2), Some('a'));
@@ -215,48 +862,337 @@ mod tests {
 
         assert_eq!(list.remove(0), Some('a'));
         check(&list, "");
-        assert!(
-            list.dummy_head
-                .borrow()
-                .next
-                .as_ref()
-                .unwrap()
-                .borrow()
-                .next
-                .is_none()
-        );
-        assert!(
-            list.dummy_tail
-                .borrow()
-                .prev
-                .as_ref()
-                .unwrap()
-                .upgrade()
-                .unwrap()
-                .borrow()
-                .prev
-                .is_none()
-        );
+        unsafe {
+            assert_eq!(Node::next(list.dummy), list.dummy);
+            assert_eq!(Node::prev(list.dummy), list.dummy);
+        }
     }
 
     #[test]
+    #[should_panic(expected = "index out of bounds")]
     fn add_to_out_of_bound() {
         let mut list = DLList::new();
         let initial = "abcde";
+        setup(&mut list, initial);
+        list.add(initial.len() + 1, 'Z');
+    }
+
+    #[test]
+    fn cursor_move() {
+        let mut list = DLList::new();
+        setup(&mut list, "abc");
+
+        let mut cursor = list.cursor_front();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.current(), Some(&'a'));
+        assert_eq!(cursor.peek_prev(), None);
+        assert_eq!(cursor.peek_next(), Some(&'b'));
+
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&'c'));
+
+        cursor.move_next();
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_prev(), Some(&'c'));
+        assert_eq!(cursor.peek_next(), Some(&'a'));
+
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&'a'));
+
+        cursor.move_prev();
+        assert_eq!(cursor.index(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&'c'));
+
+        let cursor = list.cursor_back();
+        assert_eq!(cursor.current(), Some(&'c'));
+        let cursor = list.cursor_at(1);
+        assert_eq!(cursor.current(), Some(&'b'));
+        let cursor = list.cursor_at(3);
+        assert_eq!(cursor.current(), None);
+    }
+
+    #[test]
+    fn cursor_on_empty() {
+        let mut list = DLList::<char>::new();
+
+        let mut cursor = list.cursor_front();
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.current(), None);
+        cursor.move_next();
+        assert_eq!(cursor.index(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.peek_next(), None);
+        assert_eq!(cursor.peek_prev(), None);
+
+        let mut cursor = list.cursor_back_mut();
+        assert_eq!(cursor.remove_current(), None);
+        assert_eq!(cursor.split_before().size(), 0);
+        assert_eq!(cursor.split_after().size(), 0);
+        cursor.insert_after('b');
+        cursor.insert_before('c');
+        cursor.insert_after('a');
+        check(&list, "abc");
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn cursor_out_of_bound() {
+        let list = DLList::<char>::new();
+        list.cursor_at(1);
+    }
+
+    #[test]
+    fn cursor_mut_edit() {
+        let mut list = DLList::new();
+        setup(&mut list, "abcd");
+
+        let mut cursor = list.cursor_at_mut(1);
+        *cursor.current().unwrap() = 'B';
+        cursor.insert_before('x');
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 'B'));
+        cursor.insert_after('y');
+        assert_eq!(cursor.peek_next(), Some(&mut 'y'));
+        assert_eq!(cursor.peek_prev(), Some(&mut 'x'));
+        assert_eq!(cursor.as_cursor().current(), Some(&'B'));
+        check(&list, "axBycd");
+
+        let mut cursor = list.cursor_at_mut(2);
+        assert_eq!(cursor.remove_current(), Some('B'));
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 'y'));
+        check(&list, "axycd");
+
+        let mut cursor = list.cursor_back_mut();
+        assert_eq!(cursor.remove_current(), Some('d'));
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.remove_current(), None);
+        check(&list, "axyc");
+    }
+
+    #[test]
+    fn cursor_split_before() {
+        let mut list = DLList::new();
         setup(&mut list, "abcde");
 
-        for i in initial.len() + 1..100 {
-            assert!(!list.add(i, 'Z'));
+        let mut cursor = list.cursor_at_mut(2);
+        let front = cursor.split_before();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.current(), Some(&mut 'c'));
+        check(&front, "ab");
+        check(&list, "cde");
+
+        let mut cursor = list.cursor_front_mut();
+        assert_eq!(cursor.split_before().size(), 0);
+        cursor.move_prev();
+        let all = cursor.split_before();
+        check(&all, "cde");
+        check(&list, "");
+    }
+
+    #[test]
+    fn cursor_split_after() {
+        let mut list = DLList::new();
+        setup(&mut list, "abcde");
+
+        let mut cursor = list.cursor_at_mut(2);
+        let mut back = cursor.split_after();
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 'c'));
+        check(&back, "de");
+        check(&list, "abc");
+
+        back.add(2, 'f');
+        check(&back, "def");
+
+        let mut cursor = list.cursor_back_mut();
+        assert_eq!(cursor.split_after().size(), 0);
+        cursor.move_next();
+        let all = cursor.split_after();
+        check(&all, "abc");
+        check(&list, "");
+    }
+
+    #[test]
+    fn set_out_of_bound() {
+        let mut list = DLList::new();
+        setup(&mut list, "abc");
+        assert_eq!(list.set(3, 'x'), None);
+        assert_eq!(list.get(3), None);
+        check(&list, "abc");
+    }
+
+    #[test]
+    fn iter() {
+        let mut list = DLList::new();
+        setup(&mut list, "abcd");
+
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next(), Some(&'a'));
+        assert_eq!(iter.next_back(), Some(&'d'));
+        assert_eq!(iter.next_back(), Some(&'c'));
+        assert_eq!(iter.next(), Some(&'b'));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        assert_eq!((&list).into_iter().rev().collect::<String>(), "dcba");
+        assert_eq!(DLList::<char>::new().iter().next(), None);
+    }
+
+    #[test]
+    fn iter_mut() {
+        let mut list = DLList::new();
+        setup(&mut list, "abcd");
+        for c in list.iter_mut() {
+            *c = c.to_ascii_uppercase();
         }
+        check(&list, "ABCD");
+
+        for c in (&mut list).into_iter().rev().take(2) {
+            *c = c.to_ascii_lowercase();
+        }
+        check(&list, "ABcd");
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut list = DLList::new();
+        setup(&mut list, "abcd");
+        let mut iter = list.into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next_back(), Some('d'));
+        assert_eq!(iter.collect::<String>(), "abc");
+    }
+
+    #[test]
+    fn debug() {
+        let mut list = DLList::new();
+        setup(&mut list, "ab");
+        assert_eq!(format!("{list:?}"), "['a', 'b']");
     }
 
     #[test]
-    #[should_panic(expected = "already borrowed: BorrowMutError")]
-    fn add_before_with_node_borrowed_as_mut_should_panic() {
+    fn drop_elements() {
+        let x = std::rc::Rc::new(());
+
         let mut list = DLList::new();
-        let node = list.get_node(0).unwrap();
-        let node2 = node.clone();
-        let _mut_node = node2.borrow_mut();
-        list.add_before(Some(node), 'x');
+        for i in 0..10 {
+            list.add(i, x.clone());
+        }
+        assert_eq!(std::rc::Rc::strong_count(&x), 11);
+
+        drop(list.remove(3));
+        drop(list.set(3, x.clone()));
+        assert_eq!(std::rc::Rc::strong_count(&x), 10);
+
+        let mut cursor = list.cursor_at_mut(4);
+        let back = cursor.split_after();
+        drop(cursor.remove_current());
+        assert_eq!(std::rc::Rc::strong_count(&x), 9);
+
+        let mut iter = back.into_iter();
+        iter.next();
+        drop(iter);
+        drop(list);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+    }
+
+    #[test]
+    fn clear() {
+        let x = std::rc::Rc::new(());
+        let mut list = DLList::new();
+        for i in 0..10 {
+            list.add(i, x.clone());
+        }
+        list.clear();
+        assert_eq!(list.size(), 0);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+        list.add(0, x.clone());
+        assert_eq!(list.size(), 1);
+    }
+
+    #[test]
+    fn bulk_operations() {
+        let mut rng = crate::util::Rng::with_seed(25);
+        let mut list = DLList::new();
+        let mut expected = Vec::new();
+        let steps = if cfg!(miri) { 60 } else { 300 };
+        for _ in 0..steps {
+            let n = expected.len();
+            let i = rng.next_u64() as usize % (n + 1);
+            let xs = (0..rng.next_u64() % 8).map(|x| x * 10).collect::<Vec<_>>();
+            match rng.next_u64() % 4 {
+                0 => {
+                    list.insert_many(i, xs.clone());
+                    expected.splice(i..i, xs);
+                }
+                1 => {
+                    let j = i + rng.next_u64() as usize % (n - i + 1);
+                    assert!(list.drain(i..j).eq(expected.drain(i..j)));
+                }
+                2 => {
+                    let mut tail = list.split_off(i);
+                    assert!(tail.iter().eq(&expected[i..]));
+                    tail.extend(xs.clone());
+                    list.append(&mut tail);
+                    assert_eq!(tail.size(), 0);
+                    expected.extend(xs);
+                }
+                _ => {
+                    list.extend(xs.clone());
+                    expected.extend(xs);
+                }
+            }
+            assert_eq!(list.size(), expected.len());
+            assert!(list.iter().eq(&expected));
+            assert!(list.iter().rev().eq(expected.iter().rev()));
+        }
+    }
+
+    #[test]
+    fn splice_drops_elements() {
+        let x = std::rc::Rc::new(());
+        let mut list = DLList::new();
+        list.insert_many(0, std::iter::repeat_n(x.clone(), 6));
+        let mut other = list.split_off(2);
+        drop(other.drain(1..3));
+        list.append(&mut other);
+        assert_eq!(list.size(), 4);
+        drop(other);
+        assert_eq!(std::rc::Rc::strong_count(&x), 5);
+        drop(list);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a: DLList<char> = "abc".chars().collect();
+        let mut b = DLList::default();
+        setup(&mut b, "abc");
+        assert_eq!(a, b);
+        assert_eq!(crate::util::hash_code(&a), crate::util::hash_code(&b));
+        assert_eq!(format!("{a:?}"), "['a', 'b', 'c']");
+
+        let mut c = a.clone();
+        c[2] = 'x';
+        check(&c, "abx");
+        check(&a, "abc");
+        assert!(a < c);
+        c.extend("yz".chars());
+        assert_eq!(c[4], 'z');
+    }
+
+    #[test]
+    #[should_panic(expected = "the size is 0 but the index is 0")]
+    fn index_out_of_bounds() {
+        let list = DLList::<char>::new();
+        let _ = list[0];
     }
 }
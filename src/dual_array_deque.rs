@@ -1,26 +1,68 @@
-use crate::ArrayStack;
-
-#[derive(Debug)]
-pub struct DualArrayDeque<T> {
-    front: ArrayStack<T>,
-    back: ArrayStack<T>,
+use std::{
+    cmp::Ordering,
+    collections::TryReserveError,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::{Index, IndexMut, RangeBounds},
+};
+
+use crate::{ArrayStack, Doubling, Error, GrowthPolicy, array_stack, error};
+
+#[derive(Clone)]
+pub struct DualArrayDeque<T, P = Doubling> {
+    front: ArrayStack<T, P>,
+    back: ArrayStack<T, P>,
 }
 
 impl<T> DualArrayDeque<T> {
     pub fn new(length: usize) -> Self {
+        Self::with_policy(length, Doubling)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_policy(capacity, Doubling)
+    }
+
+    pub fn try_with_capacity(length: usize) -> Result<Self, TryReserveError> {
+        Self::try_with_policy(length, Doubling)
+    }
+}
+
+// Both stacks follow the policy, and so do the arrays a rebalance moves the
+// elements into.
+impl<T, P: GrowthPolicy + Clone> DualArrayDeque<T, P> {
+    pub fn with_policy(length: usize, policy: P) -> Self {
         let nf = length / 2;
         let nb = length - nf;
 
         Self {
-            front: ArrayStack::new(nf),
-            back: ArrayStack::new(nb),
+            front: ArrayStack::with_policy(nf, policy.clone()),
+            back: ArrayStack::with_policy(nb, policy),
         }
     }
 
+    pub fn try_with_policy(length: usize, policy: P) -> Result<Self, TryReserveError> {
+        let nf = length / 2;
+        let nb = length - nf;
+
+        Ok(Self {
+            front: ArrayStack::try_with_policy(nf, policy.clone())?,
+            back: ArrayStack::try_with_policy(nb, policy)?,
+        })
+    }
+
+    pub fn policy(&self) -> &P {
+        self.front.policy()
+    }
+
     pub fn length(&self) -> usize {
         self.front.length() + self.back.length()
     }
 
+    pub fn capacity(&self) -> usize {
+        self.front.capacity() + self.back.capacity()
+    }
+
     pub fn size(&self) -> usize {
         self.front.size() + self.back.size()
     }
@@ -33,6 +75,29 @@ impl<T> DualArrayDeque<T> {
         }
     }
 
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        let m = self.front.size();
+        if i < m {
+            self.front.get_mut(m - i - 1)
+        } else {
+            self.back.get_mut(i - m)
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            front: self.front.iter().rev(),
+            back: self.back.iter(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            front: self.front.iter_mut().rev(),
+            back: self.back.iter_mut(),
+        }
+    }
+
     pub(crate) fn take(&mut self, i: usize) -> Option<T> {
         if i < self.front.size() {
             self.front.take(self.front.size() - i - 1)
@@ -41,15 +106,16 @@ impl<T> DualArrayDeque<T> {
         }
     }
 
-    pub fn set(&mut self, i: usize, x: T) {
+    pub fn set(&mut self, i: usize, x: T) -> Option<T> {
         if i < self.front.size() {
-            self.front.set(self.front.size() - i - 1, x);
+            self.front.set(self.front.size() - i - 1, x)
         } else {
-            self.back.set(i - self.front.size(), x);
+            self.back.set(i - self.front.size(), x)
         }
     }
 
     pub fn add(&mut self, i: usize, x: T) {
+        error::assert_insert(i, self.size());
         if i < self.front.size() {
             self.front.add(self.front.size() - i, x);
         } else {
@@ -59,6 +125,9 @@ impl<T> DualArrayDeque<T> {
     }
 
     pub fn remove(&mut self, i: usize) -> Option<T> {
+        if i >= self.size() {
+            return None;
+        }
         let x = if i < self.front.size() {
             self.front.remove(self.front.size() - i - 1)
         } else {
@@ -68,27 +137,395 @@ impl<T> DualArrayDeque<T> {
         x
     }
 
+    pub fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.back.extend(iter);
+        self.balance();
+    }
+
+    // The front array holds its elements in reverse, so elements going in
+    // there are reversed first.
+    pub fn insert_many<I: IntoIterator<Item = T>>(&mut self, i: usize, iter: I) {
+        error::assert_insert(i, self.size());
+        let nf = self.front.size();
+        if i < nf {
+            let xs = iter.into_iter().collect::<Vec<_>>();
+            self.front.insert_many(nf - i, xs.into_iter().rev());
+        } else {
+            self.back.insert_many(i - nf, iter);
+        }
+        self.balance();
+    }
+
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> IntoIter<T> {
+        let r = error::assert_range(range, self.size());
+        let nf = self.front.size();
+        let (fs, fe) = (r.start.min(nf), r.end.min(nf));
+        let (bs, be) = (r.start.max(nf) - nf, r.end.max(nf) - nf);
+        let iter = IntoIter {
+            front: self.front.drain(nf - fe..nf - fs).rev(),
+            back: self.back.drain(bs..be),
+        };
+        self.balance();
+        iter
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        self.back.reserve(other.size());
+        self.back.extend(other.front.drain(..).rev());
+        self.back.append(&mut other.back);
+        self.balance();
+        other.balance();
+    }
+
+    pub fn split_off(&mut self, i: usize) -> Self {
+        error::assert_insert(i, self.size());
+        let nf = self.front.size();
+        let mut other = Self::with_policy(0, self.policy().clone());
+        if i < nf {
+            // The bottom of the front array is the end of its elements.
+            let head = self.front.split_off(nf - i);
+            other.front = std::mem::replace(&mut self.front, head);
+            std::mem::swap(&mut other.back, &mut self.back);
+        } else {
+            other.back = self.back.split_off(i - nf);
+        }
+        self.balance();
+        other.balance();
+        other
+    }
+
+    // Fails without changing the deque, dropping `x`, if an array cannot be
+    // allocated. The arrays for a rebalance are allocated before anything
+    // moves.
+    pub fn try_add(&mut self, i: usize, x: T) -> Result<(), Error> {
+        error::check_insert(i, self.size())?;
+        let (nf, nb) = if i < self.front.size() {
+            (self.front.size() + 1, self.back.size())
+        } else {
+            (self.front.size(), self.back.size() + 1)
+        };
+        let arrays = if unbalanced(nf, nb) {
+            let n = nf + nb;
+            Some((
+                crate::util::try_allocate(self.policy().grow(n / 2))?,
+                crate::util::try_allocate(self.policy().grow(n - n / 2))?,
+            ))
+        } else {
+            None
+        };
+        if i < self.front.size() {
+            self.front.try_add(self.front.size() - i, x)?;
+        } else {
+            self.back.try_add(i - self.front.size(), x)?;
+        }
+        if let Some((af, ab)) = arrays {
+            self.rebalance(af, ab);
+        }
+        Ok(())
+    }
+
+    // Makes room for `additional` more elements in each array, since any of
+    // them may end up in either.
+    pub fn reserve(&mut self, additional: usize) {
+        self.front.reserve(additional);
+        self.back.reserve(additional);
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.front.try_reserve(additional)?;
+        self.back.try_reserve(additional)
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.front.shrink_to_fit();
+        self.back.shrink_to_fit();
+    }
+
+    pub fn clear(&mut self) {
+        self.front.clear();
+        self.back.clear();
+    }
+
     fn balance(&mut self) {
-        if !(3 * self.front.size() < self.back.size() || 3 * self.back.size() < self.front.size()) {
+        if !unbalanced(self.front.size(), self.back.size()) {
             return;
         }
 
+        let nf = self.size() / 2;
+        let nb = self.size() - nf;
+        let af = crate::util::allocate(self.policy().grow(nf));
+        let ab = crate::util::allocate(self.policy().grow(nb));
+        self.rebalance(af, ab);
+    }
+
+    // Moves the first half of the elements into `af` and the rest into `ab`.
+    fn rebalance(&mut self, mut af: Box<[Option<T>]>, mut ab: Box<[Option<T>]>) {
         let n = self.size();
 
         let nf = n / 2;
-        let mut af = crate::util::allocate(std::cmp::max(2 * nf, 1));
         for i in 0..nf {
             af[nf - i - 1] = self.take(i);
         }
 
         let nb = n - nf;
-        let mut ab = crate::util::allocate(std::cmp::max(2 * nb, 1));
         for i in 0..nb {
             ab[i] = self.take(nf + i);
         }
 
-        self.front = ArrayStack::from_raw(af, nf);
-        self.back = ArrayStack::from_raw(ab, nb);
+        let policy = self.policy().clone();
+        self.front = ArrayStack::from_raw(af, nf, policy.clone());
+        self.back = ArrayStack::from_raw(ab, nb, policy);
+    }
+}
+
+fn unbalanced(nf: usize, nb: usize) -> bool {
+    3 * nf < nb || 3 * nb < nf
+}
+
+pub struct Iter<'a, T> {
+    front: std::iter::Rev<array_stack::Iter<'a, T>>,
+    back: array_stack::Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.front.len() + self.back.len();
+        (n, Some(n))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.back.next_back().or_else(|| self.front.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    front: std::iter::Rev<array_stack::IterMut<'a, T>>,
+    back: array_stack::IterMut<'a, T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.front.len() + self.back.len();
+        (n, Some(n))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.back.next_back().or_else(|| self.front.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+pub struct IntoIter<T> {
+    front: std::iter::Rev<array_stack::IntoIter<T>>,
+    back: array_stack::IntoIter<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.front.len() + self.back.len();
+        (n, Some(n))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.back.next_back().or_else(|| self.front.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T, P> IntoIterator for DualArrayDeque<T, P> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            front: self.front.into_iter().rev(),
+            back: self.back.into_iter(),
+        }
+    }
+}
+
+impl<'a, T, P: GrowthPolicy + Clone> IntoIterator for &'a DualArrayDeque<T, P> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, P: GrowthPolicy + Clone> IntoIterator for &'a mut DualArrayDeque<T, P> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, P: GrowthPolicy + Clone + Default> Default for DualArrayDeque<T, P> {
+    fn default() -> Self {
+        Self::with_policy(0, P::default())
+    }
+}
+
+impl<T: fmt::Debug, P: GrowthPolicy + Clone> fmt::Debug for DualArrayDeque<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, P: GrowthPolicy + Clone> PartialEq for DualArrayDeque<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, P: GrowthPolicy + Clone> Eq for DualArrayDeque<T, P> {}
+
+impl<T: PartialOrd, P: GrowthPolicy + Clone> PartialOrd for DualArrayDeque<T, P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord, P: GrowthPolicy + Clone> Ord for DualArrayDeque<T, P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash, P: GrowthPolicy + Clone> Hash for DualArrayDeque<T, P> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.size());
+        self.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl<T, P: GrowthPolicy + Clone + Default> FromIterator<T> for DualArrayDeque<T, P> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut c = Self::default();
+        c.extend(iter);
+        c
+    }
+}
+
+impl<T, P: GrowthPolicy + Clone> Extend<T> for DualArrayDeque<T, P> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        DualArrayDeque::extend(self, iter)
+    }
+}
+
+impl<T, P: GrowthPolicy + Clone> Index<usize> for DualArrayDeque<T, P> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        self.get(i)
+            .unwrap_or_else(|| error::index_out_of_bounds(i, self.size()))
+    }
+}
+
+impl<T, P: GrowthPolicy + Clone> IndexMut<usize> for DualArrayDeque<T, P> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        let n = self.size();
+        self.get_mut(i)
+            .unwrap_or_else(|| error::index_out_of_bounds(i, n))
+    }
+}
+
+impl<T, P: GrowthPolicy + Clone> crate::List<T> for DualArrayDeque<T, P> {
+    fn size(&self) -> usize {
+        DualArrayDeque::size(self)
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        DualArrayDeque::get(self, i)
+    }
+
+    fn set(&mut self, i: usize, x: T) -> Option<T> {
+        DualArrayDeque::set(self, i, x)
+    }
+
+    fn add(&mut self, i: usize, x: T) {
+        DualArrayDeque::add(self, i, x)
+    }
+
+    fn remove(&mut self, i: usize) -> Option<T> {
+        DualArrayDeque::remove(self, i)
+    }
+}
+
+impl<T, P: GrowthPolicy + Clone> crate::Deque<T> for DualArrayDeque<T, P> {
+    fn size(&self) -> usize {
+        DualArrayDeque::size(self)
+    }
+
+    fn add_first(&mut self, x: T) {
+        self.add(0, x)
+    }
+
+    fn add_last(&mut self, x: T) {
+        self.add(self.size(), x)
+    }
+
+    fn remove_first(&mut self) -> Option<T> {
+        if self.size() == 0 {
+            return None;
+        }
+        self.remove(0)
+    }
+
+    fn remove_last(&mut self) -> Option<T> {
+        if self.size() == 0 {
+            return None;
+        }
+        self.remove(self.size() - 1)
+    }
+}
+
+impl<T, P: GrowthPolicy + Clone> crate::sort::Sortable<T> for DualArrayDeque<T, P> {
+    fn size(&self) -> usize {
+        DualArrayDeque::size(self)
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        DualArrayDeque::get(self, i)
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        if i != j {
+            let x = self.take(i).unwrap();
+            let y = self.take(j).unwrap();
+            self.set(i, y);
+            self.set(j, x);
+        }
     }
 }
 
@@ -221,4 +658,162 @@ mod tests {
         deque.remove(0);
         check(&deque, "bc", 4, "xyd", 6);
     }
+
+    #[test]
+    fn iter() {
+        let deque = create("ab", 5, "cd", 5);
+
+        let mut iter = deque.iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next_back(), Some(&'d'));
+        assert_eq!(iter.next_back(), Some(&'c'));
+        assert_eq!(iter.next_back(), Some(&'b'));
+        assert_eq!(iter.next(), Some(&'a'));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        assert_eq!((&deque).into_iter().collect::<String>(), "abcd");
+        assert_eq!(create("", 5, "abc", 5).iter().collect::<String>(), "abc");
+        assert_eq!(create("abc", 5, "", 5).iter().collect::<String>(), "abc");
+    }
+
+    #[test]
+    fn iter_mut() {
+        let mut deque = create("ab", 5, "cd", 5);
+        for c in deque.iter_mut() {
+            *c = c.to_ascii_uppercase();
+        }
+        check(&deque, "AB", 5, "CD", 5);
+
+        for c in &mut deque {
+            *c = c.to_ascii_lowercase();
+        }
+        check(&deque, "ab", 5, "cd", 5);
+    }
+
+    #[test]
+    fn into_iter() {
+        let deque = create("ab", 5, "cd", 5);
+        let mut iter = deque.into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next_back(), Some('d'));
+        assert_eq!(iter.collect::<String>(), "abc");
+    }
+
+    #[test]
+    fn try_reserve() {
+        let mut deque = DualArrayDeque::try_with_capacity(4).unwrap();
+        let mut expected = DualArrayDeque::new(4);
+        assert_eq!(deque.length(), 4);
+        for (i, c) in "abcdefg".chars().enumerate() {
+            deque.try_add(i / 2, c).unwrap();
+            expected.add(i / 2, c);
+            assert_eq!(deque.front.length(), expected.front.length());
+            assert_eq!(deque.back.length(), expected.back.length());
+            assert!(deque.iter().eq(expected.iter()));
+        }
+        check(&deque, "bd", 4, "fgeca", 6);
+        deque.try_reserve(5).unwrap();
+        check(&deque, "bd", 7, "fgeca", 10);
+
+        assert!(deque.try_reserve(usize::MAX).is_err());
+        check(&deque, "bd", 7, "fgeca", 10);
+        assert!(DualArrayDeque::<char>::try_with_capacity(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn increment() {
+        let mut rng = crate::util::Rng::with_seed(3);
+        let mut deque = DualArrayDeque::with_policy(0, crate::Increment::new(3));
+        let mut expected = std::collections::VecDeque::new();
+        for _ in 0..1000 {
+            let i = rng.next_u64() as usize % (expected.len() + 1);
+            if rng.next_u64() % 5 < 3 {
+                deque.add(i, i);
+                expected.insert(i, i);
+            } else {
+                assert_eq!(deque.remove(i), expected.remove(i));
+            }
+            // Neither array is ever more than twice the increment too long.
+            assert!(deque.front.length() - deque.front.size() < 6);
+            assert!(deque.back.length() - deque.back.size() < 6);
+        }
+        assert!(deque.iter().eq(expected.iter()));
+    }
+
+    #[test]
+    fn capacity() {
+        let mut deque = create("ab", 4, "cde", 4);
+        deque.reserve(3);
+        check(&deque, "ab", 5, "cde", 6);
+        assert_eq!(deque.capacity(), 11);
+        deque.shrink_to_fit();
+        check(&deque, "ab", 2, "cde", 3);
+
+        deque.clear();
+        check(&deque, "", 2, "", 3);
+        deque.add(0, 'x');
+        check(&deque, "", 1, "x", 2);
+    }
+
+    #[test]
+    fn bulk_operations() {
+        let mut rng = crate::util::Rng::with_seed(23);
+        let mut list = DualArrayDeque::new(0);
+        let mut expected = Vec::new();
+        for _ in 0..300 {
+            let n = expected.len();
+            let i = rng.next_u64() as usize % (n + 1);
+            let xs = (0..rng.next_u64() % 8).map(|x| x * 10).collect::<Vec<_>>();
+            match rng.next_u64() % 4 {
+                0 => {
+                    list.insert_many(i, xs.clone());
+                    expected.splice(i..i, xs);
+                }
+                1 => {
+                    let j = i + rng.next_u64() as usize % (n - i + 1);
+                    assert!(list.drain(i..j).eq(expected.drain(i..j)));
+                }
+                2 => {
+                    let mut tail = list.split_off(i);
+                    assert!(tail.iter().eq(&expected[i..]));
+                    tail.extend(xs.clone());
+                    list.append(&mut tail);
+                    assert_eq!(tail.size(), 0);
+                    expected.extend(xs);
+                }
+                _ => {
+                    list.extend(xs.clone());
+                    expected.extend(xs);
+                }
+            }
+            assert_eq!(list.size(), expected.len());
+            assert!(list.iter().eq(&expected));
+            assert!(!unbalanced(list.front.size(), list.back.size()));
+        }
+    }
+
+    #[test]
+    fn standard_traits() {
+        let mut back = ArrayStack::new(5);
+        back.extend("abcd".chars());
+        let a = DualArrayDeque {
+            front: ArrayStack::new(5),
+            back,
+        };
+        let b: DualArrayDeque<char> = "abcd".chars().collect();
+        assert_ne!(a.front.size(), b.front.size());
+        assert_eq!(a, b);
+        assert_eq!(crate::util::hash_code(&a), crate::util::hash_code(&b));
+        assert_eq!(format!("{a:?}"), "['a', 'b', 'c', 'd']");
+
+        let mut c = a.clone();
+        c[0] = 'x';
+        c[3] = 'y';
+        assert_eq!(c.iter().collect::<String>(), "xbcy");
+        assert!(a < c);
+        c.extend("z".chars());
+        assert_eq!(c[4], 'z');
+        assert_eq!(DualArrayDeque::<char>::default().size(), 0);
+    }
 }
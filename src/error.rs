@@ -0,0 +1,142 @@
+use std::{
+    collections::TryReserveError,
+    fmt,
+    ops::{Bound, Range, RangeBounds},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    IndexOutOfBounds { index: usize, size: usize },
+    Alloc(TryReserveError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IndexOutOfBounds { index, size } => {
+                write!(
+                    f,
+                    "index out of bounds: the size is {size} but the index is {index}"
+                )
+            }
+            Self::Alloc(e) => write!(f, "allocation failed: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::IndexOutOfBounds { .. } => None,
+            Self::Alloc(e) => Some(e),
+        }
+    }
+}
+
+impl From<TryReserveError> for Error {
+    fn from(e: TryReserveError) -> Self {
+        Self::Alloc(e)
+    }
+}
+
+// The check behind every `add(i, x)`, which may insert at `0..=size`.
+pub(crate) fn check_insert(index: usize, size: usize) -> Result<(), Error> {
+    if index > size {
+        return Err(Error::IndexOutOfBounds { index, size });
+    }
+    Ok(())
+}
+
+// Panics with the message of `check_insert`'s error.
+#[track_caller]
+pub(crate) fn assert_insert(index: usize, size: usize) {
+    if let Err(e) = check_insert(index, size) {
+        panic!("{e}");
+    }
+}
+
+// The panic of `Index` and `IndexMut` when there is no element at `index`.
+#[cold]
+#[track_caller]
+pub(crate) fn index_out_of_bounds(index: usize, size: usize) -> ! {
+    panic!("{}", Error::IndexOutOfBounds { index, size })
+}
+
+// Resolves `range` against a structure of `size` elements for the bulk
+// operations, panicking as slice indexing does if it does not fit.
+#[track_caller]
+pub(crate) fn assert_range<R: RangeBounds<usize>>(range: R, size: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&i) => i,
+        Bound::Excluded(&i) => i.checked_add(1).expect("range start overflows"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&i) => i.checked_add(1).expect("range end overflows"),
+        Bound::Excluded(&i) => i,
+        Bound::Unbounded => size,
+    };
+    assert!(start <= end, "range starts at {start} but ends at {end}");
+    assert!(
+        end <= size,
+        "range end {end} is out of bounds for size {size}"
+    );
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_insert_bounds() {
+        assert_eq!(check_insert(0, 0), Ok(()));
+        assert_eq!(check_insert(3, 3), Ok(()));
+        assert_eq!(
+            check_insert(4, 3),
+            Err(Error::IndexOutOfBounds { index: 4, size: 3 })
+        );
+    }
+
+    #[test]
+    fn display() {
+        let e = Error::IndexOutOfBounds { index: 4, size: 3 };
+        assert_eq!(
+            e.to_string(),
+            "index out of bounds: the size is 3 but the index is 4"
+        );
+        assert!(std::error::Error::source(&e).is_none());
+
+        let e = Error::from(Vec::<u8>::new().try_reserve(usize::MAX).unwrap_err());
+        assert!(e.to_string().starts_with("allocation failed: "));
+        assert!(std::error::Error::source(&e).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds: the size is 0 but the index is 1")]
+    fn assert_insert_panics() {
+        assert_insert(1, 0);
+    }
+
+    #[test]
+    fn assert_range_bounds() {
+        assert_eq!(assert_range(.., 4), 0..4);
+        assert_eq!(assert_range(1..=2, 4), 1..3);
+        assert_eq!(assert_range(4.., 4), 4..4);
+        let bounds = (Bound::Excluded(0), Bound::Included(3));
+        assert_eq!(assert_range(bounds, 4), 1..4);
+    }
+
+    #[test]
+    #[should_panic(expected = "range end 5 is out of bounds for size 4")]
+    fn assert_range_end() {
+        assert_range(2..5, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "range starts at 3 but ends at 2")]
+    fn assert_range_order() {
+        #[allow(clippy::reversed_empty_ranges)]
+        assert_range(3..2, 4);
+    }
+}
@@ -0,0 +1,161 @@
+// How an array-backed structure sizes its backing array. `grow` is asked
+// for a new length when an array of `n` elements has no room for another,
+// and `shrink` is asked after every removal whether an array of `length`
+// holding `n` elements should be reallocated, and to what length.
+pub trait GrowthPolicy {
+    fn grow(&self, n: usize) -> usize;
+    fn shrink(&self, n: usize, length: usize) -> Option<usize>;
+}
+
+// Doubles a full array and halves one that is at least three times longer
+// than it needs to be, which keeps resizing at amortized O(1) per operation.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Doubling;
+
+impl GrowthPolicy for Doubling {
+    fn grow(&self, n: usize) -> usize {
+        std::cmp::max(2 * n, 1)
+    }
+
+    fn shrink(&self, n: usize, length: usize) -> Option<usize> {
+        (length >= 3 * n).then(|| self.grow(n))
+    }
+}
+
+// Grows by half again, trading more frequent copies for less slack.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct HalfAgain;
+
+impl GrowthPolicy for HalfAgain {
+    fn grow(&self, n: usize) -> usize {
+        n + std::cmp::max(n / 2, 1)
+    }
+
+    fn shrink(&self, n: usize, length: usize) -> Option<usize> {
+        (length >= 2 * n && self.grow(n) < length).then(|| self.grow(n))
+    }
+}
+
+// Grows by a fixed number of slots, so at most that many are ever unused.
+// Resizing is no longer amortized O(1): adding m elements copies O(m^2 / k).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Increment(usize);
+
+impl Increment {
+    pub fn new(k: usize) -> Self {
+        assert!(k > 0, "increment must be positive");
+        Self(k)
+    }
+}
+
+impl GrowthPolicy for Increment {
+    fn grow(&self, n: usize) -> usize {
+        n.saturating_add(self.0)
+    }
+
+    fn shrink(&self, n: usize, length: usize) -> Option<usize> {
+        (length - n >= 2 * self.0).then(|| self.grow(n))
+    }
+}
+
+// Grows as `P` does but never gives memory back, so a removal never pays
+// for a copy.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NeverShrink<P = Doubling>(pub P);
+
+impl<P: GrowthPolicy> GrowthPolicy for NeverShrink<P> {
+    fn grow(&self, n: usize) -> usize {
+        self.0.grow(n)
+    }
+
+    fn shrink(&self, _: usize, _: usize) -> Option<usize> {
+        None
+    }
+}
+
+// Grows as `P` does, and shrinks back to what `P` would grow to once the
+// array is `ratio` times longer than its elements. A ratio above `P`'s
+// growth factor is needed for resizing to stay amortized O(1); lower ratios
+// free memory sooner, higher ones resize less often.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hysteresis<P = Doubling> {
+    policy: P,
+    ratio: usize,
+}
+
+impl<P> Hysteresis<P> {
+    pub fn new(policy: P, ratio: usize) -> Self {
+        assert!(ratio > 1, "ratio must be greater than one");
+        Self { policy, ratio }
+    }
+}
+
+impl<P: GrowthPolicy> GrowthPolicy for Hysteresis<P> {
+    fn grow(&self, n: usize) -> usize {
+        self.policy.grow(n)
+    }
+
+    fn shrink(&self, n: usize, length: usize) -> Option<usize> {
+        let m = self.policy.grow(n);
+        (length >= self.ratio.saturating_mul(n) && m < length).then_some(m)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doubling() {
+        assert_eq!(Doubling.grow(0), 1);
+        assert_eq!(Doubling.grow(4), 8);
+        assert_eq!(Doubling.shrink(3, 8), None);
+        assert_eq!(Doubling.shrink(2, 8), Some(4));
+        assert_eq!(Doubling.shrink(0, 1), Some(1));
+    }
+
+    #[test]
+    fn half_again() {
+        assert_eq!(HalfAgain.grow(0), 1);
+        assert_eq!(HalfAgain.grow(1), 2);
+        assert_eq!(HalfAgain.grow(8), 12);
+        assert_eq!(HalfAgain.shrink(7, 12), None);
+        assert_eq!(HalfAgain.shrink(6, 12), Some(9));
+        assert_eq!(HalfAgain.shrink(0, 1), None);
+    }
+
+    #[test]
+    fn increment() {
+        let p = Increment::new(4);
+        assert_eq!(p.grow(0), 4);
+        assert_eq!(p.grow(100), 104);
+        assert_eq!(p.shrink(93, 100), None);
+        assert_eq!(p.shrink(92, 100), Some(96));
+    }
+
+    #[test]
+    #[should_panic(expected = "increment must be positive")]
+    fn increment_zero() {
+        Increment::new(0);
+    }
+
+    #[test]
+    fn never_shrink() {
+        let p = NeverShrink(HalfAgain);
+        assert_eq!(p.grow(8), 12);
+        assert_eq!(p.shrink(0, 100), None);
+    }
+
+    #[test]
+    fn hysteresis() {
+        let p = Hysteresis::new(Doubling, 8);
+        assert_eq!(p.grow(4), 8);
+        assert_eq!(p.shrink(3, 16), None);
+        assert_eq!(p.shrink(2, 16), Some(4));
+        assert_eq!(p.shrink(0, 1), None);
+
+        // A ratio at or below the growth factor would resize the array to
+        // the length it already has.
+        assert_eq!(Hysteresis::new(Doubling, 2).shrink(4, 8), None);
+    }
+}
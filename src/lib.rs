@@ -1,13 +1,57 @@
+mod compare;
+mod error;
+mod growth;
+mod ring;
+mod traits;
+mod unsigned;
 mod util;
 
-mod array_deque;
-mod array_queue;
-mod array_stack;
-mod dual_array_deque;
-mod rootish_array_stack;
+pub mod array_deque;
+pub mod array_queue;
+pub mod array_stack;
+pub mod dual_array_deque;
+pub mod rootish_array_stack;
 
-mod doubly_linked_list;
-mod singly_linked_list;
+pub mod doubly_linked_list;
+pub mod se_list;
+pub mod singly_linked_list;
+
+pub mod skiplist_list;
+pub mod skiplist_sset;
+
+pub mod binary_search_tree;
+pub mod binary_tree;
+pub mod red_black_tree;
+pub mod red_black_tree_map;
+pub mod scapegoat_tree;
+pub mod treap;
+
+pub mod chained_hash_map;
+pub mod chained_hash_table;
+pub mod linear_hash_map;
+pub mod linear_hash_table;
+
+pub mod binary_heap;
+pub mod meldable_heap;
+
+pub mod sort;
+
+pub mod binary_trie;
+pub mod x_fast_trie;
+pub mod y_fast_trie;
+
+pub mod block_store;
+pub mod btree;
+
+pub mod adjacency_lists;
+pub mod adjacency_matrix;
+pub mod traversal;
+
+pub use compare::{Compare, Max, Min};
+pub use error::Error;
+pub use growth::{Doubling, GrowthPolicy, HalfAgain, Hysteresis, Increment, NeverShrink};
+pub use traits::{Deque, Graph, List, Queue, SSet, Stack, USet};
+pub use unsigned::Unsigned;
 
 pub use array_deque::ArrayDeque;
 pub use array_queue::ArrayQueue;
@@ -16,4 +60,33 @@ pub use dual_array_deque::DualArrayDeque;
 pub use rootish_array_stack::RootishArrayStack;
 
 pub use doubly_linked_list::DLList;
+pub use se_list::SEList;
 pub use singly_linked_list::SLList;
+
+pub use skiplist_list::SkiplistList;
+pub use skiplist_sset::SkiplistSSet;
+
+pub use binary_search_tree::BinarySearchTree;
+pub use binary_tree::BinaryTree;
+pub use red_black_tree::RedBlackTree;
+pub use red_black_tree_map::RedBlackTreeMap;
+pub use scapegoat_tree::ScapegoatTree;
+pub use treap::Treap;
+
+pub use chained_hash_map::ChainedHashMap;
+pub use chained_hash_table::ChainedHashTable;
+pub use linear_hash_map::LinearHashMap;
+pub use linear_hash_table::LinearHashTable;
+
+pub use binary_heap::BinaryHeap;
+pub use meldable_heap::MeldableHeap;
+
+pub use binary_trie::BinaryTrie;
+pub use x_fast_trie::XFastTrie;
+pub use y_fast_trie::YFastTrie;
+
+pub use block_store::{BlockStore, Codec, FileStore, MemoryStore};
+pub use btree::BTree;
+
+pub use adjacency_lists::AdjacencyLists;
+pub use adjacency_matrix::AdjacencyMatrix;
@@ -0,0 +1,826 @@
+use std::{
+    borrow::Borrow,
+    fmt,
+    hash::Hash,
+    ops::Index,
+    slice,
+    sync::atomic::{AtomicU64, Ordering::Relaxed},
+    vec,
+};
+
+use crate::util::{Rng, allocate, hash_code};
+
+#[derive(Clone)]
+pub struct LinearHashMap<K, V> {
+    t: Box<[Option<Slot<K, V>>]>,
+    n: usize,
+    q: usize,
+    d: u32,
+    tab: Box<[[u64; 256]; 8]>,
+    stats: Counters,
+}
+
+#[derive(Clone)]
+enum Slot<K, V> {
+    Val(K, V),
+    Del,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ProbeStats {
+    pub searches: u64,
+    pub probes: u64,
+    pub longest: u64,
+}
+
+impl ProbeStats {
+    pub fn average(&self) -> f64 {
+        if self.searches == 0 {
+            0.0
+        } else {
+            self.probes as f64 / self.searches as f64
+        }
+    }
+}
+
+// The counters behind `ProbeStats`. Lookups take `&self`, so they are atomic
+// to keep the map `Sync`; they are only statistics, so `Relaxed` will do.
+#[derive(Default)]
+struct Counters {
+    searches: AtomicU64,
+    probes: AtomicU64,
+    longest: AtomicU64,
+}
+
+impl Counters {
+    fn get(&self) -> ProbeStats {
+        ProbeStats {
+            searches: self.searches.load(Relaxed),
+            probes: self.probes.load(Relaxed),
+            longest: self.longest.load(Relaxed),
+        }
+    }
+
+    fn record(&self, probes: u64) {
+        self.searches.fetch_add(1, Relaxed);
+        self.probes.fetch_add(probes, Relaxed);
+        self.longest.fetch_max(probes, Relaxed);
+    }
+
+    fn reset(&self) {
+        self.searches.store(0, Relaxed);
+        self.probes.store(0, Relaxed);
+        self.longest.store(0, Relaxed);
+    }
+}
+
+impl Clone for Counters {
+    fn clone(&self) -> Self {
+        let stats = self.get();
+        Self {
+            searches: AtomicU64::new(stats.searches),
+            probes: AtomicU64::new(stats.probes),
+            longest: AtomicU64::new(stats.longest),
+        }
+    }
+}
+
+impl<K, V> Default for LinearHashMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for LinearHashMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V> LinearHashMap<K, V> {
+    pub fn new() -> Self {
+        Self::with_rng(Rng::new())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(Rng::with_seed(seed))
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self
+    where
+        K: Hash + Eq,
+    {
+        let mut map = Self::new();
+        map.reserve(capacity);
+        map
+    }
+
+    fn with_rng(mut rng: Rng) -> Self {
+        let mut tab = Box::new([[0; 256]; 8]);
+        for v in tab.iter_mut().flatten() {
+            *v = rng.next_u64();
+        }
+        Self {
+            t: allocate(2),
+            n: 0,
+            q: 0,
+            d: 1,
+            tab,
+            stats: Counters::default(),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    // The size the map can reach before an insertion rebuilds the table.
+    // Every removal leaves a tombstone that counts against this until then.
+    pub fn capacity(&self) -> usize {
+        self.n + self.t.len() / 2 - self.q
+    }
+
+    // Drops the entries and their tombstones, keeping the table.
+    pub fn clear(&mut self) {
+        self.t.fill_with(|| None);
+        self.n = 0;
+        self.q = 0;
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.t.iter(),
+            len: self.n,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            inner: self.t.iter_mut(),
+            len: self.n,
+        }
+    }
+
+    pub fn probe_stats(&self) -> ProbeStats {
+        self.stats.get()
+    }
+
+    pub fn reset_probe_stats(&self) {
+        self.stats.reset();
+    }
+
+    fn pair(&self, i: usize) -> (&K, &V) {
+        match &self.t[i] {
+            Some(Slot::Val(k, v)) => (k, v),
+            _ => unreachable!(),
+        }
+    }
+
+    fn pair_mut(&mut self, i: usize) -> (&K, &mut V) {
+        match &mut self.t[i] {
+            Some(Slot::Val(k, v)) => (k, v),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<K: Hash + Eq, V: PartialEq> PartialEq for LinearHashMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size() == other.size() && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+impl<K: Hash + Eq, V: Eq> Eq for LinearHashMap<K, V> {}
+
+impl<K: Hash + Eq, V> FromIterator<(K, V)> for LinearHashMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut m = Self::new();
+        m.extend(iter);
+        m
+    }
+}
+
+impl<K: Hash + Eq, V> Extend<(K, V)> for LinearHashMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<K, V, Q> Index<&Q> for LinearHashMap<K, V>
+where
+    K: Borrow<Q> + Hash + Eq,
+    Q: Hash + Eq + ?Sized,
+{
+    type Output = V;
+
+    fn index(&self, k: &Q) -> &V {
+        self.get(k).expect("no entry for key")
+    }
+}
+
+impl<K: Hash + Eq, V> LinearHashMap<K, V> {
+    // Tabulation hashing over the eight bytes of the key's hash code.
+    fn hash<Q: Hash + ?Sized>(&self, k: &Q) -> usize {
+        let h = hash_code(k);
+        let z = (0..8).fold(0, |z, r| z ^ self.tab[r][(h >> (8 * r)) as u8 as usize]);
+        (z >> (u64::BITS - self.d)) as usize
+    }
+
+    // Returns the slot holding `k`, or else the first slot the search can put
+    // `k` in, and records the number of slots examined.
+    fn search<Q>(&self, k: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut i = self.hash(k);
+        let mut free = None;
+        let mut probes = 1;
+        let r = loop {
+            match &self.t[i] {
+                None => break Err(free.unwrap_or(i)),
+                Some(Slot::Val(y, _)) if y.borrow() == k => break Ok(i),
+                Some(Slot::Del) if free.is_none() => free = Some(i),
+                Some(_) => {}
+            }
+            i = (i + 1) % self.t.len();
+            probes += 1;
+        };
+        self.stats.record(probes);
+        r
+    }
+
+    fn resize(&mut self) {
+        let mut d = 1;
+        while 1 << d < 3 * self.n {
+            d += 1;
+        }
+        self.rehash(d);
+    }
+
+    fn rehash(&mut self, d: u32) {
+        self.d = d;
+        let old = std::mem::replace(&mut self.t, allocate(1 << self.d));
+        for x in old.into_vec().into_iter().flatten() {
+            if let Slot::Val(k, v) = x {
+                let mut i = self.hash(&k);
+                while self.t[i].is_some() {
+                    i = (i + 1) % self.t.len();
+                }
+                self.t[i] = Some(Slot::Val(k, v));
+            }
+        }
+        self.q = self.n;
+    }
+
+    // Makes room for `additional` more entries. Removals may shrink the
+    // table again, as they would after any rebuild.
+    pub fn reserve(&mut self, additional: usize) {
+        if self.q.saturating_add(additional) <= self.t.len() / 2 {
+            return;
+        }
+        let len = (self.n.checked_add(additional))
+            .and_then(|m| m.checked_mul(2))
+            .and_then(usize::checked_next_power_of_two)
+            .expect("capacity overflow");
+        self.rehash(len.trailing_zeros().max(1));
+    }
+
+    // Rebuilds the table at the size an insertion would, dropping the
+    // tombstones.
+    pub fn shrink_to_fit(&mut self) {
+        self.resize();
+    }
+
+    pub fn get<Q>(&self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_key_value(k).map(|(_, v)| v)
+    }
+
+    pub fn get_key_value<Q>(&self, k: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let i = self.search(k).ok()?;
+        Some(self.pair(i))
+    }
+
+    pub fn get_mut<Q>(&mut self, k: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let i = self.search(k).ok()?;
+        Some(self.pair_mut(i).1)
+    }
+
+    pub fn contains_key<Q>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.search(k).is_ok()
+    }
+
+    pub fn insert(&mut self, k: K, v: V) -> Option<V> {
+        match self.entry(k) {
+            Entry::Occupied(mut e) => Some(e.insert(v)),
+            Entry::Vacant(e) => {
+                e.insert(v);
+                None
+            }
+        }
+    }
+
+    pub fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.remove_entry(k).map(|(_, v)| v)
+    }
+
+    pub fn remove_entry<Q>(&mut self, k: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let i = self.search(k).ok()?;
+        Some(self.remove_at(i))
+    }
+
+    fn remove_at(&mut self, i: usize) -> (K, V) {
+        let x = self.t[i].replace(Slot::Del);
+        self.n -= 1;
+        if 8 * self.n < self.t.len() {
+            self.resize();
+        }
+        match x {
+            Some(Slot::Val(k, v)) => (k, v),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn entry(&mut self, k: K) -> Entry<'_, K, V> {
+        match self.search(&k) {
+            Ok(i) => Entry::Occupied(OccupiedEntry { map: self, i }),
+            Err(i) => Entry::Vacant(VacantEntry { map: self, k, i }),
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn slot_count(&self) -> usize {
+        self.t.len()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn check_invariants(&self) {
+        assert_eq!(self.t.len(), 1 << self.d);
+        assert_eq!(self.t.iter().filter(|x| x.is_some()).count(), self.q);
+        let n = self
+            .t
+            .iter()
+            .filter(|x| matches!(x, Some(Slot::Val(..))))
+            .count();
+        assert_eq!(n, self.n);
+        assert!(2 * self.q <= self.t.len());
+        assert!(self.t.len() <= 2 || 8 * self.n >= self.t.len());
+        for (k, _) in self.iter() {
+            assert!(self.search(k).is_ok());
+        }
+    }
+}
+
+pub enum Entry<'a, K, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+pub struct OccupiedEntry<'a, K, V> {
+    map: &'a mut LinearHashMap<K, V>,
+    i: usize,
+}
+
+pub struct VacantEntry<'a, K, V> {
+    map: &'a mut LinearHashMap<K, V>,
+    k: K,
+    i: usize,
+}
+
+impl<'a, K: Hash + Eq, V> Entry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(e) => e.key(),
+            Entry::Vacant(e) => e.key(),
+        }
+    }
+
+    pub fn or_insert(self, v: V) -> &'a mut V {
+        self.or_insert_with(|| v)
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, f: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(f()),
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(e) = &mut self {
+            f(e.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K: Hash + Eq, V> OccupiedEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        self.map.pair(self.i).0
+    }
+
+    pub fn get(&self) -> &V {
+        self.map.pair(self.i).1
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        self.map.pair_mut(self.i).1
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        self.map.pair_mut(self.i).1
+    }
+
+    pub fn insert(&mut self, v: V) -> V {
+        std::mem::replace(self.get_mut(), v)
+    }
+
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    pub fn remove_entry(self) -> (K, V) {
+        self.map.remove_at(self.i)
+    }
+}
+
+impl<'a, K: Hash + Eq, V> VacantEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.k
+    }
+
+    pub fn into_key(self) -> K {
+        self.k
+    }
+
+    pub fn insert(self, v: V) -> &'a mut V {
+        let map = self.map;
+        let mut i = self.i;
+        if 2 * (map.q + 1) > map.t.len() {
+            map.resize();
+            i = map.hash(&self.k);
+            while map.t[i].is_some() {
+                i = (i + 1) % map.t.len();
+            }
+        }
+        if map.t[i].is_none() {
+            map.q += 1;
+        }
+        map.t[i] = Some(Slot::Val(self.k, v));
+        map.n += 1;
+        map.pair_mut(i).1
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    inner: slice::Iter<'a, Option<Slot<K, V>>>,
+    len: usize,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for x in self.inner.by_ref() {
+            if let Some(Slot::Val(k, v)) = x {
+                self.len -= 1;
+                return Some((k, v));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+pub struct IterMut<'a, K, V> {
+    inner: slice::IterMut<'a, Option<Slot<K, V>>>,
+    len: usize,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for x in self.inner.by_ref() {
+            if let Some(Slot::Val(k, v)) = x {
+                self.len -= 1;
+                return Some((&*k, v));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+pub struct IntoIter<K, V> {
+    inner: vec::IntoIter<Option<Slot<K, V>>>,
+    len: usize,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for x in self.inner.by_ref() {
+            if let Some(Slot::Val(k, v)) = x {
+                self.len -= 1;
+                return Some((k, v));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+impl<K, V> IntoIterator for LinearHashMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.t.into_vec().into_iter(),
+            len: self.n,
+        }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a LinearHashMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a mut LinearHashMap<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn check(map: &LinearHashMap<i32, i32>, expected: &HashMap<i32, i32>) {
+        assert_eq!(map.size(), expected.len());
+        let pairs = map.iter().map(|(&k, &v)| (k, v)).collect::<HashMap<_, _>>();
+        assert_eq!(pairs, *expected);
+        for (k, v) in expected {
+            assert_eq!(map.get(k), Some(v));
+        }
+        map.check_invariants();
+    }
+
+    #[test]
+    fn scenario() {
+        let mut map = LinearHashMap::with_seed(0);
+        assert_eq!(map.get("a"), None);
+
+        assert_eq!(map.insert("a".to_string(), 1), None);
+        assert_eq!(map.insert("b".to_string(), 2), None);
+        assert_eq!(map.insert("a".to_string(), 3), Some(1));
+        assert_eq!(map.size(), 2);
+
+        assert_eq!(map.get("a"), Some(&3));
+        assert_eq!(map.get_key_value("b"), Some((&"b".to_string(), &2)));
+        assert!(map.contains_key("b"));
+        assert!(!map.contains_key("c"));
+
+        *map.get_mut("b").unwrap() += 10;
+        assert_eq!(map.get("b"), Some(&12));
+        assert_eq!(map.get_mut("c"), None);
+
+        assert_eq!(map.remove("a"), Some(3));
+        assert_eq!(map.remove("a"), None);
+        assert_eq!(map.size(), 1);
+        assert_eq!(format!("{map:?}"), r#"{"b": 12}"#);
+    }
+
+    #[test]
+    fn tombstones() {
+        let mut map = LinearHashMap::with_seed(1);
+        for k in 0..12 {
+            map.insert(k, k);
+        }
+        let (len, q) = (map.t.len(), map.q);
+
+        assert_eq!(map.remove(&5), Some(5));
+        assert_eq!(map.t.len(), len);
+        assert_eq!(map.q, q);
+        assert!(map.t.iter().any(|x| matches!(x, Some(Slot::Del))));
+        for k in (0..12).filter(|&k| k != 5) {
+            assert_eq!(map.get(&k), Some(&k));
+        }
+
+        assert_eq!(map.insert(5, 50), None);
+        assert_eq!(map.q, q);
+        check(
+            &map,
+            &(0..12).map(|k| (k, if k == 5 { 50 } else { k })).collect(),
+        );
+    }
+
+    #[test]
+    fn entry() {
+        let mut map = LinearHashMap::with_seed(2);
+        for w in "the quick fox and the lazy dog and the cat".split(' ') {
+            *map.entry(w).or_insert(0) += 1;
+        }
+        assert_eq!(map.get("the"), Some(&3));
+        assert_eq!(map.get("and"), Some(&2));
+        assert_eq!(map.get("fox"), Some(&1));
+        assert_eq!(map.size(), 7);
+
+        match map.entry("the") {
+            Entry::Occupied(mut e) => {
+                assert_eq!(e.key(), &"the");
+                assert_eq!(e.get(), &3);
+                assert_eq!(e.insert(5), 3);
+                assert_eq!(e.remove_entry(), ("the", 5));
+            }
+            Entry::Vacant(_) => unreachable!(),
+        }
+        match map.entry("owl") {
+            Entry::Occupied(_) => unreachable!(),
+            Entry::Vacant(e) => {
+                assert_eq!(e.key(), &"owl");
+                assert_eq!(e.into_key(), "owl");
+            }
+        }
+        assert_eq!(map.size(), 6);
+
+        map.entry("cat").and_modify(|v| *v += 1).or_default();
+        map.entry("emu").and_modify(|v| *v += 1).or_default();
+        assert_eq!(map.get("cat"), Some(&2));
+        assert_eq!(map.get("emu"), Some(&0));
+        assert_eq!(*map.entry("yak").or_insert_with(|| 9), 9);
+    }
+
+    #[test]
+    fn random_operations() {
+        let mut rng = Rng::with_seed(3);
+        let mut map = LinearHashMap::with_seed(4);
+        let mut expected = HashMap::new();
+        for step in 0..3000 {
+            let k = (rng.next_u64() % 300) as i32;
+            match rng.next_u64() % 4 {
+                0 | 1 => assert_eq!(map.insert(k, step), expected.insert(k, step)),
+                2 => {
+                    *map.entry(k).or_insert(0) += 1;
+                    *expected.entry(k).or_insert(0) += 1;
+                }
+                _ => assert_eq!(map.remove(&k), expected.remove(&k)),
+            }
+            assert_eq!(map.get(&k), expected.get(&k));
+        }
+        check(&map, &expected);
+    }
+
+    #[test]
+    fn iter() {
+        let mut map = LinearHashMap::with_seed(5);
+        for k in 0..10 {
+            map.insert(k, k);
+        }
+        map.remove(&3);
+        for (k, v) in &mut map {
+            *v += k * 10;
+        }
+        assert_eq!(map.iter().len(), 9);
+        let expected = (0..10).filter(|&k| k != 3).map(|k| (k, k * 11));
+        check(&map, &expected.clone().collect());
+
+        let iter = map.into_iter();
+        assert_eq!(iter.len(), 9);
+        let mut pairs = iter.collect::<Vec<_>>();
+        pairs.sort();
+        assert_eq!(pairs, expected.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn capacity() {
+        let mut map = LinearHashMap::with_capacity(100);
+        let slots = map.slot_count();
+        assert!(map.capacity() >= 100);
+        for i in 0..100 {
+            map.insert(i, i);
+        }
+        assert_eq!(map.slot_count(), slots);
+
+        // Tombstones use up capacity until the table is rebuilt.
+        for i in 0..50 {
+            map.remove(&i);
+        }
+        assert_eq!(map.capacity(), 78);
+        map.shrink_to_fit();
+        assert_eq!(map.capacity(), 128);
+        map.check_invariants();
+
+        map.clear();
+        assert_eq!(map.size(), 0);
+        assert_eq!(map.get(&60), None);
+        map.insert(1, 1);
+        assert_eq!(map.get(&1), Some(&1));
+        map.remove(&1);
+        map.shrink_to_fit();
+        assert_eq!(map.slot_count(), 2);
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a: LinearHashMap<&str, i32> = [("a", 1), ("b", 2), ("c", 3)].into_iter().collect();
+        let mut b = LinearHashMap::with_capacity(64);
+        b.extend([("c", 3), ("a", 1), ("b", 2)]);
+        assert_ne!(a.capacity(), b.capacity());
+        assert_eq!(a, b);
+        assert_eq!(a["b"], 2);
+
+        let mut c = a.clone();
+        *c.get_mut("b").unwrap() = 5;
+        assert_eq!(a["b"], 2);
+        assert_ne!(a, c);
+        c.remove("b");
+        c.insert("d", 2);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    #[should_panic(expected = "no entry for key")]
+    fn index_missing_key() {
+        let map = LinearHashMap::<&str, i32>::new();
+        let _ = map["a"];
+    }
+
+    #[test]
+    fn lookups_from_several_threads() {
+        let map: LinearHashMap<u32, u32> = (0..100).map(|x| (x, x + 1)).collect();
+        map.reset_probe_stats();
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for x in 0..100 {
+                        assert_eq!(map.get(&x), Some(&(x + 1)));
+                    }
+                });
+            }
+        });
+        let stats = map.probe_stats();
+        assert_eq!(stats.searches, 400);
+        assert!(stats.probes >= 400);
+        assert_eq!(map.clone().probe_stats(), stats);
+    }
+}
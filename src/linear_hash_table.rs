@@ -0,0 +1,336 @@
+use std::{fmt, hash::Hash};
+
+use crate::{LinearHashMap, linear_hash_map, linear_hash_map::Entry};
+
+pub use crate::linear_hash_map::ProbeStats;
+
+#[derive(Clone)]
+pub struct LinearHashTable<T> {
+    map: LinearHashMap<T, ()>,
+}
+
+impl<T> Default for LinearHashTable<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for LinearHashTable<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T> LinearHashTable<T> {
+    pub fn new() -> Self {
+        Self {
+            map: LinearHashMap::new(),
+        }
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            map: LinearHashMap::with_seed(seed),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.map.size()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.map.iter(),
+        }
+    }
+
+    pub fn probe_stats(&self) -> ProbeStats {
+        self.map.probe_stats()
+    }
+
+    pub fn reset_probe_stats(&self) {
+        self.map.reset_probe_stats();
+    }
+}
+
+impl<T: Hash + Eq> LinearHashTable<T> {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            map: LinearHashMap::with_capacity(capacity),
+        }
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional);
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit();
+    }
+
+    pub fn find(&self, x: &T) -> Option<&T> {
+        self.map.get_key_value(x).map(|(y, _)| y)
+    }
+
+    pub fn contains(&self, x: &T) -> bool {
+        self.map.contains_key(x)
+    }
+
+    pub fn add(&mut self, x: T) -> bool {
+        match self.map.entry(x) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(e) => {
+                e.insert(());
+                true
+            }
+        }
+    }
+
+    pub fn remove(&mut self, x: &T) -> Option<T> {
+        self.map.remove_entry(x).map(|(y, _)| y)
+    }
+}
+
+impl<T: Hash + Eq> PartialEq for LinearHashTable<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size() == other.size() && self.iter().all(|x| other.contains(x))
+    }
+}
+
+impl<T: Hash + Eq> Eq for LinearHashTable<T> {}
+
+impl<T: Hash + Eq> FromIterator<T> for LinearHashTable<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut s = Self::new();
+        s.extend(iter);
+        s
+    }
+}
+
+impl<T: Hash + Eq> Extend<T> for LinearHashTable<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.add(x);
+        }
+    }
+}
+
+impl<T: Hash + Eq> crate::USet<T> for LinearHashTable<T> {
+    fn size(&self) -> usize {
+        LinearHashTable::size(self)
+    }
+
+    fn add(&mut self, x: T) -> bool {
+        LinearHashTable::add(self, x)
+    }
+
+    fn remove(&mut self, x: &T) -> Option<T> {
+        LinearHashTable::remove(self, x)
+    }
+
+    fn find(&self, x: &T) -> Option<&T> {
+        LinearHashTable::find(self, x)
+    }
+}
+
+pub struct Iter<'a, T> {
+    inner: linear_hash_map::Iter<'a, T, ()>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(x, _)| x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IntoIter<T> {
+    inner: linear_hash_map::IntoIter<T, ()>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(x, _)| x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for LinearHashTable<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.map.into_iter(),
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinearHashTable<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::Rng;
+    use std::collections::HashSet;
+
+    fn check(table: &LinearHashTable<i32>, expected: &HashSet<i32>) {
+        assert_eq!(table.size(), expected.len());
+        assert_eq!(table.iter().copied().collect::<HashSet<_>>(), *expected);
+        table.map.check_invariants();
+    }
+
+    #[test]
+    fn scenario() {
+        let mut table = LinearHashTable::with_seed(0);
+        assert_eq!(table.find(&1), None);
+
+        assert!(table.add(1));
+        assert!(table.add(2));
+        assert!(table.add(3));
+        assert!(!table.add(2));
+        assert_eq!(table.size(), 3);
+
+        assert_eq!(table.find(&2), Some(&2));
+        assert!(table.contains(&3));
+        assert!(!table.contains(&4));
+
+        assert_eq!(table.remove(&2), Some(2));
+        assert_eq!(table.remove(&2), None);
+        assert_eq!(table.size(), 2);
+        check(&table, &HashSet::from([1, 3]));
+        assert_eq!(format!("{:?}", LinearHashTable::<i32>::with_seed(0)), "{}");
+    }
+
+    #[test]
+    fn tombstones() {
+        let mut table = LinearHashTable::with_seed(1);
+        for x in 0..12 {
+            table.add(x);
+        }
+        let len = table.map.slot_count();
+
+        assert_eq!(table.remove(&5), Some(5));
+        assert_eq!(table.map.slot_count(), len);
+        for x in (0..12).filter(|&x| x != 5) {
+            assert_eq!(table.find(&x), Some(&x));
+        }
+
+        assert!(table.add(5));
+        check(&table, &(0..12).collect());
+    }
+
+    #[test]
+    fn resize_keeps_load_bounded() {
+        let mut table = LinearHashTable::with_seed(2);
+        let mut expected = HashSet::new();
+        for x in 0..500 {
+            table.add(x);
+            expected.insert(x);
+            check(&table, &expected);
+        }
+        for x in 0..500 {
+            table.remove(&x);
+            expected.remove(&x);
+            check(&table, &expected);
+        }
+        assert_eq!(table.map.slot_count(), 2);
+    }
+
+    #[test]
+    fn probe_stats() {
+        let mut table = LinearHashTable::with_seed(3);
+        assert_eq!(table.probe_stats(), ProbeStats::default());
+        assert_eq!(table.probe_stats().average(), 0.0);
+
+        for x in 0..100 {
+            table.add(x);
+        }
+        table.reset_probe_stats();
+        for x in 0..100 {
+            table.find(&x);
+        }
+        let stats = table.probe_stats();
+        assert_eq!(stats.searches, 100);
+        assert!(stats.probes >= 100);
+        assert!(stats.longest >= 1);
+        assert!(stats.average() >= 1.0);
+
+        table.remove(&0);
+        assert_eq!(table.probe_stats().searches, 101);
+    }
+
+    #[test]
+    fn random_operations() {
+        let mut rng = Rng::with_seed(4);
+        let mut table = LinearHashTable::with_seed(5);
+        let mut expected = HashSet::new();
+        for _ in 0..3000 {
+            let x = (rng.next_u64() % 300) as i32;
+            match rng.next_u64() % 3 {
+                0 | 1 => assert_eq!(table.add(x), expected.insert(x)),
+                _ => assert_eq!(table.remove(&x), expected.take(&x)),
+            }
+            assert_eq!(table.find(&x), expected.get(&x));
+        }
+        check(&table, &expected);
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut table = LinearHashTable::with_seed(6);
+        for x in ["a", "b", "c"] {
+            table.add(x.to_string());
+        }
+        table.remove(&"b".to_string());
+        let iter = table.into_iter();
+        assert_eq!(iter.len(), 2);
+        let mut v = iter.collect::<Vec<_>>();
+        v.sort();
+        assert_eq!(v, ["a", "c"]);
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a: LinearHashTable<i32> = (0..20).collect();
+        let mut b = LinearHashTable::with_capacity(100);
+        b.extend((0..20).rev());
+        assert_eq!(a, b);
+
+        let mut c = a.clone();
+        c.remove(&7);
+        assert!(a.contains(&7));
+        assert_ne!(a, c);
+        c.add(20);
+        assert_ne!(a, c);
+    }
+}
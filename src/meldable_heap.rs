@@ -0,0 +1,392 @@
+use std::{cmp::Ordering, fmt};
+
+use crate::{
+    BinaryTree, Compare, Min,
+    binary_tree::{self, Link, Node},
+    util::Rng,
+};
+
+#[derive(Clone)]
+pub struct MeldableHeap<T, C = Min> {
+    tree: BinaryTree<T>,
+    n: usize,
+    c: C,
+    rng: Rng,
+}
+
+impl<T, C: Compare<T> + Default> Default for MeldableHeap<T, C> {
+    fn default() -> Self {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T: fmt::Debug, C> fmt::Debug for MeldableHeap<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Ord> MeldableHeap<T> {
+    pub fn new() -> Self {
+        Self::with_comparator(Min)
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(Min, Rng::with_seed(seed))
+    }
+}
+
+impl<T, C> MeldableHeap<T, C> {
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    pub fn clear(&mut self) {
+        self.tree = BinaryTree::new();
+        self.n = 0;
+    }
+
+    pub fn as_tree(&self) -> &BinaryTree<T> {
+        &self.tree
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        // SAFETY: the root belongs to the tree, which is borrowed shared.
+        self.tree.root().map(|r| unsafe { Node::x(r) })
+    }
+
+    // Visits the elements in no particular order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.tree.iter(),
+            len: self.n,
+        }
+    }
+}
+
+impl<T, C: Compare<T>> MeldableHeap<T, C> {
+    pub fn with_comparator(c: C) -> Self {
+        Self::with_rng(c, Rng::new())
+    }
+
+    fn with_rng(c: C, rng: Rng) -> Self {
+        Self {
+            tree: BinaryTree::new(),
+            n: 0,
+            c,
+            rng,
+        }
+    }
+
+    // Merges two heap-ordered subtrees by walking down random paths of the
+    // one with the smaller root.
+    // SAFETY: the caller must guarantee that `a` and `b` are roots of
+    // disjoint live subtrees whose parents may be overwritten.
+    unsafe fn merge_nodes(&mut self, a: Link<T, ()>, b: Link<T, ()>) -> Link<T, ()> {
+        let (a, b) = match (a, b) {
+            (None, w) | (w, None) => return w,
+            (Some(a), Some(b)) => (a, b),
+        };
+        unsafe {
+            if self.c.compare(Node::x(b), Node::x(a)) == Ordering::Less {
+                return self.merge_nodes(Some(b), Some(a));
+            }
+            let w = if self.rng.next_u64() & 1 == 0 {
+                let w = self.merge_nodes(Node::left(a), Some(b));
+                Node::set_left(a, w);
+                w
+            } else {
+                let w = self.merge_nodes(Node::right(a), Some(b));
+                Node::set_right(a, w);
+                w
+            };
+            Node::set_parent(w.unwrap(), Some(a));
+        }
+        Some(a)
+    }
+
+    pub fn push(&mut self, x: T) {
+        let u = Node::alloc(x, ());
+        // SAFETY: `u` is new and the root belongs to this heap.
+        unsafe {
+            let r = self.merge_nodes(Some(u), self.tree.root());
+            self.tree.set_root(r);
+        }
+        self.n += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let r = self.tree.root()?;
+        // SAFETY: the children of the root take its place, after which the
+        // root is no longer linked into the tree.
+        unsafe {
+            let w = self.merge_nodes(Node::left(r), Node::right(r));
+            self.tree.set_root(w);
+            self.n -= 1;
+            Some(Node::into_x(r))
+        }
+    }
+
+    // Moves every element of `other` into this heap in O(log n) expected
+    // time.
+    pub fn merge(&mut self, mut other: Self) {
+        // SAFETY: the nodes of both heaps are moved into this one, and
+        // `other` is emptied before it is dropped.
+        unsafe {
+            let r = self.merge_nodes(self.tree.root(), other.tree.root());
+            other.tree.set_root(None);
+            self.tree.set_root(r);
+        }
+        self.n += std::mem::take(&mut other.n);
+    }
+
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        std::iter::from_fn(|| self.pop()).collect()
+    }
+
+    #[cfg(test)]
+    fn check(&self) {
+        binary_tree::check_links(&self.tree);
+        assert_eq!(self.tree.size(), self.n);
+        // SAFETY: test-only walk over live nodes.
+        unsafe fn check<T, C: Compare<T>>(c: &C, u: Link<T, ()>) {
+            let Some(u) = u else { return };
+            unsafe {
+                for w in [Node::left(u), Node::right(u)].into_iter().flatten() {
+                    assert_ne!(c.compare(Node::x(w), Node::x(u)), Ordering::Less);
+                    check(c, Some(w));
+                }
+            }
+        }
+        unsafe { check(&self.c, self.tree.root()) }
+    }
+}
+
+impl<T, C: Compare<T> + Default> FromIterator<T> for MeldableHeap<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut h = Self::with_comparator(C::default());
+        h.extend(iter);
+        h
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for MeldableHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl<T, C: Compare<T>> crate::Queue<T> for MeldableHeap<T, C> {
+    fn size(&self) -> usize {
+        MeldableHeap::size(self)
+    }
+
+    fn add(&mut self, x: T) {
+        self.push(x)
+    }
+
+    fn remove(&mut self) -> Option<T> {
+        self.pop()
+    }
+}
+
+pub struct Iter<'a, T> {
+    inner: binary_tree::Iter<'a, T>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.inner.next()?;
+        self.len -= 1;
+        Some(x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IntoIter<T> {
+    inner: binary_tree::IntoIter<T>,
+    len: usize,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.inner.next()?;
+        self.len -= 1;
+        Some(x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T, C> IntoIterator for MeldableHeap<T, C> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.tree.into_iter(),
+            len: self.n,
+        }
+    }
+}
+
+impl<'a, T, C> IntoIterator for &'a MeldableHeap<T, C> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Max;
+
+    #[test]
+    fn scenario() {
+        let mut heap = MeldableHeap::with_seed(0);
+        assert_eq!(heap.peek(), None);
+        assert_eq!(heap.pop(), None);
+
+        for x in [5, 2, 8, 1, 9, 2] {
+            heap.push(x);
+            heap.check();
+        }
+        assert_eq!(heap.size(), 6);
+        assert_eq!(heap.peek(), Some(&1));
+        assert_eq!(heap.pop(), Some(1));
+        assert_eq!(heap.pop(), Some(2));
+        assert_eq!(heap.pop(), Some(2));
+        heap.check();
+        assert_eq!(heap.size(), 3);
+        assert_eq!(heap.iter().len(), 3);
+        assert_eq!(heap.into_sorted_vec(), [5, 8, 9]);
+    }
+
+    #[test]
+    fn comparators() {
+        let mut heap = MeldableHeap::with_comparator(Max);
+        for x in [3, 1, 4, 1, 5] {
+            heap.push(x);
+        }
+        assert_eq!(heap.peek(), Some(&5));
+        assert_eq!(heap.into_sorted_vec(), [5, 4, 3, 1, 1]);
+    }
+
+    #[test]
+    fn random_operations() {
+        let mut rng = Rng::with_seed(1);
+        let mut heap = MeldableHeap::with_seed(2);
+        let mut expected = std::collections::BinaryHeap::new();
+        for _ in 0..3000 {
+            if rng.next_u64() % 3 < 2 {
+                let x = (rng.next_u64() % 100) as i32;
+                heap.push(x);
+                expected.push(std::cmp::Reverse(x));
+            } else {
+                assert_eq!(heap.pop(), expected.pop().map(|x| x.0));
+            }
+            assert_eq!(heap.peek(), expected.peek().map(|x| &x.0));
+            assert_eq!(heap.size(), expected.len());
+        }
+        heap.check();
+    }
+
+    #[test]
+    fn merge() {
+        let mut rng = Rng::with_seed(3);
+        let mut heap = MeldableHeap::with_seed(4);
+        let mut expected = Vec::new();
+        for i in 0..20 {
+            let mut other = MeldableHeap::with_seed(i);
+            for _ in 0..100 {
+                let x = rng.next_u64() % 1000;
+                other.push(x);
+                expected.push(x);
+            }
+            heap.merge(other);
+            heap.check();
+        }
+        heap.merge(MeldableHeap::new());
+        assert_eq!(heap.size(), 2000);
+
+        // Random merges keep the tree shallow.
+        assert!(heap.as_tree().height().unwrap() < 100);
+
+        expected.sort();
+        assert_eq!(heap.into_sorted_vec(), expected);
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut heap = MeldableHeap::with_seed(5);
+        for x in [4, 2, 8, 6] {
+            heap.push(x);
+        }
+        let iter = heap.into_iter();
+        assert_eq!(iter.len(), 4);
+        let mut v = iter.collect::<Vec<_>>();
+        v.sort();
+        assert_eq!(v, [2, 4, 6, 8]);
+    }
+
+    #[test]
+    fn drop_elements() {
+        let x = std::rc::Rc::new(0);
+        let mut heap = MeldableHeap::with_seed(6);
+        for i in 0..10 {
+            heap.push((i, x.clone()));
+        }
+        drop(heap.pop());
+        assert_eq!(std::rc::Rc::strong_count(&x), 10);
+        drop(heap);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+    }
+
+    #[test]
+    fn clear() {
+        let x = std::rc::Rc::new(());
+        let mut heap = MeldableHeap::with_seed(6);
+        for i in 0..10 {
+            heap.push((i, x.clone()));
+        }
+        heap.clear();
+        assert_eq!(heap.size(), 0);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+        assert_eq!(heap.pop(), None);
+        heap.push((2, x.clone()));
+        assert_eq!(heap.peek(), Some(&(2, x.clone())));
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a: MeldableHeap<i32> = [5, 1, 4, 2, 3].into_iter().collect();
+        let mut b = a.clone();
+        b.extend([0, 6]);
+        assert_eq!(a.size(), 5);
+        assert_eq!(b.peek(), Some(&0));
+        assert_eq!(b.into_sorted_vec(), [0, 1, 2, 3, 4, 5, 6]);
+
+        let c: MeldableHeap<i32, Max> = (1..=5).collect();
+        assert_eq!(c.peek(), Some(&5));
+        assert_eq!(MeldableHeap::<i32, Max>::default().size(), 0);
+    }
+}
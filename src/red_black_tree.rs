@@ -0,0 +1,336 @@
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::RangeBounds,
+};
+
+use crate::{RedBlackTreeMap, red_black_tree_map};
+
+#[derive(Clone)]
+pub struct RedBlackTree<T> {
+    map: RedBlackTreeMap<T, ()>,
+}
+
+impl<T> Default for RedBlackTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RedBlackTree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T> RedBlackTree<T> {
+    pub fn new() -> Self {
+        Self {
+            map: RedBlackTreeMap::new(),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.map.size()
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    pub fn height(&self) -> Option<usize> {
+        self.map.as_tree().height()
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.map.iter(),
+        }
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.map.first_key_value().map(|(x, _)| x)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.map.last_key_value().map(|(x, _)| x)
+    }
+}
+
+impl<T: Ord> RedBlackTree<T> {
+    pub fn find(&self, x: &T) -> Option<&T> {
+        self.map.find(x).map(|(y, _)| y)
+    }
+
+    pub fn contains(&self, x: &T) -> bool {
+        self.map.contains_key(x)
+    }
+
+    pub fn add(&mut self, x: T) -> bool {
+        self.map.insert(x, ()).is_none()
+    }
+
+    pub fn remove(&mut self, x: &T) -> Option<T> {
+        self.map.remove_entry(x).map(|(y, _)| y)
+    }
+
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        Range {
+            inner: self.map.range(range),
+        }
+    }
+
+    pub fn debug_validate(&self) {
+        self.map.debug_validate();
+    }
+}
+
+impl<T: PartialEq> PartialEq for RedBlackTree<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for RedBlackTree<T> {}
+
+impl<T: PartialOrd> PartialOrd for RedBlackTree<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for RedBlackTree<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for RedBlackTree<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.size());
+        self.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl<T: Ord> FromIterator<T> for RedBlackTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut c = Self::new();
+        c.extend(iter);
+        c
+    }
+}
+
+impl<T: Ord> Extend<T> for RedBlackTree<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.add(x);
+        }
+    }
+}
+
+impl<T: Ord> crate::SSet<T> for RedBlackTree<T> {
+    fn size(&self) -> usize {
+        RedBlackTree::size(self)
+    }
+
+    fn add(&mut self, x: T) -> bool {
+        RedBlackTree::add(self, x)
+    }
+
+    fn remove(&mut self, x: &T) -> Option<T> {
+        RedBlackTree::remove(self, x)
+    }
+
+    fn find(&self, x: &T) -> Option<&T> {
+        RedBlackTree::find(self, x)
+    }
+}
+
+pub struct Iter<'a, T> {
+    inner: red_black_tree_map::Iter<'a, T, ()>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(x, _)| x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IntoIter<T> {
+    inner: red_black_tree_map::IntoIter<T, ()>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(x, _)| x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+pub struct Range<'a, T> {
+    inner: red_black_tree_map::Range<'a, T, ()>,
+}
+
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(x, _)| x)
+    }
+}
+
+impl<T> IntoIterator for RedBlackTree<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.map.into_iter(),
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RedBlackTree<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::Rng;
+    use std::collections::BTreeSet;
+
+    fn check(set: &RedBlackTree<i32>, expected: &BTreeSet<i32>) {
+        set.debug_validate();
+        assert_eq!(set.size(), expected.len());
+        assert!(set.iter().eq(expected.iter()));
+    }
+
+    #[test]
+    fn scenario() {
+        let mut set = RedBlackTree::new();
+        assert_eq!(set.find(&0), None);
+        assert_eq!(set.height(), None);
+
+        for x in [5, 2, 8, 1, 3, 7, 9] {
+            assert!(set.add(x));
+        }
+        assert!(!set.add(3));
+        assert_eq!(set.size(), 7);
+        assert_eq!((set.first(), set.last()), (Some(&1), Some(&9)));
+
+        assert_eq!(set.find(&0), Some(&1));
+        assert_eq!(set.find(&4), Some(&5));
+        assert_eq!(set.find(&10), None);
+        assert!(set.contains(&8));
+        assert!(!set.contains(&4));
+        assert!(set.range(2..8).eq(&[2, 3, 5, 7]));
+
+        assert_eq!(set.remove(&5), Some(5));
+        assert_eq!(set.remove(&5), None);
+        check(&set, &BTreeSet::from([1, 2, 3, 7, 8, 9]));
+        assert_eq!(format!("{set:?}"), "{1, 2, 3, 7, 8, 9}");
+    }
+
+    #[test]
+    fn random_operations() {
+        let mut rng = Rng::with_seed(1);
+        let mut set = RedBlackTree::new();
+        let mut expected = BTreeSet::new();
+        for _ in 0..3000 {
+            let x = (rng.next_u64() % 300) as i32;
+            match rng.next_u64() % 3 {
+                0 | 1 => assert_eq!(set.add(x), expected.insert(x)),
+                _ => assert_eq!(set.remove(&x), expected.take(&x)),
+            }
+            assert_eq!(set.find(&x), expected.range(x..).next());
+            assert!(set.range(x..x + 20).eq(expected.range(x..x + 20)));
+            check(&set, &expected);
+        }
+    }
+
+    #[test]
+    fn height_is_logarithmic() {
+        let mut rng = Rng::with_seed(2);
+        let mut set = RedBlackTree::new();
+        for x in 0..4096 {
+            set.add(x);
+        }
+        for _ in 0..2048 {
+            set.remove(&((rng.next_u64() % 4096) as i32));
+        }
+        // A red-black tree with n nodes has height below 2 log(n + 1).
+        let n = set.size() as f64;
+        assert!((set.height().unwrap() as f64) < 2.0 * (n + 1.0).log2());
+        set.debug_validate();
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut set = RedBlackTree::new();
+        for x in [4, 2, 8, 6] {
+            set.add(x);
+        }
+        let mut iter = set.into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.collect::<Vec<_>>(), [4, 6, 8]);
+    }
+
+    #[test]
+    fn drop_elements() {
+        let x = std::rc::Rc::new(0);
+        let mut set = RedBlackTree::new();
+        for i in 0..10 {
+            set.add((i, x.clone()));
+        }
+        drop(set.remove(&(3, x.clone())));
+        assert_eq!(std::rc::Rc::strong_count(&x), 10);
+        drop(set);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a: RedBlackTree<char> = "dbfaceg".chars().collect();
+        let mut b = RedBlackTree::new();
+        b.extend("abcdefg".chars());
+        assert_eq!(a, b);
+        assert_eq!(crate::util::hash_code(&a), crate::util::hash_code(&b));
+        assert_eq!(format!("{a:?}"), "{'a', 'b', 'c', 'd', 'e', 'f', 'g'}");
+
+        let mut c = a.clone();
+        c.debug_validate();
+        c.remove(&'a');
+        assert!(a.contains(&'a'));
+        assert!(a < c);
+        assert_eq!(c.cmp(&c.clone()), Ordering::Equal);
+    }
+}
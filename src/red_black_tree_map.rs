@@ -0,0 +1,864 @@
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem,
+    ops::{Bound, Index, RangeBounds},
+    ptr::NonNull,
+};
+
+use crate::{
+    BinaryTree,
+    binary_tree::{self, Link, Node, leftmost, successor},
+};
+
+// A node's colour is the number of black nodes it adds to the paths through
+// it, so removal can briefly leave a node double-black.
+const RED: u8 = 0;
+const BLACK: u8 = 1;
+
+type NodePtr<K, V> = NonNull<Node<(K, V), u8>>;
+
+#[derive(Clone)]
+pub struct RedBlackTreeMap<K, V> {
+    tree: BinaryTree<(K, V), u8>,
+    n: usize,
+}
+
+// Missing children count as black.
+// SAFETY: the caller must guarantee that `u` is a live node if any.
+unsafe fn colour<K, V>(u: Link<(K, V), u8>) -> u8 {
+    u.map_or(BLACK, |u| unsafe { *Node::extra(u) })
+}
+
+// SAFETY (for the colour helpers below): the nodes must be live and not
+// borrowed elsewhere.
+unsafe fn set_colour<K, V>(u: NodePtr<K, V>, c: u8) {
+    unsafe { *Node::extra_mut(u) = c }
+}
+
+unsafe fn swap_colours<K, V>(u: NodePtr<K, V>, w: NodePtr<K, V>) {
+    unsafe { mem::swap(Node::extra_mut(u), Node::extra_mut(w)) }
+}
+
+// Moves one black from `u` down to both of its children.
+unsafe fn push_black<K, V>(u: NodePtr<K, V>) {
+    unsafe {
+        *Node::extra_mut(u) -= 1;
+        *Node::extra_mut(Node::left(u).unwrap()) += 1;
+        *Node::extra_mut(Node::right(u).unwrap()) += 1;
+    }
+}
+
+// Moves one black from both children of `u` up to it.
+unsafe fn pull_black<K, V>(u: NodePtr<K, V>) {
+    unsafe {
+        *Node::extra_mut(u) += 1;
+        *Node::extra_mut(Node::left(u).unwrap()) -= 1;
+        *Node::extra_mut(Node::right(u).unwrap()) -= 1;
+    }
+}
+
+// Returns the black height of the subtree at `u`, checking the red-black
+// properties on the way.
+// SAFETY: the caller must guarantee that the subtree is live and not mutated
+// during the call.
+unsafe fn validate<K: Ord, V>(u: Link<(K, V), u8>) -> usize {
+    let Some(u) = u else {
+        return 1;
+    };
+    unsafe {
+        let (l, r) = (Node::left(u), Node::right(u));
+        let c = colour(Some(u));
+        assert!(c == RED || c == BLACK, "node is neither red nor black");
+        if c == RED {
+            assert!(colour(l) == BLACK && colour(r) == BLACK, "red edge");
+        }
+        assert!(colour(r) == BLACK || colour(l) == RED, "right-leaning");
+        if let Some(l) = l {
+            assert_eq!(Node::parent(l), Some(u));
+            assert!(Node::x(l).0 < Node::x(u).0, "out of order");
+        }
+        if let Some(r) = r {
+            assert_eq!(Node::parent(r), Some(u));
+            assert!(Node::x(r).0 > Node::x(u).0, "out of order");
+        }
+        let h = validate(l);
+        assert_eq!(h, validate(r), "black heights differ");
+        h + c as usize
+    }
+}
+
+impl<K, V> Default for RedBlackTreeMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for RedBlackTreeMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V> RedBlackTreeMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            tree: BinaryTree::new(),
+            n: 0,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    pub fn clear(&mut self) {
+        self.tree = BinaryTree::new();
+        self.n = 0;
+    }
+
+    pub fn as_tree(&self) -> &BinaryTree<(K, V), impl Sized> {
+        &self.tree
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.tree.iter(),
+            len: self.n,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            next: self.tree.first_node(),
+            len: self.n,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        // SAFETY: the tree is borrowed shared.
+        let (k, v) = unsafe { Node::x(self.tree.first_node()?) };
+        Some((k, v))
+    }
+
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        // SAFETY: the tree is borrowed shared.
+        let (k, v) = unsafe { Node::x(self.tree.last_node()?) };
+        Some((k, v))
+    }
+
+    // SAFETY (for the rebalancing helpers below): the nodes must belong to
+    // this tree and have the children the operation moves.
+    unsafe fn flip_left(&mut self, u: NodePtr<K, V>) {
+        unsafe {
+            swap_colours(u, Node::right(u).unwrap());
+            self.tree.rotate_left(u);
+        }
+    }
+
+    unsafe fn flip_right(&mut self, u: NodePtr<K, V>) {
+        unsafe {
+            swap_colours(u, Node::left(u).unwrap());
+            self.tree.rotate_right(u);
+        }
+    }
+
+    // Restores the red-black properties after the red node `u` was added.
+    unsafe fn add_fixup(&mut self, mut u: NodePtr<K, V>) {
+        unsafe {
+            while colour(Some(u)) == RED {
+                let Some(mut w) = Node::parent(u) else {
+                    set_colour(u, BLACK);
+                    return;
+                };
+                if colour(Node::left(w)) == BLACK {
+                    self.flip_left(w);
+                    u = w;
+                    w = Node::parent(u).unwrap();
+                }
+                if colour(Some(w)) == BLACK {
+                    return;
+                }
+                let g = Node::parent(w).unwrap();
+                if colour(Node::right(g)) == BLACK {
+                    self.flip_right(g);
+                    return;
+                }
+                push_black(g);
+                u = g;
+            }
+        }
+    }
+
+    // Removes the node `w`, which has no right child, and rebalances.
+    unsafe fn remove_node(&mut self, w: NodePtr<K, V>) {
+        unsafe {
+            // A black leaf leaves a double-black hole. The fixup runs with `w`
+            // standing in for it, since it stays a leaf until it is spliced.
+            let u = match Node::left(w) {
+                Some(c) => {
+                    set_colour(c, BLACK);
+                    c
+                }
+                None if colour(Some(w)) == BLACK => {
+                    set_colour(w, BLACK + 1);
+                    self.remove_fixup(w)
+                }
+                None => w,
+            };
+            let p = Node::parent(w);
+            self.tree.splice(w);
+            let p = if u == w { p } else { Node::parent(u) };
+            if let Some(p) = p
+                && colour(Node::right(p)) == RED
+                && colour(Node::left(p)) == BLACK
+            {
+                self.flip_left(p);
+            }
+        }
+    }
+
+    unsafe fn remove_fixup(&mut self, mut u: NodePtr<K, V>) -> NodePtr<K, V> {
+        unsafe {
+            while colour(Some(u)) > BLACK {
+                match Node::parent(u) {
+                    None => set_colour(u, BLACK),
+                    Some(w) if colour(Node::left(w)) == RED => self.flip_right(w),
+                    Some(w) if Node::left(w) == Some(u) => u = self.remove_fixup_left(w),
+                    Some(w) => u = self.remove_fixup_right(w),
+                }
+            }
+            u
+        }
+    }
+
+    // The double-black node is the left child of `w`.
+    unsafe fn remove_fixup_left(&mut self, w: NodePtr<K, V>) -> NodePtr<K, V> {
+        unsafe {
+            let v = Node::right(w).unwrap();
+            pull_black(w);
+            self.flip_left(w);
+            match Node::right(w) {
+                Some(q) if colour(Some(q)) == RED => {
+                    self.tree.rotate_left(w);
+                    self.flip_right(v);
+                    push_black(q);
+                    if colour(Node::right(v)) == RED {
+                        self.flip_left(v);
+                    }
+                    q
+                }
+                _ => v,
+            }
+        }
+    }
+
+    // The double-black node is the right child of `w`.
+    unsafe fn remove_fixup_right(&mut self, w: NodePtr<K, V>) -> NodePtr<K, V> {
+        unsafe {
+            let v = Node::left(w).unwrap();
+            pull_black(w);
+            self.flip_right(w);
+            match Node::left(w) {
+                Some(q) if colour(Some(q)) == RED => {
+                    self.tree.rotate_right(w);
+                    self.flip_left(v);
+                    push_black(q);
+                    q
+                }
+                _ if colour(Node::left(v)) == RED => {
+                    push_black(v);
+                    v
+                }
+                _ => {
+                    self.flip_left(v);
+                    w
+                }
+            }
+        }
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for RedBlackTreeMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for RedBlackTreeMap<K, V> {}
+
+impl<K: PartialOrd, V: PartialOrd> PartialOrd for RedBlackTreeMap<K, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<K: Ord, V: Ord> Ord for RedBlackTreeMap<K, V> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<K: Hash, V: Hash> Hash for RedBlackTreeMap<K, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.n);
+        self.iter().for_each(|e| e.hash(state));
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for RedBlackTreeMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut m = Self::new();
+        m.extend(iter);
+        m
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for RedBlackTreeMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<K, V, Q> Index<&Q> for RedBlackTreeMap<K, V>
+where
+    K: Borrow<Q> + Ord,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+
+    fn index(&self, k: &Q) -> &V {
+        self.get(k).expect("no entry for key")
+    }
+}
+
+impl<K: Ord, V> RedBlackTreeMap<K, V> {
+    // The node holding `k`, or else the node that would become its parent.
+    fn find_last<Q>(&self, k: &Q) -> Link<(K, V), u8>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut w = self.tree.root();
+        let mut prev = None;
+        // SAFETY: the tree is borrowed shared.
+        unsafe {
+            while let Some(u) = w {
+                prev = w;
+                match k.cmp(Node::x(u).0.borrow()) {
+                    Ordering::Less => w = Node::left(u),
+                    Ordering::Greater => w = Node::right(u),
+                    Ordering::Equal => return w,
+                }
+            }
+        }
+        prev
+    }
+
+    fn locate<Q>(&self, k: &Q) -> Link<(K, V), u8>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        // SAFETY: the tree is borrowed shared.
+        self.find_last(k)
+            .filter(|&u| unsafe { Node::x(u).0.borrow() == k })
+    }
+
+    // The first node whose key satisfies `above`, which must be false for a
+    // prefix of the keys and true for the rest.
+    fn partition_point(&self, mut above: impl FnMut(&K) -> bool) -> Link<(K, V), u8> {
+        let mut w = self.tree.root();
+        let mut z = None;
+        // SAFETY: the tree is borrowed shared.
+        unsafe {
+            while let Some(u) = w {
+                if above(&Node::x(u).0) {
+                    z = w;
+                    w = Node::left(u);
+                } else {
+                    w = Node::right(u);
+                }
+            }
+        }
+        z
+    }
+
+    pub fn get<Q>(&self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(k).map(|(_, v)| v)
+    }
+
+    pub fn get_key_value<Q>(&self, k: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        // SAFETY: the tree is borrowed shared.
+        let (k, v) = unsafe { Node::x(self.locate(k)?) };
+        Some((k, v))
+    }
+
+    pub fn get_mut<Q>(&mut self, k: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        // SAFETY: the tree is borrowed exclusively.
+        Some(unsafe { &mut Node::x_mut(self.locate(k)?).1 })
+    }
+
+    pub fn contains_key<Q>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.locate(k).is_some()
+    }
+
+    // The entry with the smallest key that is not less than `k`.
+    pub fn find<Q>(&self, k: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let u = self.partition_point(|y| y.borrow() >= k)?;
+        // SAFETY: the tree is borrowed shared.
+        let (k, v) = unsafe { Node::x(u) };
+        Some((k, v))
+    }
+
+    pub fn insert(&mut self, k: K, v: V) -> Option<V> {
+        let p = self.find_last(&k);
+        // SAFETY: `p` belongs to the tree and the new node is linked in below
+        // it before rebalancing.
+        unsafe {
+            let u = match p {
+                None => {
+                    let u = Node::alloc((k, v), RED);
+                    self.tree.set_root(Some(u));
+                    u
+                }
+                Some(p) => {
+                    let ord = k.cmp(&Node::x(p).0);
+                    if ord == Ordering::Equal {
+                        return Some(mem::replace(&mut Node::x_mut(p).1, v));
+                    }
+                    let u = Node::alloc((k, v), RED);
+                    if ord == Ordering::Less {
+                        Node::set_left(p, Some(u));
+                    } else {
+                        Node::set_right(p, Some(u));
+                    }
+                    Node::set_parent(u, Some(p));
+                    u
+                }
+            };
+            self.add_fixup(u);
+        }
+        self.n += 1;
+        None
+    }
+
+    pub fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(k).map(|(_, v)| v)
+    }
+
+    pub fn remove_entry<Q>(&mut self, k: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let u = self.locate(k)?;
+        // SAFETY: `u` belongs to the tree. The node that gets unlinked holds
+        // `u`'s entry after the swap and is freed exactly once.
+        unsafe {
+            let w = match Node::right(u) {
+                None => u,
+                Some(r) => {
+                    let w = leftmost(r);
+                    mem::swap(Node::x_mut(u), Node::x_mut(w));
+                    w
+                }
+            };
+            self.remove_node(w);
+            self.n -= 1;
+            Some(Node::into_x(w))
+        }
+    }
+
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let next = match range.start_bound() {
+            Bound::Included(s) => self.partition_point(|k| k.borrow() >= s),
+            Bound::Excluded(s) => self.partition_point(|k| k.borrow() > s),
+            Bound::Unbounded => self.tree.first_node(),
+        };
+        let end = match range.end_bound() {
+            Bound::Included(e) => self.partition_point(|k| k.borrow() > e),
+            Bound::Excluded(e) => self.partition_point(|k| k.borrow() >= e),
+            Bound::Unbounded => None,
+        };
+        // An empty or reversed range starts at or after its end.
+        // SAFETY: the tree is borrowed shared.
+        let next = match (next, end) {
+            (Some(u), Some(z)) if unsafe { Node::x(u).0 >= Node::x(z).0 } => None,
+            _ => next,
+        };
+        Range {
+            next,
+            end,
+            marker: PhantomData,
+        }
+    }
+
+    // Panics unless the tree is a left-leaning red-black tree holding `size`
+    // entries in order.
+    pub fn debug_validate(&self) {
+        if let Some(r) = self.tree.root() {
+            // SAFETY: the tree is borrowed shared.
+            unsafe {
+                assert_eq!(Node::parent(r), None);
+                assert_eq!(colour(Some(r)), BLACK, "red root");
+                validate(Some(r));
+            }
+        }
+        assert_eq!(self.tree.size(), self.n);
+        let mut keys = self.iter().map(|(k, _)| k);
+        if let Some(mut prev) = keys.next() {
+            for k in keys {
+                assert!(prev < k, "out of order");
+                prev = k;
+            }
+        }
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    inner: binary_tree::Iter<'a, (K, V), u8>,
+    len: usize,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (k, v) = self.inner.next()?;
+        self.len -= 1;
+        Some((k, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+pub struct IterMut<'a, K, V> {
+    next: Link<(K, V), u8>,
+    len: usize,
+    marker: PhantomData<&'a mut (K, V)>,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let u = self.next?;
+        // SAFETY: the tree is borrowed exclusively for `'a` and each node is
+        // visited once.
+        unsafe {
+            self.next = successor(u);
+            self.len -= 1;
+            let (k, v) = Node::x_mut(u);
+            Some((&*k, v))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+pub struct IntoIter<K, V> {
+    inner: binary_tree::IntoIter<(K, V), u8>,
+    len: usize,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.inner.next()?;
+        self.len -= 1;
+        Some(x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+pub struct Range<'a, K, V> {
+    next: Link<(K, V), u8>,
+    end: Link<(K, V), u8>,
+    marker: PhantomData<&'a (K, V)>,
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let u = self.next.filter(|&u| Some(u) != self.end)?;
+        // SAFETY: the tree keeps its nodes alive and unmodified for `'a`.
+        unsafe {
+            self.next = successor(u);
+            let (k, v) = Node::x(u);
+            Some((k, v))
+        }
+    }
+}
+
+impl<K, V> IntoIterator for RedBlackTreeMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.tree.into_iter(),
+            len: self.n,
+        }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a RedBlackTreeMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a mut RedBlackTreeMap<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::Rng;
+    use std::collections::BTreeMap;
+
+    fn check(map: &RedBlackTreeMap<i32, i32>, expected: &BTreeMap<i32, i32>) {
+        map.debug_validate();
+        assert_eq!(map.size(), expected.len());
+        assert!(map.iter().eq(expected.iter()));
+    }
+
+    #[test]
+    fn scenario() {
+        let mut map = RedBlackTreeMap::new();
+        assert_eq!(map.get("a"), None);
+        assert_eq!(map.first_key_value(), None);
+
+        assert_eq!(map.insert("b".to_string(), 2), None);
+        assert_eq!(map.insert("a".to_string(), 1), None);
+        assert_eq!(map.insert("d".to_string(), 4), None);
+        assert_eq!(map.insert("a".to_string(), 3), Some(1));
+        assert_eq!(map.size(), 3);
+        map.debug_validate();
+
+        assert_eq!(map.get("a"), Some(&3));
+        assert_eq!(map.get_key_value("b"), Some((&"b".to_string(), &2)));
+        assert!(map.contains_key("d"));
+        assert!(!map.contains_key("c"));
+        assert_eq!(map.find("c"), Some((&"d".to_string(), &4)));
+        assert_eq!(map.find("e"), None);
+        assert_eq!(map.first_key_value(), Some((&"a".to_string(), &3)));
+        assert_eq!(map.last_key_value(), Some((&"d".to_string(), &4)));
+
+        *map.get_mut("b").unwrap() += 10;
+        assert_eq!(map.get("b"), Some(&12));
+        assert_eq!(map.get_mut("c"), None);
+
+        assert_eq!(map.remove("a"), Some(3));
+        assert_eq!(map.remove("a"), None);
+        assert_eq!(map.remove_entry("d"), Some(("d".to_string(), 4)));
+        assert_eq!(map.size(), 1);
+        map.debug_validate();
+        assert_eq!(format!("{map:?}"), r#"{"b": 12}"#);
+    }
+
+    #[test]
+    fn random_operations() {
+        for seed in 0..4 {
+            let mut rng = Rng::with_seed(seed);
+            let mut map = RedBlackTreeMap::new();
+            let mut expected = BTreeMap::new();
+            for step in 0..2000 {
+                let k = (rng.next_u64() % 200) as i32;
+                match rng.next_u64() % 3 {
+                    0 | 1 => assert_eq!(map.insert(k, step), expected.insert(k, step)),
+                    _ => assert_eq!(map.remove(&k), expected.remove(&k)),
+                }
+                assert_eq!(map.get(&k), expected.get(&k));
+                assert_eq!(map.find(&k), expected.range(k..).next());
+                check(&map, &expected);
+            }
+
+            while let Some((&k, _)) = expected.iter().next() {
+                assert_eq!(map.remove(&k), expected.remove(&k));
+                check(&map, &expected);
+            }
+            assert!(map.as_tree().is_empty());
+        }
+    }
+
+    #[test]
+    fn sorted_input_stays_shallow() {
+        let mut map = RedBlackTreeMap::new();
+        for k in 0..1024 {
+            map.insert(k, ());
+        }
+        map.debug_validate();
+        assert!(map.as_tree().height().unwrap() < 2 * 10);
+
+        for k in (0..1024).step_by(2) {
+            map.remove(&k);
+        }
+        map.debug_validate();
+        assert!(map.as_tree().height().unwrap() < 2 * 9);
+    }
+
+    #[test]
+    fn range() {
+        let map = (0..10)
+            .map(|k| (k * 2, k))
+            .fold(RedBlackTreeMap::new(), |mut map, (k, v)| {
+                map.insert(k, v);
+                map
+            });
+        let keys = |r: Range<'_, i32, i32>| r.map(|(&k, _)| k).collect::<Vec<_>>();
+        assert_eq!(keys(map.range(3..9)), [4, 6, 8]);
+        assert_eq!(keys(map.range(4..=8)), [4, 6, 8]);
+        assert_eq!(keys(map.range(..3)), [0, 2]);
+        assert_eq!(keys(map.range(15..)), [16, 18]);
+        assert_eq!(keys(map.range(..)).len(), 10);
+        assert_eq!(
+            keys(map.range((Bound::Excluded(4), Bound::Excluded(10)))),
+            [6, 8]
+        );
+        assert_eq!(keys(map.range(5..5)), []);
+        assert_eq!(
+            keys(map.range((Bound::Included(7), Bound::Excluded(3)))),
+            []
+        );
+        assert_eq!(keys(map.range(19..)), []);
+
+        let mut rng = Rng::with_seed(5);
+        let expected = map
+            .iter()
+            .map(|(&k, &v)| (k, v))
+            .collect::<BTreeMap<_, _>>();
+        for _ in 0..200 {
+            let s = (rng.next_u64() % 24) as i32 - 2;
+            let e = s + (rng.next_u64() % 10) as i32;
+            assert!(map.range(s..e).eq(expected.range(s..e)));
+            assert!(map.range(s..=e).eq(expected.range(s..=e)));
+        }
+    }
+
+    #[test]
+    fn iter() {
+        let mut map = RedBlackTreeMap::new();
+        for k in [3, 1, 4, 0, 2] {
+            map.insert(k, k);
+        }
+        for (k, v) in &mut map {
+            *v += k * 10;
+        }
+        assert_eq!(map.iter().len(), 5);
+        check(&map, &(0..5).map(|k| (k, k * 11)).collect());
+
+        let mut iter = map.into_iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next(), Some((0, 0)));
+        assert_eq!(
+            iter.collect::<Vec<_>>(),
+            [(1, 11), (2, 22), (3, 33), (4, 44)]
+        );
+    }
+
+    #[test]
+    fn drop_elements() {
+        let x = std::rc::Rc::new(0);
+        let mut map = RedBlackTreeMap::new();
+        for k in 0..10 {
+            map.insert(k, x.clone());
+        }
+        drop(map.insert(3, x.clone()));
+        drop(map.remove(&4));
+        assert_eq!(std::rc::Rc::strong_count(&x), 10);
+        drop(map);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+    }
+
+    #[test]
+    fn clear() {
+        let x = std::rc::Rc::new(());
+        let mut map = RedBlackTreeMap::new();
+        for i in 0..10 {
+            map.insert(i, x.clone());
+        }
+        map.clear();
+        assert_eq!(map.size(), 0);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+        assert_eq!(map.first_key_value(), None);
+        map.insert(3, x.clone());
+        map.debug_validate();
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a: RedBlackTreeMap<&str, i32> = [("b", 2), ("a", 1), ("c", 3)].into_iter().collect();
+        let mut b = RedBlackTreeMap::default();
+        b.extend([("a", 1), ("b", 2), ("c", 3)]);
+        assert_eq!(a, b);
+        assert_eq!(crate::util::hash_code(&a), crate::util::hash_code(&b));
+        assert_eq!(a["b"], 2);
+
+        let mut c = a.clone();
+        c.debug_validate();
+        *c.get_mut("b").unwrap() = 5;
+        assert_eq!(a["b"], 2);
+        assert!(a < c);
+    }
+}
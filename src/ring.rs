@@ -0,0 +1,195 @@
+pub(crate) fn slices<T>(a: &[Option<T>], j: usize, n: usize) -> (&[Option<T>], &[Option<T>]) {
+    if j + n <= a.len() {
+        (&a[j..j + n], &[])
+    } else {
+        let (back, front) = a.split_at(j);
+        (front, &back[..j + n - a.len()])
+    }
+}
+
+pub(crate) fn slices_mut<T>(
+    a: &mut [Option<T>],
+    j: usize,
+    n: usize,
+) -> (&mut [Option<T>], &mut [Option<T>]) {
+    if j + n <= a.len() {
+        (&mut a[j..j + n], &mut [])
+    } else {
+        let length = a.len();
+        let (back, front) = a.split_at_mut(j);
+        (front, &mut back[..j + n - length])
+    }
+}
+
+pub struct Iter<'a, T> {
+    front: std::slice::Iter<'a, Option<T>>,
+    back: std::slice::Iter<'a, Option<T>>,
+}
+
+impl<'a, T> Iter<'a, T> {
+    pub(crate) fn new(a: &'a [Option<T>], j: usize, n: usize) -> Self {
+        let (front, back) = slices(a, j, n);
+        Self {
+            front: front.iter(),
+            back: back.iter(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.front.next().or_else(|| self.back.next())?;
+        x.as_ref()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.front.len() + self.back.len();
+        (n, Some(n))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let x = self.back.next_back().or_else(|| self.front.next_back())?;
+        x.as_ref()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    front: std::slice::IterMut<'a, Option<T>>,
+    back: std::slice::IterMut<'a, Option<T>>,
+}
+
+impl<'a, T> IterMut<'a, T> {
+    pub(crate) fn new(a: &'a mut [Option<T>], j: usize, n: usize) -> Self {
+        let (front, back) = slices_mut(a, j, n);
+        Self {
+            front: front.iter_mut(),
+            back: back.iter_mut(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.front.next().or_else(|| self.back.next())?;
+        x.as_mut()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.front.len() + self.back.len();
+        (n, Some(n))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let x = self.back.next_back().or_else(|| self.front.next_back())?;
+        x.as_mut()
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+pub struct IntoIter<T> {
+    a: Box<[Option<T>]>,
+    j: usize,
+    n: usize,
+}
+
+impl<T> IntoIter<T> {
+    pub(crate) fn new(a: Box<[Option<T>]>, j: usize, n: usize) -> Self {
+        Self { a, j, n }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.n == 0 {
+            return None;
+        }
+        let x = self.a[self.j].take();
+        self.j = (self.j + 1) % self.a.len();
+        self.n -= 1;
+        x
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.n, Some(self.n))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.n == 0 {
+            return None;
+        }
+        self.n -= 1;
+        self.a[(self.j + self.n) % self.a.len()].take()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ring(s: &str) -> Box<[Option<char>]> {
+        s.chars().map(|c| (c != '_').then_some(c)).collect()
+    }
+
+    #[test]
+    fn slices_contiguous() {
+        let a = ring("_abc__");
+        let (front, back) = slices(&a, 1, 3);
+        assert_eq!(front, &[Some('a'), Some('b'), Some('c')]);
+        assert!(back.is_empty());
+    }
+
+    #[test]
+    fn slices_wrapped() {
+        let a = ring("cd__ab");
+        let (front, back) = slices(&a, 4, 4);
+        assert_eq!(front, &[Some('a'), Some('b')]);
+        assert_eq!(back, &[Some('c'), Some('d')]);
+    }
+
+    #[test]
+    fn slices_empty() {
+        let a = ring("");
+        let (front, back) = slices(&a, 0, 0);
+        assert!(front.is_empty());
+        assert!(back.is_empty());
+    }
+
+    #[test]
+    fn iter_wrapped() {
+        let a = ring("cd__ab");
+        let mut iter = Iter::new(&a, 4, 4);
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next_back(), Some(&'d'));
+        assert_eq!(iter.next(), Some(&'a'));
+        assert_eq!(iter.next_back(), Some(&'c'));
+        assert_eq!(iter.next_back(), Some(&'b'));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn into_iter_wrapped() {
+        let iter = IntoIter::new(ring("cd__ab"), 4, 4);
+        assert_eq!(iter.collect::<String>(), "abcd");
+
+        let iter = IntoIter::new(ring("cd__ab"), 4, 4);
+        assert_eq!(iter.rev().collect::<String>(), "dcba");
+    }
+}
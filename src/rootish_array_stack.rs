@@ -1,6 +1,14 @@
-use crate::ArrayStack;
+use std::{
+    cmp::Ordering,
+    collections::TryReserveError,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::{Index, IndexMut, RangeBounds},
+};
 
-#[derive(Debug)]
+use crate::{ArrayStack, Error, array_stack, error};
+
+#[derive(Clone)]
 pub struct RootishArrayStack<T> {
     blocks: ArrayStack<Box<[Option<T>]>>,
     n: usize,
@@ -25,6 +33,19 @@ impl<T> RootishArrayStack<T> {
         Self { blocks, n: 0 }
     }
 
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::new(capacity)
+    }
+
+    pub fn try_with_capacity(min_length: usize) -> Result<Self, TryReserveError> {
+        let mut stack = Self {
+            blocks: ArrayStack::new(0),
+            n: 0,
+        };
+        stack.try_reserve(min_length)?;
+        Ok(stack)
+    }
+
     pub fn size(&self) -> usize {
         self.n
     }
@@ -37,25 +58,84 @@ impl<T> RootishArrayStack<T> {
         length
     }
 
+    pub fn capacity(&self) -> usize {
+        self.length()
+    }
+
     pub fn get(&self, i: usize) -> Option<&T> {
+        if i >= self.n {
+            return None;
+        }
         let b = i2b(i);
         let j = i - b * (b + 1) / 2;
         self.blocks.get(b)?.get(j)?.as_ref()
     }
 
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        if i >= self.n {
+            return None;
+        }
+        let b = i2b(i);
+        let j = i - b * (b + 1) / 2;
+        self.blocks.get_mut(b)?.get_mut(j)?.as_mut()
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        let blocks = self.blocks.as_slice();
+        let (blocks, back): (_, &[Option<T>]) = if self.n == 0 {
+            (&blocks[..0], &[])
+        } else {
+            let b = i2b(self.n - 1);
+            let last = blocks[b].as_ref().unwrap();
+            (&blocks[..b], &last[..self.n - b * (b + 1) / 2])
+        };
+        Iter {
+            blocks: blocks.iter(),
+            front: [].iter(),
+            back: back.iter(),
+            len: self.n,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let n = self.n;
+        let blocks = self.blocks.as_mut_slice();
+        let (blocks, back): (_, &mut [Option<T>]) = if n == 0 {
+            (&mut blocks[..0], &mut [])
+        } else {
+            let b = i2b(n - 1);
+            let (blocks, last) = blocks.split_at_mut(b);
+            let last = last[0].as_mut().unwrap();
+            (blocks, &mut last[..n - b * (b + 1) / 2])
+        };
+        IterMut {
+            blocks: blocks.iter_mut(),
+            front: [].iter_mut(),
+            back: back.iter_mut(),
+            len: n,
+        }
+    }
+
     pub(crate) fn take(&mut self, i: usize) -> Option<T> {
+        if i >= self.n {
+            return None;
+        }
         let b = i2b(i);
         let j = i - b * (b + 1) / 2;
         self.blocks.get_mut(b)?.get_mut(j)?.take()
     }
 
     pub fn set(&mut self, i: usize, x: T) -> Option<T> {
+        if i >= self.n {
+            return None;
+        }
         let b = i2b(i);
         let j = i - b * (b + 1) / 2;
         self.blocks.get_mut(b)?.get_mut(j)?.replace(x)
     }
 
     pub fn add(&mut self, i: usize, x: T) {
+        error::assert_insert(i, self.n);
         let r = self.blocks.size();
         if r * (r + 1) / 2 < self.n + 1 {
             self.grow()
@@ -68,34 +148,448 @@ impl<T> RootishArrayStack<T> {
         self.set(i, x);
     }
 
+    pub fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for x in iter {
+            self.add(self.n, x);
+        }
+    }
+
+    // Opens a gap for all of the new elements with one pass over the tail.
+    pub fn insert_many<I: IntoIterator<Item = T>>(&mut self, i: usize, iter: I) {
+        error::assert_insert(i, self.n);
+        let xs = iter.into_iter().collect::<Vec<_>>();
+        let k = xs.len();
+        self.reserve(k);
+        self.n += k;
+        for j in (i..self.n - k).rev() {
+            let tmp = self.take(j).unwrap();
+            self.set(j + k, tmp);
+        }
+        for (m, x) in xs.into_iter().enumerate() {
+            self.set(i + m, x);
+        }
+    }
+
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> IntoIter<T> {
+        let r = error::assert_range(range, self.n);
+        let k = r.len();
+        let mut drained = Self::with_capacity(k);
+        for j in r.clone() {
+            drained.add(j - r.start, self.take(j).unwrap());
+        }
+        for j in r.end..self.n {
+            let tmp = self.take(j).unwrap();
+            self.set(j - k, tmp);
+        }
+        self.n -= k;
+        self.shrink();
+        drained.into_iter()
+    }
+
+    // Moves the elements of `other` over, leaving it empty with its blocks.
+    pub fn append(&mut self, other: &mut Self) {
+        self.reserve(other.n);
+        for j in 0..other.n {
+            let x = other.take(j).unwrap();
+            self.add(self.n, x);
+        }
+        other.n = 0;
+    }
+
+    pub fn split_off(&mut self, i: usize) -> Self {
+        error::assert_insert(i, self.n);
+        let mut other = Self::with_capacity(self.n - i);
+        for j in i..self.n {
+            other.add(j - i, self.take(j).unwrap());
+        }
+        self.n = i;
+        self.shrink();
+        other
+    }
+
+    // Drops `x` and reports the failure if a new block is needed and cannot
+    // be allocated.
+    pub fn try_add(&mut self, i: usize, x: T) -> Result<(), Error> {
+        error::check_insert(i, self.n)?;
+        self.try_reserve(1)?;
+        self.add(i, x);
+        Ok(())
+    }
+
+    // Adds blocks until `additional` more elements fit.
+    pub fn reserve(&mut self, additional: usize) {
+        let m = self.n.checked_add(additional).expect("capacity overflow");
+        let mut r = self.blocks.size();
+        while r * (r + 1) / 2 < m {
+            self.grow();
+            r += 1;
+        }
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let m = self.n.saturating_add(additional);
+        let mut r = self.blocks.size();
+        if r * (r + 1) / 2 >= m {
+            return Ok(());
+        }
+        // No set of blocks could hold more than one array could, and `i2b`
+        // is only exact well below that.
+        if m > isize::MAX as usize / size_of::<Option<T>>() {
+            return crate::util::try_allocate::<T>(m).map(drop);
+        }
+        let target = i2b(m - 1) + 1;
+        self.blocks.try_reserve(target - r)?;
+        while r < target {
+            self.blocks.add(r, crate::util::try_allocate(r + 1)?);
+            r += 1;
+        }
+        Ok(())
+    }
+
     fn grow(&mut self) {
         let block = crate::util::allocate(self.blocks.size() + 1);
         self.blocks.add(self.blocks.size(), block);
     }
 
     pub fn remove(&mut self, i: usize) -> Option<T> {
-        let x = self.take(i);
+        let x = self.take(i)?;
         for j in i..self.n - 1 {
             let tmp = self.take(j + 1).unwrap();
             self.set(j, tmp);
         }
         self.n -= 1;
         let r = self.blocks.size();
-        if (r - 2) * (r - 1) / 2 >= self.n {
+        if r.saturating_sub(2) * r.saturating_sub(1) / 2 >= self.n {
             self.shrink();
         }
-        x
+        Some(x)
+    }
+
+    // Frees every block that holds no elements, where `remove` would keep
+    // up to two.
+    pub fn shrink_to_fit(&mut self) {
+        let mut r = self.blocks.size();
+        while r > 0 && (r - 1) * r / 2 >= self.n {
+            self.blocks.remove(r - 1);
+            r -= 1;
+        }
+        self.blocks.shrink_to_fit();
+    }
+
+    // Drops the elements, keeping the blocks.
+    pub fn clear(&mut self) {
+        for i in 0..self.n {
+            self.take(i);
+        }
+        self.n = 0;
     }
 
     fn shrink(&mut self) {
         let mut r = self.blocks.size();
-        while r > 0 && (r - 2) * (r - 1) / 2 >= self.n {
+        while r > 0 && r.saturating_sub(2) * r.saturating_sub(1) / 2 >= self.n {
             self.blocks.remove(self.blocks.size() - 1);
             r -= 1;
         }
     }
 }
 
+pub struct Iter<'a, T> {
+    blocks: std::slice::Iter<'a, Option<Box<[Option<T>]>>>,
+    front: std::slice::Iter<'a, Option<T>>,
+    back: std::slice::Iter<'a, Option<T>>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = loop {
+            if let Some(x) = self.front.next() {
+                break x;
+            }
+            match self.blocks.next() {
+                Some(block) => self.front = block.as_ref().unwrap().iter(),
+                None => break self.back.next()?,
+            }
+        };
+        self.len -= 1;
+        x.as_ref()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let x = loop {
+            if let Some(x) = self.back.next_back() {
+                break x;
+            }
+            match self.blocks.next_back() {
+                Some(block) => self.back = block.as_ref().unwrap().iter(),
+                None => break self.front.next_back()?,
+            }
+        };
+        self.len -= 1;
+        x.as_ref()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    blocks: std::slice::IterMut<'a, Option<Box<[Option<T>]>>>,
+    front: std::slice::IterMut<'a, Option<T>>,
+    back: std::slice::IterMut<'a, Option<T>>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = loop {
+            if let Some(x) = self.front.next() {
+                break x;
+            }
+            match self.blocks.next() {
+                Some(block) => self.front = block.as_mut().unwrap().iter_mut(),
+                None => break self.back.next()?,
+            }
+        };
+        self.len -= 1;
+        x.as_mut()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let x = loop {
+            if let Some(x) = self.back.next_back() {
+                break x;
+            }
+            match self.blocks.next_back() {
+                Some(block) => self.back = block.as_mut().unwrap().iter_mut(),
+                None => break self.front.next_back()?,
+            }
+        };
+        self.len -= 1;
+        x.as_mut()
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+pub struct IntoIter<T> {
+    blocks: array_stack::IntoIter<Box<[Option<T>]>>,
+    front: std::vec::IntoIter<Option<T>>,
+    back: std::vec::IntoIter<Option<T>>,
+    len: usize,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = loop {
+            if let Some(x) = self.front.next() {
+                break x;
+            }
+            match self.blocks.next() {
+                Some(block) => self.front = block.into_iter(),
+                None => break self.back.next()?,
+            }
+        };
+        self.len -= 1;
+        x
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let x = loop {
+            if let Some(x) = self.back.next_back() {
+                break x;
+            }
+            match self.blocks.next_back() {
+                Some(block) => self.back = block.into_iter(),
+                None => break self.front.next_back()?,
+            }
+        };
+        self.len -= 1;
+        x
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for RootishArrayStack<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        let mut back = Vec::new();
+        if self.n > 0 {
+            let b = i2b(self.n - 1);
+            while self.blocks.size() > b + 1 {
+                self.blocks.remove(self.blocks.size() - 1);
+            }
+            back = Vec::from(self.blocks.remove(b).unwrap());
+            back.truncate(self.n - b * (b + 1) / 2);
+        } else {
+            self.blocks = ArrayStack::new(0);
+        }
+        IntoIter {
+            blocks: self.blocks.into_iter(),
+            front: Vec::new().into_iter(),
+            back: back.into_iter(),
+            len: self.n,
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RootishArrayStack<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut RootishArrayStack<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> Default for RootishArrayStack<T> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RootishArrayStack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for RootishArrayStack<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for RootishArrayStack<T> {}
+
+impl<T: PartialOrd> PartialOrd for RootishArrayStack<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for RootishArrayStack<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for RootishArrayStack<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.n);
+        self.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl<T> FromIterator<T> for RootishArrayStack<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut c = Self::default();
+        c.extend(iter);
+        c
+    }
+}
+
+impl<T> Extend<T> for RootishArrayStack<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        RootishArrayStack::extend(self, iter)
+    }
+}
+
+impl<T> Index<usize> for RootishArrayStack<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        self.get(i)
+            .unwrap_or_else(|| error::index_out_of_bounds(i, self.n))
+    }
+}
+
+impl<T> IndexMut<usize> for RootishArrayStack<T> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        let n = self.n;
+        self.get_mut(i)
+            .unwrap_or_else(|| error::index_out_of_bounds(i, n))
+    }
+}
+
+impl<T> crate::List<T> for RootishArrayStack<T> {
+    fn size(&self) -> usize {
+        RootishArrayStack::size(self)
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        RootishArrayStack::get(self, i)
+    }
+
+    fn set(&mut self, i: usize, x: T) -> Option<T> {
+        RootishArrayStack::set(self, i, x)
+    }
+
+    fn add(&mut self, i: usize, x: T) {
+        RootishArrayStack::add(self, i, x)
+    }
+
+    fn remove(&mut self, i: usize) -> Option<T> {
+        RootishArrayStack::remove(self, i)
+    }
+}
+
+impl<T> crate::sort::Sortable<T> for RootishArrayStack<T> {
+    fn size(&self) -> usize {
+        RootishArrayStack::size(self)
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        RootishArrayStack::get(self, i)
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        if i != j {
+            let x = self.take(i).unwrap();
+            let y = self.take(j).unwrap();
+            self.set(i, y);
+            self.set(j, x);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -174,7 +668,7 @@ mod tests {
         let a3 = vec![Some('g'), Some('h'), None, None].into_boxed_slice();
 
         let a = vec![Some(a0), Some(a1), Some(a2), Some(a3)].into_boxed_slice();
-        let blocks = ArrayStack::from_raw(a, 4);
+        let blocks = ArrayStack::from_raw(a, 4, crate::Doubling);
 
         let stack = RootishArrayStack { blocks, n: 8 };
 
@@ -284,4 +778,178 @@ mod tests {
         assert_eq!(stack.remove(6), Some('g'));
         check(&stack, "axcdef");
     }
+
+    #[test]
+    fn iter() {
+        for len in 0..12 {
+            let mut stack = RootishArrayStack::new(len);
+            let expected = &"abcdefghijk"[..len];
+            for (i, v) in expected.chars().enumerate() {
+                stack.add(i, v);
+            }
+
+            let mut iter = stack.iter();
+            assert_eq!(iter.len(), len);
+            assert_eq!(iter.by_ref().collect::<String>(), expected);
+            assert_eq!(iter.next(), None);
+            assert_eq!(iter.next_back(), None);
+
+            let reversed = expected.chars().rev().collect::<String>();
+            assert_eq!((&stack).into_iter().rev().collect::<String>(), reversed);
+        }
+    }
+
+    #[test]
+    fn iter_both_ends() {
+        let mut stack = RootishArrayStack::new(0);
+        for (i, v) in "abcdefgh".chars().enumerate() {
+            stack.add(i, v);
+        }
+
+        let mut iter = stack.iter();
+        assert_eq!(iter.next(), Some(&'a'));
+        assert_eq!(iter.next_back(), Some(&'h'));
+        assert_eq!(iter.next_back(), Some(&'g'));
+        assert_eq!(iter.next(), Some(&'b'));
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next_back(), Some(&'f'));
+        assert_eq!(iter.next_back(), Some(&'e'));
+        assert_eq!(iter.next_back(), Some(&'d'));
+        assert_eq!(iter.next_back(), Some(&'c'));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn iter_mut() {
+        let mut stack = RootishArrayStack::new(0);
+        for (i, v) in "abcdefgh".chars().enumerate() {
+            stack.add(i, v);
+        }
+
+        for v in stack.iter_mut() {
+            *v = v.to_ascii_uppercase();
+        }
+        check(&stack, "ABCDEFGH");
+
+        for v in (&mut stack).into_iter().rev().take(3) {
+            *v = v.to_ascii_lowercase();
+        }
+        check(&stack, "ABCDEfgh");
+    }
+
+    #[test]
+    fn into_iter() {
+        for len in 0..12 {
+            let mut stack = RootishArrayStack::new(12);
+            let expected = &"abcdefghijk"[..len];
+            for (i, v) in expected.chars().enumerate() {
+                stack.add(i, v);
+            }
+
+            let mut iter = stack.into_iter();
+            assert_eq!(iter.len(), len);
+            if len > 0 {
+                assert_eq!(iter.next_back(), expected.chars().last());
+                assert_eq!(iter.collect::<String>(), &expected[..len - 1]);
+            } else {
+                assert_eq!(iter.next(), None);
+            }
+        }
+    }
+
+    #[test]
+    fn try_reserve() {
+        let mut stack = RootishArrayStack::try_with_capacity(5).unwrap();
+        assert_eq!(stack.length(), 6);
+        for (i, v) in "abcdefg".chars().enumerate() {
+            stack.try_add(i, v).unwrap();
+        }
+        check(&stack, "abcdefg");
+        assert_eq!(stack.length(), 10);
+        stack.try_reserve(8).unwrap();
+        assert_eq!(stack.length(), 15);
+
+        assert!(stack.try_reserve(usize::MAX).is_err());
+        check(&stack, "abcdefg");
+        assert!(RootishArrayStack::<char>::try_with_capacity(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn capacity() {
+        let mut stack = RootishArrayStack::with_capacity(10);
+        assert_eq!(stack.capacity(), 10);
+        for (i, c) in "abc".chars().enumerate() {
+            stack.add(i, c);
+        }
+        stack.shrink_to_fit();
+        assert_eq!(stack.blocks.size(), 2);
+        assert_eq!(stack.capacity(), 3);
+        check(&stack, "abc");
+        stack.reserve(4);
+        assert_eq!(stack.capacity(), 10);
+
+        stack.clear();
+        check(&stack, "");
+        assert_eq!(stack.capacity(), 10);
+        stack.shrink_to_fit();
+        assert_eq!(stack.blocks.size(), 0);
+        stack.add(0, 'z');
+        check(&stack, "z");
+    }
+
+    #[test]
+    fn bulk_operations() {
+        let mut rng = crate::util::Rng::with_seed(24);
+        let mut list = RootishArrayStack::new(0);
+        let mut expected = Vec::new();
+        for _ in 0..300 {
+            let n = expected.len();
+            let i = rng.next_u64() as usize % (n + 1);
+            let xs = (0..rng.next_u64() % 8).map(|x| x * 10).collect::<Vec<_>>();
+            match rng.next_u64() % 4 {
+                0 => {
+                    list.insert_many(i, xs.clone());
+                    expected.splice(i..i, xs);
+                }
+                1 => {
+                    let j = i + rng.next_u64() as usize % (n - i + 1);
+                    assert!(list.drain(i..j).eq(expected.drain(i..j)));
+                }
+                2 => {
+                    let mut tail = list.split_off(i);
+                    assert!(tail.iter().eq(&expected[i..]));
+                    tail.extend(xs.clone());
+                    list.append(&mut tail);
+                    assert_eq!(tail.size(), 0);
+                    expected.extend(xs);
+                }
+                _ => {
+                    list.extend(xs.clone());
+                    expected.extend(xs);
+                }
+            }
+            assert_eq!(list.size(), expected.len());
+            assert!(list.iter().eq(&expected));
+            let r = list.blocks.size();
+            assert!(r * (r + 1) / 2 >= list.size());
+        }
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a: RootishArrayStack<char> = "abcd".chars().collect();
+        let mut b = RootishArrayStack::with_capacity(10);
+        b.extend("abcd".chars());
+        assert_ne!(a.capacity(), b.capacity());
+        assert_eq!(a, b);
+        assert_eq!(crate::util::hash_code(&a), crate::util::hash_code(&b));
+        assert_eq!(format!("{a:?}"), "['a', 'b', 'c', 'd']");
+
+        let mut c = a.clone();
+        c[3] = 'x';
+        assert_eq!(c[3], 'x');
+        assert!(a < c);
+        assert_eq!(RootishArrayStack::<char>::default().size(), 0);
+    }
 }
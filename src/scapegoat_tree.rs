@@ -0,0 +1,433 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use crate::{
+    BinaryTree,
+    binary_tree::{self, Node},
+};
+
+#[derive(Clone)]
+pub struct ScapegoatTree<T> {
+    tree: BinaryTree<T>,
+    n: usize,
+    q: usize,
+    stats: RebuildStats,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RebuildStats {
+    pub rebuilds: u64,
+    pub nodes: u64,
+}
+
+// The largest depth a tree with `q` as its counter may have, `log_{3/2} q`.
+fn log32(q: usize) -> f64 {
+    (q as f64).ln() / 1.5f64.ln()
+}
+
+impl<T> Default for ScapegoatTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ScapegoatTree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T> ScapegoatTree<T> {
+    pub fn new() -> Self {
+        Self {
+            tree: BinaryTree::new(),
+            n: 0,
+            q: 0,
+            stats: RebuildStats::default(),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    pub fn clear(&mut self) {
+        self.tree = BinaryTree::new();
+        self.n = 0;
+        self.q = 0;
+    }
+
+    pub fn as_tree(&self) -> &BinaryTree<T> {
+        &self.tree
+    }
+
+    pub fn rebuild_stats(&self) -> RebuildStats {
+        self.stats
+    }
+
+    pub fn reset_rebuild_stats(&mut self) {
+        self.stats = RebuildStats::default();
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.tree.iter(),
+            len: self.n,
+        }
+    }
+
+    // SAFETY: the caller must guarantee that `u` belongs to this tree.
+    unsafe fn rebuild(&mut self, u: std::ptr::NonNull<Node<T, ()>>) {
+        let ns = unsafe { self.tree.rebuild(u) };
+        self.stats.rebuilds += 1;
+        self.stats.nodes += ns as u64;
+    }
+}
+
+impl<T: Ord> ScapegoatTree<T> {
+    pub fn find(&self, x: &T) -> Option<&T> {
+        self.tree.find(x)
+    }
+
+    pub fn contains(&self, x: &T) -> bool {
+        // SAFETY: the node belongs to the tree, which is borrowed shared.
+        self.tree
+            .find_last(x)
+            .is_some_and(|u| unsafe { Node::x(u) } == x)
+    }
+
+    pub fn add(&mut self, x: T) -> bool {
+        let p = self.tree.find_last(&x);
+        // SAFETY: `p` belongs to the tree, the new node is linked in below it,
+        // and the scapegoat search only climbs through its ancestors.
+        unsafe {
+            if p.is_some_and(|p| Node::x(p) == &x) {
+                return false;
+            }
+            let u = Node::alloc(x, ());
+            self.tree.add_child(p, u);
+            self.n += 1;
+            self.q += 1;
+
+            if self.tree.depth_at(u) as f64 > log32(self.q) {
+                let mut w = p.unwrap();
+                let mut wp = Node::parent(w).unwrap();
+                while 3 * self.tree.size_at(w) <= 2 * self.tree.size_at(wp) {
+                    w = wp;
+                    wp = Node::parent(w).unwrap();
+                }
+                self.rebuild(wp);
+            }
+        }
+        true
+    }
+
+    pub fn remove(&mut self, x: &T) -> Option<T> {
+        let u = self.tree.find_last(x)?;
+        // SAFETY: `u` belongs to the tree, and the node `remove_node` unlinks
+        // is freed exactly once.
+        unsafe {
+            if Node::x(u) != x {
+                return None;
+            }
+            let w = self.tree.remove_node(u);
+            self.n -= 1;
+            if 2 * self.n < self.q {
+                if let Some(r) = self.tree.root() {
+                    self.rebuild(r);
+                }
+                self.q = self.n;
+            }
+            Some(Node::into_x(w))
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for ScapegoatTree<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for ScapegoatTree<T> {}
+
+impl<T: PartialOrd> PartialOrd for ScapegoatTree<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for ScapegoatTree<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for ScapegoatTree<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.size());
+        self.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl<T: Ord> FromIterator<T> for ScapegoatTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut c = Self::new();
+        c.extend(iter);
+        c
+    }
+}
+
+impl<T: Ord> Extend<T> for ScapegoatTree<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.add(x);
+        }
+    }
+}
+
+impl<T: Ord> crate::SSet<T> for ScapegoatTree<T> {
+    fn size(&self) -> usize {
+        ScapegoatTree::size(self)
+    }
+
+    fn add(&mut self, x: T) -> bool {
+        ScapegoatTree::add(self, x)
+    }
+
+    fn remove(&mut self, x: &T) -> Option<T> {
+        ScapegoatTree::remove(self, x)
+    }
+
+    fn find(&self, x: &T) -> Option<&T> {
+        ScapegoatTree::find(self, x)
+    }
+}
+
+pub struct Iter<'a, T> {
+    inner: binary_tree::Iter<'a, T>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.inner.next()?;
+        self.len -= 1;
+        Some(x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IntoIter<T> {
+    inner: binary_tree::IntoIter<T>,
+    len: usize,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.inner.next()?;
+        self.len -= 1;
+        Some(x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for ScapegoatTree<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.tree.into_iter(),
+            len: self.n,
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ScapegoatTree<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::Rng;
+    use std::collections::BTreeSet;
+
+    fn check(set: &ScapegoatTree<i32>, expected: &BTreeSet<i32>) {
+        assert_eq!(set.size(), expected.len());
+        assert_eq!(set.tree.size(), expected.len());
+        assert!(set.iter().eq(expected.iter()));
+        assert!(set.q / 2 <= set.n && set.n <= set.q);
+        let height = set.tree.height().unwrap_or(0);
+        assert!(height as f64 <= log32(set.q).max(0.0), "height {height}");
+        binary_tree::check_links(&set.tree);
+    }
+
+    #[test]
+    fn scenario() {
+        let mut set = ScapegoatTree::new();
+        assert_eq!(set.find(&0), None);
+
+        for x in [5, 2, 8, 1, 3, 7, 9] {
+            assert!(set.add(x));
+        }
+        assert!(!set.add(3));
+        assert_eq!(set.size(), 7);
+
+        assert_eq!(set.find(&0), Some(&1));
+        assert_eq!(set.find(&4), Some(&5));
+        assert_eq!(set.find(&10), None);
+        assert!(set.contains(&8));
+        assert!(!set.contains(&4));
+
+        assert_eq!(set.remove(&5), Some(5));
+        assert_eq!(set.remove(&5), None);
+        check(&set, &BTreeSet::from([1, 2, 3, 7, 8, 9]));
+        assert_eq!(format!("{set:?}"), "{1, 2, 3, 7, 8, 9}");
+    }
+
+    #[test]
+    fn sorted_input_rebuilds() {
+        let mut set = ScapegoatTree::new();
+        let mut expected = BTreeSet::new();
+        for x in 0..1000 {
+            set.add(x);
+            expected.insert(x);
+            check(&set, &expected);
+        }
+        assert!(set.rebuild_stats().rebuilds > 0);
+
+        set.reset_rebuild_stats();
+        assert_eq!(set.rebuild_stats(), RebuildStats::default());
+        for x in 0..600 {
+            set.remove(&x);
+            expected.remove(&x);
+            check(&set, &expected);
+        }
+        assert_eq!(set.rebuild_stats().rebuilds, 1);
+        assert_eq!(set.rebuild_stats().nodes, 499);
+    }
+
+    #[test]
+    fn random_operations() {
+        let mut rng = Rng::with_seed(1);
+        let mut set = ScapegoatTree::new();
+        let mut expected = BTreeSet::new();
+        for _ in 0..3000 {
+            let x = (rng.next_u64() % 300) as i32;
+            match rng.next_u64() % 3 {
+                0 | 1 => assert_eq!(set.add(x), expected.insert(x)),
+                _ => assert_eq!(set.remove(&x), expected.take(&x)),
+            }
+            assert_eq!(set.find(&x), expected.range(x..).next());
+            check(&set, &expected);
+        }
+    }
+
+    #[test]
+    fn amortized_rebuild_cost() {
+        // Starting from an empty tree, m operations move O(m log m) nodes in
+        // total through rebuilds.
+        for seed in 0..4 {
+            let mut rng = Rng::with_seed(seed);
+            let mut set = ScapegoatTree::new();
+            let m = 20_000;
+            for i in 0..m {
+                if i % 3 == 2 {
+                    set.remove(&(rng.next_u64() % 5000));
+                } else {
+                    set.add(rng.next_u64() % 5000);
+                }
+            }
+            let bound = 4.0 * m as f64 * log32(m);
+            assert!((set.rebuild_stats().nodes as f64) <= bound);
+        }
+
+        let mut set = ScapegoatTree::new();
+        let m = 20_000;
+        for x in 0..m {
+            set.add(x);
+        }
+        let bound = 4.0 * m as f64 * log32(m);
+        assert!((set.rebuild_stats().nodes as f64) <= bound);
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut set = ScapegoatTree::new();
+        for x in [4, 2, 8, 6] {
+            set.add(x);
+        }
+        let mut iter = set.into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.collect::<Vec<_>>(), [4, 6, 8]);
+    }
+
+    #[test]
+    fn drop_elements() {
+        let x = std::rc::Rc::new(0);
+        let mut set = ScapegoatTree::new();
+        for i in 0..10 {
+            set.add((i, x.clone()));
+        }
+        drop(set.remove(&(3, x.clone())));
+        assert_eq!(std::rc::Rc::strong_count(&x), 10);
+        drop(set);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+    }
+
+    #[test]
+    fn clear() {
+        let x = std::rc::Rc::new(());
+        let mut tree = ScapegoatTree::new();
+        for i in 0..10 {
+            tree.add((i, x.clone()));
+        }
+        tree.clear();
+        assert_eq!(tree.size(), 0);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+        assert!(tree.add((0, x.clone())));
+        assert!(tree.contains(&(0, x.clone())));
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a: ScapegoatTree<char> = "dbfaceg".chars().collect();
+        let mut b = ScapegoatTree::new();
+        b.extend("abcdefg".chars());
+        assert_eq!(a, b);
+        assert_eq!(crate::util::hash_code(&a), crate::util::hash_code(&b));
+        assert_eq!(format!("{a:?}"), "{'a', 'b', 'c', 'd', 'e', 'f', 'g'}");
+
+        let mut c = a.clone();
+        assert_eq!(c.as_tree().height(), a.as_tree().height());
+        c.remove(&'a');
+        assert!(a.contains(&'a'));
+        assert!(a < c);
+        assert_eq!(c.cmp(&c.clone()), Ordering::Equal);
+    }
+}
@@ -0,0 +1,772 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::Flatten,
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    ArrayDeque, DLList, array_deque,
+    doubly_linked_list::{self, CursorMut},
+};
+
+#[derive(Clone)]
+struct BDeque<T> {
+    d: ArrayDeque<T>,
+}
+
+impl<T> BDeque<T> {
+    fn new(b: usize) -> Self {
+        Self {
+            d: ArrayDeque::new(b + 1),
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.d.size()
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        self.d.get(i)
+    }
+
+    fn set(&mut self, i: usize, x: T) -> Option<T> {
+        self.d.set(i, x)
+    }
+
+    fn add(&mut self, i: usize, x: T) {
+        self.d.add_no_resize(i, x);
+    }
+
+    fn remove(&mut self, i: usize) -> Option<T> {
+        self.d.remove_no_resize(i)
+    }
+
+    fn add_first(&mut self, x: T) {
+        self.add(0, x);
+    }
+
+    fn add_last(&mut self, x: T) {
+        self.add(self.size(), x);
+    }
+
+    fn remove_first(&mut self) -> T {
+        self.remove(0).unwrap()
+    }
+
+    fn remove_last(&mut self) -> T {
+        self.remove(self.size() - 1).unwrap()
+    }
+}
+
+impl<T> IntoIterator for BDeque<T> {
+    type Item = T;
+    type IntoIter = array_deque::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.d.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a BDeque<T> {
+    type Item = &'a T;
+    type IntoIter = array_deque::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.d.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut BDeque<T> {
+    type Item = &'a mut T;
+    type IntoIter = array_deque::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.d.iter_mut()
+    }
+}
+
+// The block size of a list made by `default` or `collect`.
+const DEFAULT_BLOCK_SIZE: usize = 16;
+
+#[derive(Clone)]
+pub struct SEList<T> {
+    blocks: DLList<BDeque<T>>,
+    n: usize,
+    b: usize,
+}
+
+impl<T> Default for SEList<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_BLOCK_SIZE)
+    }
+}
+
+fn locate_mut<T>(
+    blocks: &mut DLList<BDeque<T>>,
+    n: usize,
+    i: usize,
+) -> (CursorMut<'_, BDeque<T>>, usize) {
+    if i < n / 2 {
+        let mut cursor = blocks.cursor_front_mut();
+        let mut j = i;
+        while let Some(u) = cursor.current() {
+            if j < u.size() {
+                break;
+            }
+            j -= u.size();
+            cursor.move_next();
+        }
+        (cursor, j)
+    } else {
+        let mut cursor = blocks.cursor_back_mut();
+        let mut start = n;
+        while let Some(u) = cursor.current() {
+            start -= u.size();
+            if i >= start {
+                return (cursor, i - start);
+            }
+            cursor.move_prev();
+        }
+        (cursor, 0)
+    }
+}
+
+// Fills the `b` full blocks starting at the cursor up to `b` elements each
+// by inserting a new block after them. The cursor is left where it started.
+fn spread<T>(cursor: &mut CursorMut<'_, BDeque<T>>, b: usize) {
+    for _ in 0..b {
+        cursor.move_next();
+    }
+    cursor.insert_before(BDeque::new(b));
+    cursor.move_prev();
+    for _ in 0..b {
+        while cursor.current().unwrap().size() < b {
+            let x = cursor.peek_prev().unwrap().remove_last();
+            cursor.current().unwrap().add_first(x);
+        }
+        cursor.move_prev();
+    }
+}
+
+// Merges the `b` blocks of `b - 1` elements starting at the cursor into
+// `b - 1` full blocks. The cursor is left where it started.
+fn gather<T>(cursor: &mut CursorMut<'_, BDeque<T>>, b: usize) {
+    for _ in 0..b - 1 {
+        while cursor.current().unwrap().size() < b {
+            let x = cursor.peek_next().unwrap().remove_first();
+            cursor.current().unwrap().add_last(x);
+        }
+        cursor.move_next();
+    }
+    cursor.remove_current();
+    for _ in 0..b - 1 {
+        cursor.move_prev();
+    }
+}
+
+impl<T> SEList<T> {
+    pub fn new(b: usize) -> Self {
+        assert!(b > 0, "block size must be positive");
+        Self {
+            blocks: DLList::new(),
+            n: 0,
+            b,
+        }
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.b
+    }
+
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new(self.b);
+    }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if i >= self.n {
+            return None;
+        }
+
+        if i < self.n / 2 {
+            let mut j = i;
+            for u in self.blocks.iter() {
+                if j < u.size() {
+                    return u.get(j);
+                }
+                j -= u.size();
+            }
+        } else {
+            let mut start = self.n;
+            for u in self.blocks.iter().rev() {
+                start -= u.size();
+                if i >= start {
+                    return u.get(i - start);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        if i >= self.n {
+            return None;
+        }
+
+        if i < self.n / 2 {
+            let mut j = i;
+            for u in self.blocks.iter_mut() {
+                if j < u.size() {
+                    return u.d.get_mut(j);
+                }
+                j -= u.size();
+            }
+        } else {
+            let mut start = self.n;
+            for u in self.blocks.iter_mut().rev() {
+                start -= u.size();
+                if i >= start {
+                    return u.d.get_mut(i - start);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.blocks.iter().flatten(),
+            len: self.n,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            inner: self.blocks.iter_mut().flatten(),
+            len: self.n,
+        }
+    }
+
+    pub fn set(&mut self, i: usize, x: T) -> Option<T> {
+        if i >= self.n {
+            return None;
+        }
+        let (mut cursor, j) = locate_mut(&mut self.blocks, self.n, i);
+        cursor.current()?.set(j, x)
+    }
+
+    pub fn add(&mut self, i: usize, x: T) {
+        crate::error::assert_insert(i, self.n);
+
+        let b = self.b;
+        if i == self.n {
+            if self
+                .blocks
+                .iter()
+                .next_back()
+                .is_none_or(|u| u.size() == b + 1)
+            {
+                self.blocks.add(self.blocks.size(), BDeque::new(b));
+            }
+            self.blocks.iter_mut().next_back().unwrap().add_last(x);
+            self.n += 1;
+            return;
+        }
+
+        let (mut cursor, j) = locate_mut(&mut self.blocks, self.n, i);
+        let mut r = 0;
+        while r < b && cursor.current().is_some_and(|u| u.size() == b + 1) {
+            cursor.move_next();
+            r += 1;
+        }
+        if r == b {
+            for _ in 0..r {
+                cursor.move_prev();
+            }
+            spread(&mut cursor, b);
+            r = 0;
+        } else if cursor.current().is_none() {
+            cursor.insert_before(BDeque::new(b));
+            cursor.move_prev();
+        }
+        for _ in 0..r {
+            let y = cursor.peek_prev().unwrap().remove_last();
+            cursor.current().unwrap().add_first(y);
+            cursor.move_prev();
+        }
+        cursor.current().unwrap().add(j, x);
+        self.n += 1;
+    }
+
+    pub fn remove(&mut self, i: usize) -> Option<T> {
+        if i >= self.n {
+            return None;
+        }
+
+        let b = self.b;
+        let (mut cursor, j) = locate_mut(&mut self.blocks, self.n, i);
+        let mut r = 0;
+        while r < b && cursor.current().is_some_and(|u| u.size() == b - 1) {
+            cursor.move_next();
+            r += 1;
+        }
+        for _ in 0..r {
+            cursor.move_prev();
+        }
+        if r == b {
+            gather(&mut cursor, b);
+        }
+
+        let x = cursor.current().unwrap().remove(j);
+        while cursor.current().unwrap().size() < b - 1 {
+            let Some(next) = cursor.peek_next() else {
+                break;
+            };
+            let y = next.remove_first();
+            cursor.current().unwrap().add_last(y);
+            cursor.move_next();
+        }
+        if cursor.current().unwrap().size() == 0 {
+            cursor.remove_current();
+        }
+        self.n -= 1;
+        x
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SEList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for SEList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for SEList<T> {}
+
+impl<T: PartialOrd> PartialOrd for SEList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for SEList<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for SEList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.n);
+        self.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl<T> FromIterator<T> for SEList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut c = Self::default();
+        c.extend(iter);
+        c
+    }
+}
+
+impl<T> Extend<T> for SEList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.add(self.n, x);
+        }
+    }
+}
+
+impl<T> Index<usize> for SEList<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        self.get(i)
+            .unwrap_or_else(|| crate::error::index_out_of_bounds(i, self.n))
+    }
+}
+
+impl<T> IndexMut<usize> for SEList<T> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        let n = self.n;
+        self.get_mut(i)
+            .unwrap_or_else(|| crate::error::index_out_of_bounds(i, n))
+    }
+}
+
+impl<T> crate::List<T> for SEList<T> {
+    fn size(&self) -> usize {
+        SEList::size(self)
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        SEList::get(self, i)
+    }
+
+    fn set(&mut self, i: usize, x: T) -> Option<T> {
+        SEList::set(self, i, x)
+    }
+
+    fn add(&mut self, i: usize, x: T) {
+        SEList::add(self, i, x)
+    }
+
+    fn remove(&mut self, i: usize) -> Option<T> {
+        SEList::remove(self, i)
+    }
+}
+
+pub struct Iter<'a, T> {
+    inner: Flatten<doubly_linked_list::Iter<'a, BDeque<T>>>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.inner.next()?;
+        self.len -= 1;
+        Some(x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let x = self.inner.next_back()?;
+        self.len -= 1;
+        Some(x)
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    inner: Flatten<doubly_linked_list::IterMut<'a, BDeque<T>>>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.inner.next()?;
+        self.len -= 1;
+        Some(x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let x = self.inner.next_back()?;
+        self.len -= 1;
+        Some(x)
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+pub struct IntoIter<T> {
+    inner: Flatten<doubly_linked_list::IntoIter<BDeque<T>>>,
+    len: usize,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.inner.next()?;
+        self.len -= 1;
+        Some(x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let x = self.inner.next_back()?;
+        self.len -= 1;
+        Some(x)
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for SEList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            len: self.n,
+            inner: self.blocks.into_iter().flatten(),
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SEList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut SEList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(list: &SEList<char>, expected: &str) {
+        assert_eq!(list.size(), expected.len());
+        for (i, v) in expected.chars().enumerate() {
+            assert_eq!(list.get(i), Some(&v));
+        }
+        assert_eq!(list.get(expected.len()), None);
+    }
+
+    fn check_blocks(list: &SEList<char>, expected: &[&str]) {
+        assert_eq!(list.blocks.size(), expected.len());
+        for (u, s) in std::iter::zip(list.blocks.iter(), expected) {
+            assert_eq!(u.d.length(), list.b + 1);
+            assert_eq!(u.size(), s.len());
+            for (j, v) in s.chars().enumerate() {
+                assert_eq!(u.get(j), Some(&v));
+            }
+        }
+    }
+
+    fn create(b: usize, blocks: &[&str]) -> SEList<char> {
+        let mut list = SEList::new(b);
+        for s in blocks {
+            let mut u = BDeque::new(b);
+            for v in s.chars() {
+                u.add_last(v);
+            }
+            list.blocks.add(list.blocks.size(), u);
+            list.n += s.len();
+        }
+        list
+    }
+
+    #[test]
+    fn test_create_helper() {
+        let list = create(3, &["abc", "defg", "hi"]);
+        check(&list, "abcdefghi");
+        check_blocks(&list, &["abc", "defg", "hi"]);
+    }
+
+    #[test]
+    fn add_to_end() {
+        let mut list = SEList::new(3);
+        for (i, v) in "abcdefghi".chars().enumerate() {
+            list.add(i, v);
+        }
+        check(&list, "abcdefghi");
+        check_blocks(&list, &["abcd", "efgh", "i"]);
+    }
+
+    #[test]
+    fn add_shifts_into_next_block() {
+        let mut list = create(3, &["abcd", "efg"]);
+        list.add(1, 'x');
+        check_blocks(&list, &["axbc", "defg"]);
+    }
+
+    #[test]
+    fn add_appends_block() {
+        let mut list = create(3, &["abcd", "efgh"]);
+        list.add(5, 'x');
+        check_blocks(&list, &["abcd", "exfg", "h"]);
+    }
+
+    #[test]
+    fn add_spreads() {
+        let mut list = create(2, &["abc", "def", "g"]);
+        list.add(0, 'x');
+        check_blocks(&list, &["xab", "cd", "ef", "g"]);
+    }
+
+    #[test]
+    fn remove_pulls_from_next_block() {
+        let mut list = create(3, &["ab", "def", "gh"]);
+        assert_eq!(list.remove(0), Some('a'));
+        check_blocks(&list, &["bd", "ef", "gh"]);
+    }
+
+    #[test]
+    fn remove_gathers() {
+        let mut list = create(3, &["ab", "cd", "ef", "g"]);
+        assert_eq!(list.remove(0), Some('a'));
+        check_blocks(&list, &["bc", "def", "g"]);
+    }
+
+    #[test]
+    fn remove_drops_empty_block() {
+        let mut list = create(3, &["abcd", "e"]);
+        assert_eq!(list.remove(4), Some('e'));
+        check_blocks(&list, &["abcd"]);
+        assert_eq!(list.remove(4), None);
+    }
+
+    #[test]
+    fn scenario() {
+        for b in 1..6 {
+            let mut list = SEList::new(b);
+            let mut expected = String::new();
+            for k in 0..50 {
+                let i = k * 7 % (expected.len() + 1);
+                let v = char::from(b'a' + (k % 26) as u8);
+                list.add(i, v);
+                expected.insert(i, v);
+                check(&list, &expected);
+            }
+            for k in 0..20 {
+                let i = k * 5 % expected.len();
+                assert_eq!(list.set(i, 'X'), expected[i..].chars().next());
+                expected.replace_range(i..=i, "X");
+                check(&list, &expected);
+            }
+            for k in 0..50 {
+                let i = k * 3 % expected.len();
+                assert_eq!(list.remove(i), Some(expected.remove(i)));
+                check(&list, &expected);
+            }
+            assert_eq!(list.blocks.size(), 0);
+        }
+    }
+
+    #[test]
+    fn block_sizes_stay_bounded() {
+        let b = 4;
+        let mut list = SEList::new(b);
+        for k in 0..200 {
+            list.add(k * 13 % (k + 1), k);
+        }
+        for k in 0..150 {
+            list.remove(k * 11 % list.size());
+        }
+        let sizes = list.blocks.iter().map(BDeque::size).collect::<Vec<_>>();
+        let (last, rest) = sizes.split_last().unwrap();
+        assert!(rest.iter().all(|&s| b - 1 <= s && s <= b + 1));
+        assert!(*last <= b + 1);
+    }
+
+    #[test]
+    fn clear() {
+        let x = std::rc::Rc::new(());
+        let mut list = SEList::new(3);
+        for i in 0..10 {
+            list.add(i, x.clone());
+        }
+        list.clear();
+        assert_eq!(list.size(), 0);
+        assert_eq!(list.block_size(), 3);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+        list.add(0, x.clone());
+        assert_eq!(list.get(0), Some(&x));
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a = create(2, &["ab", "cd", "e"]);
+        let b = create(3, &["abc", "de"]);
+        assert_eq!(a, b);
+        assert_eq!(crate::util::hash_code(&a), crate::util::hash_code(&b));
+        assert_eq!(format!("{a:?}"), "['a', 'b', 'c', 'd', 'e']");
+
+        let mut c = a.clone();
+        check_blocks(&c, &["ab", "cd", "e"]);
+        c[1] = 'x';
+        c[4] = 'y';
+        check(&c, "axcdy");
+        assert!(a < c);
+        c.extend("z".chars());
+        assert_eq!(c[5], 'z');
+
+        let d = "abcde".chars().collect::<SEList<_>>();
+        assert_eq!(d, a);
+        assert_eq!(d.block_size(), DEFAULT_BLOCK_SIZE);
+        assert_eq!(SEList::<char>::default().size(), 0);
+    }
+
+    #[test]
+    fn iter() {
+        let list = create(2, &["ab", "c", "de"]);
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next(), Some(&'a'));
+        assert_eq!(iter.next_back(), Some(&'e'));
+        assert_eq!(iter.next_back(), Some(&'d'));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next(), Some(&'b'));
+        assert_eq!(iter.next(), Some(&'c'));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        assert_eq!((&list).into_iter().rev().collect::<String>(), "edcba");
+        assert_eq!(SEList::<char>::new(3).iter().next(), None);
+    }
+
+    #[test]
+    fn iter_mut() {
+        let mut list = create(2, &["ab", "c", "de"]);
+        for c in list.iter_mut() {
+            *c = c.to_ascii_uppercase();
+        }
+        check(&list, "ABCDE");
+
+        for c in (&mut list).into_iter().rev().take(3) {
+            *c = c.to_ascii_lowercase();
+        }
+        check(&list, "ABcde");
+    }
+
+    #[test]
+    fn into_iter() {
+        let list = create(2, &["ab", "c", "de"]);
+        let mut iter = list.into_iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next_back(), Some('e'));
+        assert_eq!(iter.collect::<String>(), "abcd");
+
+        let x = std::rc::Rc::new(());
+        let mut list = SEList::new(2);
+        list.extend(std::iter::repeat_n(x.clone(), 7));
+        let mut iter = list.into_iter();
+        drop(iter.next());
+        drop(iter);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+    }
+}
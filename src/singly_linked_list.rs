@@ -1,27 +1,48 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ptr::NonNull,
+};
 
-#[derive(Debug)]
 pub struct SLList<T> {
-    head: Option<Rc<RefCell<Node<T>>>>,
-    tail: Option<Rc<RefCell<Node<T>>>>,
+    head: Option<NonNull<Node<T>>>,
+    tail: Option<NonNull<Node<T>>>,
     n: usize,
+    marker: PhantomData<Box<Node<T>>>,
 }
 
+// SAFETY: `SLList` owns its nodes exclusively, like `Box<Node<T>>` would.
+unsafe impl<T: Send> Send for SLList<T> {}
+unsafe impl<T: Sync> Sync for SLList<T> {}
+
 impl<T> Default for SLList<T> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-#[derive(Debug)]
+impl<T> Drop for SLList<T> {
+    fn drop(&mut self) {
+        while self.remove().is_some() {}
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SLList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
 struct Node<T> {
     x: T,
-    next: Option<Rc<RefCell<Node<T>>>>,
+    next: Option<NonNull<Node<T>>>,
 }
 
 impl<T> Node<T> {
-    fn new(x: T) -> Self {
-        Self { x, next: None }
+    fn alloc(x: T) -> NonNull<Self> {
+        NonNull::from(Box::leak(Box::new(Self { x, next: None })))
     }
 }
 
@@ -31,6 +52,7 @@ impl<T> SLList<T> {
             head: None,
             tail: None,
             n: 0,
+            marker: PhantomData,
         }
     }
 
@@ -38,25 +60,46 @@ impl<T> SLList<T> {
         self.n
     }
 
+    pub fn clear(&mut self) {
+        while self.remove().is_some() {}
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head,
+            len: self.n,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            next: self.head,
+            len: self.n,
+            marker: PhantomData,
+        }
+    }
+
     pub fn push(&mut self, x: T) {
-        let u = Rc::new(RefCell::new(Node::new(x)));
-        u.borrow_mut().next = self.head.take();
+        let u = Node::alloc(x);
+        // SAFETY: `u` was just allocated and is not aliased.
+        unsafe {
+            (*u.as_ptr()).next = self.head;
+        }
         if self.n == 0 {
-            self.tail = Some(u.clone());
+            self.tail = Some(u);
         }
         self.head = Some(u);
         self.n += 1;
     }
 
     pub fn pop(&mut self) -> Option<T> {
-        if self.n == 0 {
-            return None;
-        }
+        let old_head = self.head?;
+        // SAFETY: the head was allocated by `Node::alloc` and, once unlinked,
+        // is owned by nothing else.
+        let old_head = unsafe { Box::from_raw(old_head.as_ptr()) };
 
-        let old_head = self.head.take().unwrap();
-        let new_head = old_head.borrow_mut().next.take();
-
-        self.head = new_head;
+        self.head = old_head.next;
 
         self.n -= 1;
 
@@ -64,16 +107,15 @@ impl<T> SLList<T> {
             self.tail = None;
         }
 
-        Rc::into_inner(old_head).map(|rc| rc.into_inner().x)
+        Some(old_head.x)
     }
 
     pub fn add(&mut self, x: T) {
-        let u = Rc::new(RefCell::new(Node::new(x)));
-        if self.n == 0 {
-            self.head = Some(u.clone());
-        } else {
-            let old_tail = self.tail.take().unwrap();
-            old_tail.borrow_mut().next = Some(u.clone());
+        let u = Node::alloc(x);
+        match self.tail {
+            None => self.head = Some(u),
+            // SAFETY: the tail is a live node owned by the list.
+            Some(old_tail) => unsafe { (*old_tail.as_ptr()).next = Some(u) },
         }
 
         self.tail = Some(u);
@@ -81,52 +123,209 @@ impl<T> SLList<T> {
     }
 
     pub fn remove(&mut self) -> Option<T> {
-        if self.n == 0 {
-            return None;
-        }
+        self.pop()
+    }
+}
 
-        let old_head = self.head.take().unwrap();
-        let new_head = old_head.borrow_mut().next.take();
+pub struct Iter<'a, T> {
+    next: Option<NonNull<Node<T>>>,
+    len: usize,
+    marker: PhantomData<&'a Node<T>>,
+}
 
-        self.head = new_head;
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
 
-        self.n -= 1;
+    fn next(&mut self) -> Option<Self::Item> {
+        let u = self.next?;
+        // SAFETY: the list keeps its nodes alive and unmodified for `'a`.
+        let u = unsafe { &*u.as_ptr() };
+        self.next = u.next;
+        self.len -= 1;
+        Some(&u.x)
+    }
 
-        if self.n == 0 {
-            self.tail = None;
-        }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
 
-        Rc::into_inner(old_head).map(|v| v.into_inner().x)
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    next: Option<NonNull<Node<T>>>,
+    len: usize,
+    marker: PhantomData<&'a mut Node<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let u = self.next?;
+        // SAFETY: the list is borrowed exclusively for `'a` and every node is
+        // yielded at most once.
+        let u = unsafe { &mut *u.as_ptr() };
+        self.next = u.next;
+        self.len -= 1;
+        Some(&mut u.x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+pub struct IntoIter<T> {
+    list: SLList<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.remove()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.n, Some(self.list.n))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for SLList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SLList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut SLList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T: Clone> Clone for SLList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
     }
 }
 
+impl<T: PartialEq> PartialEq for SLList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for SLList<T> {}
+
+impl<T: PartialOrd> PartialOrd for SLList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for SLList<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for SLList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.n);
+        self.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl<T> FromIterator<T> for SLList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut c = Self::new();
+        c.extend(iter);
+        c
+    }
+}
+
+impl<T> Extend<T> for SLList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|x| self.add(x));
+    }
+}
+
+impl<T> crate::Queue<T> for SLList<T> {
+    fn size(&self) -> usize {
+        SLList::size(self)
+    }
+
+    fn add(&mut self, x: T) {
+        SLList::add(self, x)
+    }
+
+    fn remove(&mut self) -> Option<T> {
+        SLList::remove(self)
+    }
+}
+
+impl<T> crate::Stack<T> for SLList<T> {
+    fn size(&self) -> usize {
+        SLList::size(self)
+    }
+
+    fn push(&mut self, x: T) {
+        SLList::push(self, x)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        SLList::pop(self)
+    }
+}
+
+// Run under Miri along with `DLList`; see the note on its tests.
 #[cfg(test)]
 mod tests {
     use super::*;
 
     impl<T> SLList<T> {
-        fn get(&self, i: usize) -> Option<Rc<RefCell<Node<T>>>> {
+        fn get(&self, i: usize) -> Option<&T> {
             if self.n == 0 {
                 return None;
             }
 
             if i == self.n - 1 {
-                return self.tail.clone();
+                return self.tail.map(|u| unsafe { &(*u.as_ptr()).x });
             }
 
-            let mut cursor = self.head.clone();
+            let mut cursor = self.head;
 
             for _ in 0..i {
-                cursor = cursor?.borrow().next.clone();
+                cursor = unsafe { (*cursor?.as_ptr()).next };
             }
 
-            cursor.clone()
+            cursor.map(|u| unsafe { &(*u.as_ptr()).x })
         }
     }
 
     fn check(list: &SLList<char>, expected: &str) {
         for (i, v) in expected.chars().enumerate() {
-            assert_eq!(list.get(i).unwrap().borrow().x, v);
+            assert_eq!(list.get(i), Some(&v));
         }
         assert_eq!(list.size(), expected.len());
     }
@@ -208,4 +407,88 @@ mod tests {
         list.push('y');
         check(&list, "ycdex");
     }
+
+    #[test]
+    fn iter() {
+        let mut list = SLList::new();
+        for v in "abcd".chars() {
+            list.add(v);
+        }
+
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next(), Some(&'a'));
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.collect::<String>(), "bcd");
+
+        for v in &mut list {
+            *v = v.to_ascii_uppercase();
+        }
+        check(&list, "ABCD");
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.next(), Some('A'));
+        assert_eq!(iter.collect::<String>(), "BCD");
+    }
+
+    #[test]
+    fn debug() {
+        let mut list = SLList::new();
+        list.add('a');
+        list.add('b');
+        assert_eq!(format!("{list:?}"), "['a', 'b']");
+    }
+
+    #[test]
+    fn drop_elements() {
+        let x = std::rc::Rc::new(());
+
+        let mut list = SLList::new();
+        for _ in 0..5 {
+            list.add(x.clone());
+            list.push(x.clone());
+        }
+        assert_eq!(std::rc::Rc::strong_count(&x), 11);
+
+        drop(list.pop());
+        drop(list.remove());
+        assert_eq!(std::rc::Rc::strong_count(&x), 9);
+
+        let mut iter = list.into_iter();
+        iter.next();
+        drop(iter);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+    }
+
+    #[test]
+    fn clear() {
+        let x = std::rc::Rc::new(());
+        let mut list = SLList::new();
+        for _ in 0..10 {
+            list.push(x.clone());
+        }
+        list.clear();
+        assert_eq!(list.size(), 0);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+        list.add(x.clone());
+        assert_eq!(list.size(), 1);
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a: SLList<char> = "abc".chars().collect();
+        let mut b = SLList::default();
+        for c in "cba".chars() {
+            b.push(c);
+        }
+        assert_eq!(a, b);
+        assert_eq!(crate::util::hash_code(&a), crate::util::hash_code(&b));
+        assert_eq!(format!("{a:?}"), "['a', 'b', 'c']");
+
+        let mut c = a.clone();
+        c.extend("d".chars());
+        assert_eq!(c.get(3), Some(&'d'));
+        assert_eq!(a.size(), 3);
+        assert!(a < c);
+    }
 }
@@ -0,0 +1,620 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+    ptr::NonNull,
+};
+
+use crate::util::Rng;
+
+const MAX_HEIGHT: usize = u64::BITS as usize;
+
+pub struct SkiplistList<T> {
+    sentinel: NonNull<Node<T>>,
+    h: usize,
+    n: usize,
+    rng: Rng,
+    marker: PhantomData<Box<Node<T>>>,
+}
+
+struct Node<T> {
+    x: Option<T>,
+    next: Box<[Option<NonNull<Node<T>>>]>,
+    // Number of elements skipped by `next[r]`, or the number of elements
+    // after this node when `next[r]` is `None`.
+    length: Box<[usize]>,
+}
+
+// SAFETY: `SkiplistList` owns its nodes exclusively, like `Box<Node<T>>` would.
+unsafe impl<T: Send> Send for SkiplistList<T> {}
+unsafe impl<T: Sync> Sync for SkiplistList<T> {}
+
+impl<T> Node<T> {
+    fn alloc(x: Option<T>, height: usize) -> NonNull<Self> {
+        NonNull::from(Box::leak(Box::new(Self {
+            x,
+            next: vec![None; height + 1].into_boxed_slice(),
+            length: vec![0; height + 1].into_boxed_slice(),
+        })))
+    }
+
+    // SAFETY: the caller must guarantee that `u` is a live node.
+    unsafe fn next(u: NonNull<Self>, r: usize) -> Option<NonNull<Self>> {
+        unsafe { (&(*u.as_ptr()).next)[r] }
+    }
+
+    // SAFETY: the caller must guarantee that `u` is a live node that is not
+    // borrowed elsewhere.
+    unsafe fn set_next(u: NonNull<Self>, r: usize, v: Option<NonNull<Self>>) {
+        unsafe { (&mut (*u.as_ptr()).next)[r] = v }
+    }
+
+    // SAFETY: the caller must guarantee that `u` is a live node.
+    unsafe fn length(u: NonNull<Self>, r: usize) -> usize {
+        unsafe { (&(*u.as_ptr()).length)[r] }
+    }
+
+    // SAFETY: the caller must guarantee that `u` is a live node that is not
+    // borrowed elsewhere.
+    unsafe fn set_length(u: NonNull<Self>, r: usize, length: usize) {
+        unsafe { (&mut (*u.as_ptr()).length)[r] = length }
+    }
+}
+
+impl<T> Default for SkiplistList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for SkiplistList<T> {
+    fn drop(&mut self) {
+        // SAFETY: level 0 links every node exactly once, and all of them were
+        // allocated by `Node::alloc`.
+        unsafe {
+            let mut u = Some(self.sentinel);
+            while let Some(w) = u {
+                u = Node::next(w, 0);
+                drop(Box::from_raw(w.as_ptr()));
+            }
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SkiplistList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> SkiplistList<T> {
+    pub fn new() -> Self {
+        Self::with_rng(Rng::new())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(Rng::with_seed(seed))
+    }
+
+    fn with_rng(rng: Rng) -> Self {
+        Self {
+            sentinel: Node::alloc(None, MAX_HEIGHT),
+            h: 0,
+            n: 0,
+            rng,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::with_rng(self.rng.clone());
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            // SAFETY: the sentinel is always live.
+            next: unsafe { Node::next(self.sentinel, 0) },
+            len: self.n,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            // SAFETY: the sentinel is always live.
+            next: unsafe { Node::next(self.sentinel, 0) },
+            len: self.n,
+            marker: PhantomData,
+        }
+    }
+
+    fn pick_height(&mut self) -> usize {
+        self.rng.next_u64().trailing_ones() as usize
+    }
+
+    fn find_pred(&self, i: usize) -> NonNull<Node<T>> {
+        let mut u = self.sentinel;
+        // `j` is one more than the index of `u`, so the sentinel sits at 0.
+        let mut j = 0;
+        // SAFETY: the search only follows links between live nodes.
+        unsafe {
+            for r in (0..=self.h).rev() {
+                while let Some(w) = Node::next(u, r) {
+                    if j + Node::length(u, r) > i {
+                        break;
+                    }
+                    j += Node::length(u, r);
+                    u = w;
+                }
+            }
+        }
+        u
+    }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if i >= self.n {
+            return None;
+        }
+        let u = self.find_pred(i);
+        // SAFETY: the node belongs to the list, which is borrowed shared.
+        unsafe { (*Node::next(u, 0)?.as_ptr()).x.as_ref() }
+    }
+
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        if i >= self.n {
+            return None;
+        }
+        let u = self.find_pred(i);
+        // SAFETY: the node belongs to the list, which is borrowed exclusively.
+        unsafe { (*Node::next(u, 0)?.as_ptr()).x.as_mut() }
+    }
+
+    pub fn set(&mut self, i: usize, x: T) -> Option<T> {
+        if i >= self.n {
+            return None;
+        }
+        let u = self.find_pred(i);
+        // SAFETY: the node belongs to the list, which is borrowed exclusively.
+        unsafe { (*Node::next(u, 0)?.as_ptr()).x.replace(x) }
+    }
+
+    pub fn add(&mut self, i: usize, x: T) {
+        crate::error::assert_insert(i, self.n);
+
+        let k = self.pick_height();
+        let w = Node::alloc(Some(x), k);
+        // Levels above `h` are empty, so the sentinel's length there is the
+        // whole list.
+        for r in self.h + 1..=k {
+            // SAFETY: the sentinel is always live.
+            unsafe { Node::set_length(self.sentinel, r, self.n) };
+        }
+        self.h = self.h.max(k);
+
+        let mut u = self.sentinel;
+        let mut j = 0;
+        // SAFETY: the search only follows links between live nodes, and `w`
+        // is linked in after the predecessor found on each of its levels.
+        unsafe {
+            for r in (0..=self.h).rev() {
+                while let Some(v) = Node::next(u, r) {
+                    if j + Node::length(u, r) > i {
+                        break;
+                    }
+                    j += Node::length(u, r);
+                    u = v;
+                }
+                Node::set_length(u, r, Node::length(u, r) + 1);
+                if r <= k {
+                    Node::set_next(w, r, Node::next(u, r));
+                    Node::set_next(u, r, Some(w));
+                    Node::set_length(w, r, Node::length(u, r) - (i + 1 - j));
+                    Node::set_length(u, r, i + 1 - j);
+                }
+            }
+        }
+        self.n += 1;
+    }
+
+    pub fn remove(&mut self, i: usize) -> Option<T> {
+        if i >= self.n {
+            return None;
+        }
+
+        let mut removed = None;
+        let mut u = self.sentinel;
+        let mut j = 0;
+        // SAFETY: the search only follows links between live nodes; the
+        // removed node is freed only after it has been unlinked on all levels.
+        unsafe {
+            for r in (0..=self.h).rev() {
+                while let Some(v) = Node::next(u, r) {
+                    if j + Node::length(u, r) > i {
+                        break;
+                    }
+                    j += Node::length(u, r);
+                    u = v;
+                }
+                Node::set_length(u, r, Node::length(u, r) - 1);
+                if let Some(v) = Node::next(u, r)
+                    && j + Node::length(u, r) == i
+                {
+                    removed = Some(v);
+                    Node::set_length(u, r, Node::length(u, r) + Node::length(v, r));
+                    Node::set_next(u, r, Node::next(v, r));
+                    if u == self.sentinel && r > 0 && Node::next(u, r).is_none() {
+                        self.h -= 1;
+                    }
+                }
+            }
+            self.n -= 1;
+            Box::from_raw(removed?.as_ptr()).x
+        }
+    }
+}
+
+impl<T: Clone> Clone for SkiplistList<T> {
+    fn clone(&self) -> Self {
+        let mut c = Self::with_rng(self.rng.clone());
+        c.extend(self.iter().cloned());
+        c
+    }
+}
+
+impl<T: PartialEq> PartialEq for SkiplistList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for SkiplistList<T> {}
+
+impl<T: PartialOrd> PartialOrd for SkiplistList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for SkiplistList<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for SkiplistList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.n);
+        self.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl<T> FromIterator<T> for SkiplistList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut c = Self::new();
+        c.extend(iter);
+        c
+    }
+}
+
+impl<T> Extend<T> for SkiplistList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.add(self.n, x);
+        }
+    }
+}
+
+impl<T> Index<usize> for SkiplistList<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        self.get(i)
+            .unwrap_or_else(|| crate::error::index_out_of_bounds(i, self.n))
+    }
+}
+
+impl<T> IndexMut<usize> for SkiplistList<T> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        let n = self.n;
+        self.get_mut(i)
+            .unwrap_or_else(|| crate::error::index_out_of_bounds(i, n))
+    }
+}
+
+impl<T> crate::List<T> for SkiplistList<T> {
+    fn size(&self) -> usize {
+        SkiplistList::size(self)
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        SkiplistList::get(self, i)
+    }
+
+    fn set(&mut self, i: usize, x: T) -> Option<T> {
+        SkiplistList::set(self, i, x)
+    }
+
+    fn add(&mut self, i: usize, x: T) {
+        SkiplistList::add(self, i, x)
+    }
+
+    fn remove(&mut self, i: usize) -> Option<T> {
+        SkiplistList::remove(self, i)
+    }
+}
+
+pub struct Iter<'a, T> {
+    next: Option<NonNull<Node<T>>>,
+    len: usize,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let u = self.next?;
+        self.len -= 1;
+        // SAFETY: the list keeps its nodes alive and unmodified for `'a`.
+        unsafe {
+            self.next = Node::next(u, 0);
+            (*u.as_ptr()).x.as_ref()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    next: Option<NonNull<Node<T>>>,
+    len: usize,
+    marker: PhantomData<&'a mut Node<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let u = self.next?;
+        self.len -= 1;
+        // SAFETY: the list is borrowed exclusively for `'a` and every node is
+        // yielded at most once.
+        unsafe {
+            self.next = Node::next(u, 0);
+            (*u.as_ptr()).x.as_mut()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+pub struct IntoIter<T> {
+    list: SkiplistList<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.remove(0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.n, Some(self.list.n))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for SkiplistList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SkiplistList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut SkiplistList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArrayStack;
+
+    fn check_lengths<T>(list: &SkiplistList<T>) {
+        // SAFETY: test-only walk over live nodes.
+        unsafe {
+            for r in 0..=list.h {
+                let mut u = list.sentinel;
+                let mut i = 0;
+                while let Some(w) = Node::next(u, r) {
+                    i += Node::length(u, r);
+                    u = w;
+                }
+                assert_eq!(i + Node::length(u, r), list.n);
+            }
+        }
+    }
+
+    fn check(list: &SkiplistList<char>, expected: &str) {
+        assert_eq!(list.size(), expected.len());
+        for (i, v) in expected.chars().enumerate() {
+            assert_eq!(list.get(i), Some(&v));
+        }
+        assert_eq!(list.get(expected.len()), None);
+        assert_eq!(list.iter().collect::<String>(), expected);
+        check_lengths(list);
+    }
+
+    #[test]
+    fn scenario() {
+        let mut list = SkiplistList::with_seed(0);
+        check(&list, "");
+
+        for (i, v) in "abcde".chars().enumerate() {
+            list.add(i, v);
+        }
+        check(&list, "abcde");
+
+        list.add(0, 'x');
+        check(&list, "xabcde");
+
+        list.add(3, 'y');
+        check(&list, "xabycde");
+
+        list.add(7, 'z');
+        check(&list, "xabycdez");
+
+        assert_eq!(list.set(4, 'C'), Some('c'));
+        check(&list, "xabyCdez");
+
+        assert_eq!(list.remove(0), Some('x'));
+        check(&list, "abyCdez");
+
+        assert_eq!(list.remove(6), Some('z'));
+        check(&list, "abyCde");
+
+        assert_eq!(list.remove(2), Some('y'));
+        check(&list, "abCde");
+
+        assert_eq!(list.remove(5), None);
+        assert_eq!(list.set(5, 'q'), None);
+        check(&list, "abCde");
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn add_to_out_of_bound() {
+        let mut list = SkiplistList::with_seed(0);
+        list.add(1, 'a');
+    }
+
+    #[test]
+    fn random_operations() {
+        let mut rng = Rng::with_seed(1);
+        let mut list = SkiplistList::with_seed(2);
+        let mut expected = ArrayStack::new(0);
+        for step in 0..3000 {
+            let n = expected.size();
+            let x = rng.next_u64();
+            match x % 4 {
+                0 | 1 => {
+                    let i = (x >> 8) as usize % (n + 1);
+                    list.add(i, step);
+                    expected.add(i, step);
+                }
+                2 if n > 0 => {
+                    let i = (x >> 8) as usize % n;
+                    assert_eq!(list.set(i, step), expected.set(i, step));
+                }
+                _ if n > 0 => {
+                    let i = (x >> 8) as usize % n;
+                    assert_eq!(list.remove(i), expected.remove(i));
+                }
+                _ => {}
+            }
+            assert_eq!(list.size(), expected.size());
+        }
+        assert!(list.iter().eq(expected.iter()));
+        check_lengths(&list);
+
+        while expected.size() > 0 {
+            let i = expected.size() / 2;
+            assert_eq!(list.remove(i), expected.remove(i));
+        }
+        check_lengths(&list);
+        assert_eq!(list.h, 0);
+    }
+
+    #[test]
+    fn iter() {
+        let mut list = SkiplistList::with_seed(3);
+        for (i, v) in "abcd".chars().enumerate() {
+            list.add(i, v);
+        }
+        for v in &mut list {
+            *v = v.to_ascii_uppercase();
+        }
+        check(&list, "ABCD");
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next(), Some('A'));
+        assert_eq!(iter.collect::<String>(), "BCD");
+    }
+
+    #[test]
+    fn drop_elements() {
+        let x = std::rc::Rc::new(());
+        let mut list = SkiplistList::with_seed(4);
+        for i in 0..10 {
+            list.add(i, x.clone());
+        }
+        drop(list.remove(3));
+        assert_eq!(std::rc::Rc::strong_count(&x), 10);
+        drop(list);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+    }
+
+    #[test]
+    fn clear() {
+        let x = std::rc::Rc::new(());
+        let mut list = SkiplistList::with_seed(1);
+        for i in 0..10 {
+            list.add(i, x.clone());
+        }
+        list.clear();
+        assert_eq!(list.size(), 0);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+        list.add(0, x.clone());
+        assert_eq!(list.size(), 1);
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a: SkiplistList<char> = "abc".chars().collect();
+        let mut b = SkiplistList::with_rng(Rng::with_seed(1));
+        b.extend("abc".chars());
+        assert_eq!(a, b);
+        assert_eq!(crate::util::hash_code(&a), crate::util::hash_code(&b));
+        assert_eq!(format!("{a:?}"), "['a', 'b', 'c']");
+
+        let mut c = a.clone();
+        c[1] = 'x';
+        assert_eq!(c[1], 'x');
+        assert_eq!(a[1], 'b');
+        assert!(a < c);
+        assert_eq!(SkiplistList::<char>::default().size(), 0);
+    }
+}
@@ -0,0 +1,513 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ptr::NonNull,
+};
+
+use crate::util::Rng;
+
+const MAX_HEIGHT: usize = u64::BITS as usize;
+
+pub struct SkiplistSSet<T> {
+    sentinel: NonNull<Node<T>>,
+    h: usize,
+    n: usize,
+    rng: Rng,
+    marker: PhantomData<Box<Node<T>>>,
+}
+
+struct Node<T> {
+    x: Option<T>,
+    next: Box<[Option<NonNull<Node<T>>>]>,
+}
+
+// SAFETY: `SkiplistSSet` owns its nodes exclusively, like `Box<Node<T>>` would.
+unsafe impl<T: Send> Send for SkiplistSSet<T> {}
+unsafe impl<T: Sync> Sync for SkiplistSSet<T> {}
+
+impl<T> Node<T> {
+    fn alloc(x: Option<T>, height: usize) -> NonNull<Self> {
+        NonNull::from(Box::leak(Box::new(Self {
+            x,
+            next: vec![None; height + 1].into_boxed_slice(),
+        })))
+    }
+
+    // SAFETY: the caller must guarantee that `u` is a live node.
+    unsafe fn height(u: NonNull<Self>) -> usize {
+        unsafe { <[_]>::len(&(*u.as_ptr()).next) - 1 }
+    }
+
+    // SAFETY: the caller must guarantee that `u` is a live node.
+    unsafe fn next(u: NonNull<Self>, r: usize) -> Option<NonNull<Self>> {
+        unsafe { (&(*u.as_ptr()).next)[r] }
+    }
+
+    // SAFETY: the caller must guarantee that `u` is a live node that is not
+    // borrowed elsewhere.
+    unsafe fn set_next(u: NonNull<Self>, r: usize, v: Option<NonNull<Self>>) {
+        unsafe { (&mut (*u.as_ptr()).next)[r] = v }
+    }
+
+    // SAFETY: the caller must guarantee that `u` is a live element node that
+    // outlives `'a` and is not mutated meanwhile.
+    unsafe fn x<'a>(u: NonNull<Self>) -> &'a T {
+        unsafe { (*u.as_ptr()).x.as_ref().unwrap() }
+    }
+}
+
+impl<T> Default for SkiplistSSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for SkiplistSSet<T> {
+    fn drop(&mut self) {
+        // SAFETY: level 0 links every node exactly once, and all of them were
+        // allocated by `Node::alloc`.
+        unsafe {
+            let mut u = Some(self.sentinel);
+            while let Some(w) = u {
+                u = Node::next(w, 0);
+                drop(Box::from_raw(w.as_ptr()));
+            }
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SkiplistSSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T> SkiplistSSet<T> {
+    pub fn new() -> Self {
+        Self::with_rng(Rng::new())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(Rng::with_seed(seed))
+    }
+
+    fn with_rng(rng: Rng) -> Self {
+        Self {
+            sentinel: Node::alloc(None, MAX_HEIGHT),
+            h: 0,
+            n: 0,
+            rng,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::with_rng(self.rng.clone());
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            // SAFETY: the sentinel is always live.
+            next: unsafe { Node::next(self.sentinel, 0) },
+            len: self.n,
+            marker: PhantomData,
+        }
+    }
+
+    fn pick_height(&mut self) -> usize {
+        self.rng.next_u64().trailing_ones() as usize
+    }
+
+    fn pop_first(&mut self) -> Option<T> {
+        // SAFETY: the first node is directly after the sentinel on each of
+        // its levels, so unlinking it there removes every reference to it.
+        unsafe {
+            let u = Node::next(self.sentinel, 0)?;
+            for r in 0..=Node::height(u) {
+                Node::set_next(self.sentinel, r, Node::next(u, r));
+            }
+            while self.h > 0 && Node::next(self.sentinel, self.h).is_none() {
+                self.h -= 1;
+            }
+            self.n -= 1;
+            Box::from_raw(u.as_ptr()).x
+        }
+    }
+}
+
+impl<T: Ord> SkiplistSSet<T> {
+    fn find_pred_node(&self, x: &T) -> NonNull<Node<T>> {
+        let mut u = self.sentinel;
+        // SAFETY: the search only follows links between live nodes.
+        unsafe {
+            for r in (0..=self.h).rev() {
+                while let Some(w) = Node::next(u, r) {
+                    if Node::x(w) >= x {
+                        break;
+                    }
+                    u = w;
+                }
+            }
+        }
+        u
+    }
+
+    pub fn find(&self, x: &T) -> Option<&T> {
+        let u = self.find_pred_node(x);
+        // SAFETY: the node belongs to the set, which is borrowed shared.
+        unsafe { Node::next(u, 0).map(|w| Node::x(w)) }
+    }
+
+    pub fn contains(&self, x: &T) -> bool {
+        self.find(x) == Some(x)
+    }
+
+    pub fn add(&mut self, x: T) -> bool {
+        let mut stack = [self.sentinel; MAX_HEIGHT + 1];
+        let mut u = self.sentinel;
+        // SAFETY: the search only follows links between live nodes, and the
+        // new node is linked in after every predecessor found on the way.
+        unsafe {
+            for r in (0..=self.h).rev() {
+                while let Some(w) = Node::next(u, r) {
+                    match Node::x(w).cmp(&x) {
+                        Ordering::Less => u = w,
+                        Ordering::Equal => return false,
+                        Ordering::Greater => break,
+                    }
+                }
+                stack[r] = u;
+            }
+
+            let height = self.pick_height();
+            let w = Node::alloc(Some(x), height);
+            self.h = self.h.max(height);
+            for (r, &u) in stack.iter().enumerate().take(height + 1) {
+                Node::set_next(w, r, Node::next(u, r));
+                Node::set_next(u, r, Some(w));
+            }
+        }
+        self.n += 1;
+        true
+    }
+
+    pub fn remove(&mut self, x: &T) -> Option<T> {
+        let mut removed = None;
+        let mut u = self.sentinel;
+        // SAFETY: the search only follows links between live nodes; the
+        // removed node is freed only after it has been unlinked on all levels.
+        unsafe {
+            for r in (0..=self.h).rev() {
+                while let Some(w) = Node::next(u, r) {
+                    match Node::x(w).cmp(x) {
+                        Ordering::Less => u = w,
+                        Ordering::Equal => {
+                            removed = Some(w);
+                            Node::set_next(u, r, Node::next(w, r));
+                            if u == self.sentinel && r > 0 && Node::next(u, r).is_none() {
+                                self.h -= 1;
+                            }
+                            break;
+                        }
+                        Ordering::Greater => break,
+                    }
+                }
+            }
+            let w = removed?;
+            self.n -= 1;
+            Box::from_raw(w.as_ptr()).x
+        }
+    }
+}
+
+impl<T: Ord + Clone> Clone for SkiplistSSet<T> {
+    fn clone(&self) -> Self {
+        let mut c = Self::with_rng(self.rng.clone());
+        c.extend(self.iter().cloned());
+        c
+    }
+}
+
+impl<T: PartialEq> PartialEq for SkiplistSSet<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for SkiplistSSet<T> {}
+
+impl<T: PartialOrd> PartialOrd for SkiplistSSet<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for SkiplistSSet<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for SkiplistSSet<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.size());
+        self.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl<T: Ord> FromIterator<T> for SkiplistSSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut c = Self::new();
+        c.extend(iter);
+        c
+    }
+}
+
+impl<T: Ord> Extend<T> for SkiplistSSet<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.add(x);
+        }
+    }
+}
+
+impl<T: Ord> crate::SSet<T> for SkiplistSSet<T> {
+    fn size(&self) -> usize {
+        SkiplistSSet::size(self)
+    }
+
+    fn add(&mut self, x: T) -> bool {
+        SkiplistSSet::add(self, x)
+    }
+
+    fn remove(&mut self, x: &T) -> Option<T> {
+        SkiplistSSet::remove(self, x)
+    }
+
+    fn find(&self, x: &T) -> Option<&T> {
+        SkiplistSSet::find(self, x)
+    }
+}
+
+pub struct Iter<'a, T> {
+    next: Option<NonNull<Node<T>>>,
+    len: usize,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let u = self.next?;
+        self.len -= 1;
+        // SAFETY: the set keeps its nodes alive and unmodified for `'a`.
+        unsafe {
+            self.next = Node::next(u, 0);
+            Some(Node::x(u))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IntoIter<T> {
+    set: SkiplistSSet<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.set.pop_first()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.set.n, Some(self.set.n))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for SkiplistSSet<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { set: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SkiplistSSet<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn check(set: &SkiplistSSet<i32>, expected: &BTreeSet<i32>) {
+        assert_eq!(set.size(), expected.len());
+        assert!(set.iter().eq(expected.iter()));
+        // SAFETY: test-only walk over live nodes.
+        unsafe {
+            for r in 1..=set.h {
+                let mut u = Node::next(set.sentinel, r);
+                let mut prev = None;
+                while let Some(w) = u {
+                    assert!(Node::height(w) >= r);
+                    assert!(prev < Some(Node::x(w)));
+                    prev = Some(Node::x(w));
+                    u = Node::next(w, r);
+                }
+            }
+            if set.h > 0 {
+                assert!(Node::next(set.sentinel, set.h).is_some());
+            }
+            for r in set.h + 1..=MAX_HEIGHT {
+                assert!(Node::next(set.sentinel, r).is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn scenario() {
+        let mut set = SkiplistSSet::with_seed(0);
+        assert_eq!(set.find(&0), None);
+
+        assert!(set.add(5));
+        assert!(set.add(1));
+        assert!(set.add(3));
+        assert!(!set.add(3));
+        assert_eq!(set.size(), 3);
+
+        assert_eq!(set.find(&0), Some(&1));
+        assert_eq!(set.find(&1), Some(&1));
+        assert_eq!(set.find(&2), Some(&3));
+        assert_eq!(set.find(&4), Some(&5));
+        assert_eq!(set.find(&6), None);
+        assert!(set.contains(&3));
+        assert!(!set.contains(&4));
+
+        assert_eq!(set.remove(&3), Some(3));
+        assert_eq!(set.remove(&3), None);
+        assert_eq!(set.find(&2), Some(&5));
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), [1, 5]);
+        assert_eq!(format!("{set:?}"), "{1, 5}");
+    }
+
+    #[test]
+    fn deterministic_with_seed() {
+        let mut a = SkiplistSSet::with_seed(7);
+        let mut b = SkiplistSSet::with_seed(7);
+        for x in 0..100 {
+            a.add(x);
+            b.add(x);
+        }
+        assert_eq!(a.h, b.h);
+        unsafe {
+            let (mut u, mut v) = (Node::next(a.sentinel, 0), Node::next(b.sentinel, 0));
+            while let (Some(w), Some(z)) = (u, v) {
+                assert_eq!(Node::height(w), Node::height(z));
+                u = Node::next(w, 0);
+                v = Node::next(z, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn random_operations() {
+        let mut rng = Rng::with_seed(1);
+        let mut set = SkiplistSSet::with_seed(2);
+        let mut expected = BTreeSet::new();
+        for _ in 0..2000 {
+            let x = (rng.next_u64() % 200) as i32;
+            match rng.next_u64() % 3 {
+                0 | 1 => assert_eq!(set.add(x), expected.insert(x)),
+                _ => assert_eq!(set.remove(&x), expected.take(&x)),
+            }
+            assert_eq!(set.find(&x), expected.range(x..).next());
+        }
+        check(&set, &expected);
+
+        while let Some(&x) = expected.iter().next() {
+            assert_eq!(set.remove(&x), expected.take(&x));
+        }
+        check(&set, &expected);
+        assert_eq!(set.h, 0);
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut set = SkiplistSSet::with_seed(3);
+        for x in [4, 2, 8, 6] {
+            set.add(x);
+        }
+        let mut iter = set.into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.collect::<Vec<_>>(), [4, 6, 8]);
+    }
+
+    #[test]
+    fn drop_elements() {
+        let x = std::rc::Rc::new(0);
+        let mut set = SkiplistSSet::with_seed(4);
+        for i in 0..10 {
+            set.add((i, x.clone()));
+        }
+        drop(set.remove(&(3, x.clone())));
+        assert_eq!(std::rc::Rc::strong_count(&x), 10);
+
+        let mut iter = set.into_iter();
+        iter.next();
+        drop(iter);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+    }
+
+    #[test]
+    fn clear() {
+        let x = std::rc::Rc::new(());
+        let mut set = SkiplistSSet::with_seed(2);
+        for i in 0..10 {
+            set.add((i, x.clone()));
+        }
+        set.clear();
+        assert_eq!(set.size(), 0);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+        assert!(set.add((0, x.clone())));
+        assert!(set.contains(&(0, x.clone())));
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a: SkiplistSSet<char> = "dbfaceg".chars().collect();
+        let mut b = SkiplistSSet::with_seed(1);
+        b.extend("abcdefg".chars());
+        assert_eq!(a, b);
+        assert_eq!(crate::util::hash_code(&a), crate::util::hash_code(&b));
+        assert_eq!(format!("{a:?}"), "{'a', 'b', 'c', 'd', 'e', 'f', 'g'}");
+
+        let mut c = a.clone();
+        c.remove(&'a');
+        assert!(a.contains(&'a'));
+        assert!(a < c);
+        assert_eq!(c.cmp(&c.clone()), Ordering::Equal);
+    }
+}
@@ -0,0 +1,424 @@
+use std::{cell::Cell, cmp::Ordering};
+
+use crate::{Compare, Min, util::Rng};
+
+// The random-access operations the sorts need, so that the crate's array
+// lists are sorted where they are instead of through a `Vec`.
+pub trait Sortable<T> {
+    fn size(&self) -> usize;
+    fn get(&self, i: usize) -> Option<&T>;
+    fn swap(&mut self, i: usize, j: usize);
+}
+
+impl<T> Sortable<T> for [T] {
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        <[T]>::get(self, i)
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        <[T]>::swap(self, i, j)
+    }
+}
+
+impl<T> Sortable<T> for Vec<T> {
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        self.as_slice().get(i)
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.as_mut_slice().swap(i, j)
+    }
+}
+
+// A comparator that counts how often it is called.
+#[derive(Debug, Default)]
+pub struct Counter<C = Min> {
+    c: C,
+    comparisons: Cell<u64>,
+}
+
+impl<C> Counter<C> {
+    pub fn new(c: C) -> Self {
+        Self {
+            c,
+            comparisons: Cell::new(0),
+        }
+    }
+
+    pub fn comparisons(&self) -> u64 {
+        self.comparisons.get()
+    }
+
+    pub fn reset(&self) {
+        self.comparisons.set(0);
+    }
+}
+
+impl<T: ?Sized, C: Compare<T>> Compare<T> for Counter<C> {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self.comparisons.set(self.comparisons.get() + 1);
+        self.c.compare(a, b)
+    }
+}
+
+fn compare<T, S, C>(a: &S, c: &C, i: usize, j: usize) -> Ordering
+where
+    S: Sortable<T> + ?Sized,
+    C: Compare<T>,
+{
+    c.compare(a.get(i).unwrap(), a.get(j).unwrap())
+}
+
+// Moves the element at each `i` to `p[i]`, following the cycles of the
+// permutation so that every element is moved by swaps alone.
+fn permute<T, S: Sortable<T> + ?Sized>(a: &mut S, mut p: Vec<usize>) {
+    for i in 0..p.len() {
+        while p[i] != i {
+            let j = p[i];
+            a.swap(i, j);
+            p.swap(i, j);
+        }
+    }
+}
+
+pub fn merge_sort<T: Ord, S: Sortable<T> + ?Sized>(a: &mut S) {
+    merge_sort_by(a, &Min)
+}
+
+// Stable. The merging is done on indices and the elements are then moved
+// into place, which costs O(n) extra words but no element copies.
+pub fn merge_sort_by<T, S, C>(a: &mut S, c: &C)
+where
+    S: Sortable<T> + ?Sized,
+    C: Compare<T>,
+{
+    fn sort<T, S, C>(a: &S, c: &C, idx: &mut [usize])
+    where
+        S: Sortable<T> + ?Sized,
+        C: Compare<T>,
+    {
+        if idx.len() <= 1 {
+            return;
+        }
+        let m = idx.len() / 2;
+        let (mut a0, mut a1) = (idx[..m].to_vec(), idx[m..].to_vec());
+        sort(a, c, &mut a0);
+        sort(a, c, &mut a1);
+        let (mut i0, mut i1) = (0, 0);
+        for x in idx.iter_mut() {
+            if i1 == a1.len() || (i0 < a0.len() && compare(a, c, a0[i0], a1[i1]).is_le()) {
+                *x = a0[i0];
+                i0 += 1;
+            } else {
+                *x = a1[i1];
+                i1 += 1;
+            }
+        }
+    }
+
+    let n = a.size();
+    let mut idx = (0..n).collect::<Vec<_>>();
+    sort(a, c, &mut idx);
+    let mut p = vec![0; n];
+    for (k, &i) in idx.iter().enumerate() {
+        p[i] = k;
+    }
+    permute(a, p);
+}
+
+pub fn quick_sort<T: Ord, S: Sortable<T> + ?Sized>(a: &mut S) {
+    quick_sort_by(a, &Min)
+}
+
+pub fn quick_sort_by<T, S, C>(a: &mut S, c: &C)
+where
+    S: Sortable<T> + ?Sized,
+    C: Compare<T>,
+{
+    // Sorts `a[i..i + n]` around a random pivot, which is kept at `i` while
+    // the rest is split three ways.
+    fn sort<T, S, C>(a: &mut S, c: &C, rng: &mut Rng, i: usize, n: usize)
+    where
+        S: Sortable<T> + ?Sized,
+        C: Compare<T>,
+    {
+        if n <= 1 {
+            return;
+        }
+        a.swap(i, i + (rng.next_u64() % n as u64) as usize);
+        let (mut p, mut j, mut q) = (i, i + 1, i + n);
+        // a[i + 1..=p] < x, a[p + 1..j] == x, a[q..i + n] > x
+        while j < q {
+            match compare(a, c, j, i) {
+                Ordering::Less => {
+                    p += 1;
+                    a.swap(j, p);
+                    j += 1;
+                }
+                Ordering::Greater => {
+                    q -= 1;
+                    a.swap(j, q);
+                }
+                Ordering::Equal => j += 1,
+            }
+        }
+        a.swap(i, p);
+        sort(a, c, rng, i, p - i);
+        sort(a, c, rng, q, i + n - q);
+    }
+
+    let n = a.size();
+    sort(a, c, &mut Rng::new(), 0, n);
+}
+
+pub fn heap_sort<T: Ord, S: Sortable<T> + ?Sized>(a: &mut S) {
+    heap_sort_by(a, &Min)
+}
+
+// Builds a heap with the greatest element at the root in place, then
+// repeatedly swaps the root behind the shrinking heap.
+pub fn heap_sort_by<T, S, C>(a: &mut S, c: &C)
+where
+    S: Sortable<T> + ?Sized,
+    C: Compare<T>,
+{
+    fn trickle_down<T, S, C>(a: &mut S, c: &C, mut i: usize, n: usize)
+    where
+        S: Sortable<T> + ?Sized,
+        C: Compare<T>,
+    {
+        loop {
+            let (l, r) = (2 * i + 1, 2 * i + 2);
+            let mut j = i;
+            if l < n && compare(a, c, l, j).is_gt() {
+                j = l;
+            }
+            if r < n && compare(a, c, r, j).is_gt() {
+                j = r;
+            }
+            if j == i {
+                return;
+            }
+            a.swap(i, j);
+            i = j;
+        }
+    }
+
+    let n = a.size();
+    for i in (0..n / 2).rev() {
+        trickle_down(a, c, i, n);
+    }
+    for m in (1..n).rev() {
+        a.swap(0, m);
+        trickle_down(a, c, 0, m);
+    }
+}
+
+pub fn counting_sort<S: Sortable<usize> + ?Sized>(a: &mut S, k: usize) {
+    counting_sort_by_key(a, k, |&x| x)
+}
+
+// Stable. Every key must be less than `k`.
+pub fn counting_sort_by_key<T, S, F>(a: &mut S, k: usize, key: F)
+where
+    S: Sortable<T> + ?Sized,
+    F: Fn(&T) -> usize,
+{
+    let n = a.size();
+    let keys = (0..n).map(|i| key(a.get(i).unwrap())).collect::<Vec<_>>();
+    let mut c = vec![0; k];
+    for &x in &keys {
+        assert!(x < k, "key out of range");
+        c[x] += 1;
+    }
+    for x in 1..k {
+        c[x] += c[x - 1];
+    }
+    let mut p = vec![0; n];
+    for i in (0..n).rev() {
+        c[keys[i]] -= 1;
+        p[i] = c[keys[i]];
+    }
+    permute(a, p);
+}
+
+pub fn radix_sort<S: Sortable<u64> + ?Sized>(a: &mut S) {
+    radix_sort_by_key(a, |&x| x)
+}
+
+// Sorts by one 8-bit digit at a time with a stable counting sort, starting
+// from the least significant.
+pub fn radix_sort_by_key<T, S, F>(a: &mut S, key: F)
+where
+    S: Sortable<T> + ?Sized,
+    F: Fn(&T) -> u64,
+{
+    const D: u32 = 8;
+    for p in 0..u64::BITS / D {
+        counting_sort_by_key(a, 1 << D, |x| {
+            ((key(x) >> (D * p)) & ((1 << D) - 1)) as usize
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArrayDeque, ArrayStack, DualArrayDeque, Max, RootishArrayStack};
+
+    fn random_vec(seed: u64, n: usize, k: u64) -> Vec<u64> {
+        let mut rng = Rng::with_seed(seed);
+        (0..n).map(|_| rng.next_u64() % k).collect()
+    }
+
+    type Sort = fn(&mut [u64], &Counter);
+
+    const SORTS: [(&str, Sort); 3] = [
+        ("merge", |a, c| merge_sort_by(a, c)),
+        ("quick", |a, c| quick_sort_by(a, c)),
+        ("heap", |a, c| heap_sort_by(a, c)),
+    ];
+
+    #[test]
+    fn comparison_sorts() {
+        for (name, sort) in SORTS {
+            for (seed, n, k) in [
+                (0, 0, 1),
+                (1, 1, 1),
+                (2, 2, 2),
+                (3, 100, 10),
+                (4, 1000, 1 << 40),
+            ] {
+                let mut a = random_vec(seed, n, k);
+                let mut expected = a.clone();
+                expected.sort();
+                sort(&mut a, &Counter::new(Min));
+                assert_eq!(a, expected, "{name} sort");
+            }
+        }
+
+        let mut a = random_vec(5, 100, 50);
+        merge_sort(&mut a);
+        assert!(a.is_sorted());
+        let mut a = random_vec(6, 100, 50);
+        quick_sort(&mut a);
+        assert!(a.is_sorted());
+        let mut a = random_vec(7, 100, 50);
+        heap_sort(&mut a);
+        assert!(a.is_sorted());
+    }
+
+    #[test]
+    fn comparison_counts() {
+        // Each sort makes O(n log n) comparisons on random input.
+        let n = 1 << 12;
+        for (name, sort) in SORTS {
+            let c = Counter::new(Min);
+            sort(&mut random_vec(8, n, u64::MAX), &c);
+            assert!(c.comparisons() >= (n as u64) * 8, "{name} sort");
+            assert!(c.comparisons() <= (n as u64) * 12 * 3, "{name} sort");
+            c.reset();
+            assert_eq!(c.comparisons(), 0);
+        }
+
+        // Merge sort never needs more than n log n.
+        let c = Counter::new(Min);
+        merge_sort_by(&mut random_vec(9, n, u64::MAX), &c);
+        assert!(c.comparisons() <= (n as u64) * 12);
+    }
+
+    #[test]
+    fn comparators() {
+        let mut a = random_vec(10, 200, 1000);
+        merge_sort_by(&mut a, &Max);
+        assert!(a.is_sorted_by(|x, y| x >= y));
+        quick_sort_by(&mut a, &Min);
+        assert!(a.is_sorted());
+        heap_sort_by(&mut a, &|x: &u64, y: &u64| (x % 10).cmp(&(y % 10)));
+        assert!(a.is_sorted_by_key(|x| x % 10));
+    }
+
+    #[test]
+    fn stability() {
+        let pairs = random_vec(11, 500, 20)
+            .into_iter()
+            .enumerate()
+            .map(|(i, k)| (k, i))
+            .collect::<Vec<_>>();
+        let mut expected = pairs.clone();
+        expected.sort_by_key(|&(k, _)| k);
+
+        let mut a = pairs.clone();
+        merge_sort_by(&mut a, &|x: &(u64, usize), y: &(u64, usize)| x.0.cmp(&y.0));
+        assert_eq!(a, expected);
+        let mut a = pairs.clone();
+        counting_sort_by_key(&mut a, 20, |&(k, _)| k as usize);
+        assert_eq!(a, expected);
+        let mut a = pairs;
+        radix_sort_by_key(&mut a, |&(k, _)| k);
+        assert_eq!(a, expected);
+    }
+
+    #[test]
+    fn integer_sorts() {
+        let mut a = random_vec(12, 1000, 50)
+            .into_iter()
+            .map(|x| x as usize)
+            .collect::<Vec<_>>();
+        let mut expected = a.clone();
+        expected.sort();
+        counting_sort(&mut a, 50);
+        assert_eq!(a, expected);
+
+        let mut a = random_vec(13, 1000, u64::MAX);
+        let mut expected = a.clone();
+        expected.sort();
+        radix_sort(&mut a);
+        assert_eq!(a, expected);
+        radix_sort(&mut a[..0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "key out of range")]
+    fn counting_sort_out_of_range() {
+        counting_sort(&mut [3, 1, 4][..], 4);
+    }
+
+    #[test]
+    fn lists() {
+        let xs = random_vec(14, 300, 100);
+        let mut expected = xs.clone();
+        expected.sort();
+
+        // A deque whose elements wrap around the end of its array.
+        let mut deque = ArrayDeque::new(0);
+        for (i, &x) in xs.iter().enumerate() {
+            deque.add(i / 2, x);
+        }
+        quick_sort(&mut deque);
+        assert!(deque.iter().eq(&expected));
+
+        let mut stack = ArrayStack::new(0);
+        let mut dual = DualArrayDeque::new(0);
+        let mut rootish = RootishArrayStack::new(0);
+        for (i, &x) in xs.iter().enumerate() {
+            stack.add(i, x);
+            dual.add(i / 2, x);
+            rootish.add(i, x);
+        }
+        merge_sort(&mut stack);
+        heap_sort(&mut dual);
+        radix_sort(&mut rootish);
+        assert!(stack.iter().eq(&expected));
+        assert!(dual.iter().eq(&expected));
+        assert!(rootish.iter().eq(&expected));
+    }
+}
@@ -0,0 +1,373 @@
+use crate::ArrayStack;
+
+// Indices run over `0..size()`. Outside that range `get`, `set` and
+// `remove` return `None` and leave the list as it was, while `add` accepts
+// `0..=size()` and panics with `Error::IndexOutOfBounds`'s message beyond.
+pub trait List<T> {
+    fn size(&self) -> usize;
+    fn get(&self, i: usize) -> Option<&T>;
+    fn set(&mut self, i: usize, x: T) -> Option<T>;
+    fn add(&mut self, i: usize, x: T);
+    fn remove(&mut self, i: usize) -> Option<T>;
+}
+
+pub trait Queue<T> {
+    fn size(&self) -> usize;
+    fn add(&mut self, x: T);
+    fn remove(&mut self) -> Option<T>;
+}
+
+pub trait Stack<T> {
+    fn size(&self) -> usize;
+    fn push(&mut self, x: T);
+    fn pop(&mut self) -> Option<T>;
+}
+
+pub trait Deque<T> {
+    fn size(&self) -> usize;
+    fn add_first(&mut self, x: T);
+    fn add_last(&mut self, x: T);
+    fn remove_first(&mut self) -> Option<T>;
+    fn remove_last(&mut self) -> Option<T>;
+}
+
+pub trait SSet<T> {
+    fn size(&self) -> usize;
+    fn add(&mut self, x: T) -> bool;
+    fn remove(&mut self, x: &T) -> Option<T>;
+    fn find(&self, x: &T) -> Option<&T>;
+}
+
+pub trait USet<T> {
+    fn size(&self) -> usize;
+    fn add(&mut self, x: T) -> bool;
+    fn remove(&mut self, x: &T) -> Option<T>;
+    fn find(&self, x: &T) -> Option<&T>;
+}
+
+// The edges form a set, so adding an edge that is already there does
+// nothing. For `AdjacencyLists` that check makes `add_edge` scan the
+// out-edges of `i`.
+pub trait Graph {
+    fn n_vertices(&self) -> usize;
+    fn add_edge(&mut self, i: usize, j: usize);
+    fn remove_edge(&mut self, i: usize, j: usize);
+    fn has_edge(&self, i: usize, j: usize) -> bool;
+    fn out_edges(&self, i: usize) -> ArrayStack<usize>;
+    fn in_edges(&self, j: usize) -> ArrayStack<usize>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        AdjacencyLists, AdjacencyMatrix, ArrayDeque, ArrayQueue, ArrayStack, BinarySearchTree,
+        ChainedHashTable, DLList, DualArrayDeque, LinearHashTable, RedBlackTree, RootishArrayStack,
+        SEList, SLList, ScapegoatTree, SkiplistList, SkiplistSSet, Treap,
+    };
+
+    fn check_list<L: List<char>>(list: &L, expected: &[char]) {
+        assert_eq!(list.size(), expected.len());
+        for (i, c) in expected.iter().enumerate() {
+            assert_eq!(list.get(i), Some(c));
+        }
+    }
+
+    fn list_conformance<L: List<char>>(mut list: L) {
+        let mut expected = Vec::new();
+        check_list(&list, &expected);
+
+        for (i, c) in "abcdefgh".chars().enumerate() {
+            list.add(i, c);
+            expected.insert(i, c);
+            check_list(&list, &expected);
+        }
+
+        for (i, c) in [(0, 'x'), (4, 'y'), (10, 'z'), (3, 'w')] {
+            list.add(i, c);
+            expected.insert(i, c);
+            check_list(&list, &expected);
+        }
+
+        for (i, c) in [(0, 'A'), (5, 'B'), (11, 'C')] {
+            assert_eq!(list.set(i, c), Some(expected[i]));
+            expected[i] = c;
+            check_list(&list, &expected);
+        }
+
+        for i in [0, 4, 9, 3, 0] {
+            assert_eq!(list.remove(i), Some(expected.remove(i)));
+            check_list(&list, &expected);
+        }
+
+        while !expected.is_empty() {
+            let i = expected.len() / 2;
+            assert_eq!(list.remove(i), Some(expected.remove(i)));
+            check_list(&list, &expected);
+        }
+    }
+
+    fn list_bounds<L: List<char>>(mut list: L) {
+        assert_eq!(list.get(0), None);
+        assert_eq!(list.set(0, 'x'), None);
+        assert_eq!(list.remove(0), None);
+        check_list(&list, &[]);
+
+        list.add(0, 'b');
+        list.add(1, 'c');
+        list.add(0, 'a');
+        for i in [3, 4, 100, usize::MAX] {
+            assert_eq!(list.get(i), None);
+            assert_eq!(list.set(i, 'x'), None);
+            assert_eq!(list.remove(i), None);
+        }
+        check_list(&list, &['a', 'b', 'c']);
+
+        let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| list.add(4, 'x')));
+        let message = panic.unwrap_err().downcast::<String>().unwrap();
+        assert_eq!(
+            *message,
+            "index out of bounds: the size is 3 but the index is 4"
+        );
+        check_list(&list, &['a', 'b', 'c']);
+    }
+
+    fn queue_conformance<Q: Queue<char>>(mut queue: Q) {
+        assert_eq!(queue.size(), 0);
+        assert_eq!(queue.remove(), None);
+        for c in "abcde".chars() {
+            queue.add(c);
+        }
+        assert_eq!(queue.size(), 5);
+        assert_eq!(queue.remove(), Some('a'));
+        assert_eq!(queue.remove(), Some('b'));
+        queue.add('f');
+        for c in "cdef".chars() {
+            assert_eq!(queue.remove(), Some(c));
+        }
+        assert_eq!(queue.size(), 0);
+        assert_eq!(queue.remove(), None);
+        queue.add('g');
+        assert_eq!(queue.remove(), Some('g'));
+    }
+
+    fn stack_conformance<S: Stack<char>>(mut stack: S) {
+        assert_eq!(stack.size(), 0);
+        assert_eq!(stack.pop(), None);
+        for c in "abcde".chars() {
+            stack.push(c);
+        }
+        assert_eq!(stack.size(), 5);
+        assert_eq!(stack.pop(), Some('e'));
+        assert_eq!(stack.pop(), Some('d'));
+        stack.push('f');
+        for c in "fcba".chars() {
+            assert_eq!(stack.pop(), Some(c));
+        }
+        assert_eq!(stack.size(), 0);
+        assert_eq!(stack.pop(), None);
+    }
+
+    fn deque_conformance<D: Deque<char>>(mut deque: D) {
+        assert_eq!(deque.size(), 0);
+        assert_eq!(deque.remove_first(), None);
+        assert_eq!(deque.remove_last(), None);
+        deque.add_last('c');
+        deque.add_first('b');
+        deque.add_last('d');
+        deque.add_first('a');
+        deque.add_last('e');
+        assert_eq!(deque.size(), 5);
+        assert_eq!(deque.remove_first(), Some('a'));
+        assert_eq!(deque.remove_last(), Some('e'));
+        assert_eq!(deque.remove_first(), Some('b'));
+        assert_eq!(deque.remove_last(), Some('d'));
+        assert_eq!(deque.remove_last(), Some('c'));
+        assert_eq!(deque.size(), 0);
+        assert_eq!(deque.remove_first(), None);
+    }
+
+    #[test]
+    fn array_stack() {
+        list_conformance(ArrayStack::new(0));
+        list_conformance(ArrayStack::new(4));
+        list_bounds(ArrayStack::new(0));
+        stack_conformance(ArrayStack::new(0));
+    }
+
+    #[test]
+    fn array_queue() {
+        queue_conformance(ArrayQueue::new(0));
+        queue_conformance(ArrayQueue::new(4));
+    }
+
+    #[test]
+    fn array_deque() {
+        list_conformance(ArrayDeque::new(0));
+        list_conformance(ArrayDeque::new(4));
+        list_bounds(ArrayDeque::new(0));
+        deque_conformance(ArrayDeque::new(0));
+    }
+
+    #[test]
+    fn dual_array_deque() {
+        list_conformance(DualArrayDeque::new(0));
+        list_conformance(DualArrayDeque::new(4));
+        list_bounds(DualArrayDeque::new(0));
+        deque_conformance(DualArrayDeque::new(0));
+    }
+
+    #[test]
+    fn rootish_array_stack() {
+        list_conformance(RootishArrayStack::new(0));
+        list_conformance(RootishArrayStack::new(4));
+        list_bounds(RootishArrayStack::new(0));
+    }
+
+    #[test]
+    fn dl_list() {
+        list_conformance(DLList::new());
+        list_bounds(DLList::new());
+        deque_conformance(DLList::new());
+    }
+
+    #[test]
+    fn se_list() {
+        list_conformance(SEList::new(1));
+        list_conformance(SEList::new(3));
+        list_bounds(SEList::new(2));
+    }
+
+    #[test]
+    fn skiplist_list() {
+        list_conformance(SkiplistList::with_seed(0));
+        list_bounds(SkiplistList::with_seed(1));
+    }
+
+    #[test]
+    fn sl_list() {
+        queue_conformance(SLList::new());
+        stack_conformance(SLList::new());
+    }
+
+    fn sset_conformance<S: SSet<i32>>(mut set: S) {
+        assert_eq!(set.size(), 0);
+        assert_eq!(set.find(&0), None);
+        assert_eq!(set.remove(&0), None);
+
+        for x in [5, 1, 9, 3, 7] {
+            assert!(set.add(x));
+        }
+        assert!(!set.add(3));
+        assert_eq!(set.size(), 5);
+
+        for (x, expected) in [
+            (0, Some(1)),
+            (1, Some(1)),
+            (4, Some(5)),
+            (9, Some(9)),
+            (10, None),
+        ] {
+            assert_eq!(set.find(&x), expected.as_ref());
+        }
+
+        assert_eq!(set.remove(&4), None);
+        assert_eq!(set.remove(&5), Some(5));
+        assert_eq!(set.find(&4), Some(&7));
+        assert_eq!(set.size(), 4);
+
+        for x in [1, 3, 7, 9] {
+            assert_eq!(set.remove(&x), Some(x));
+        }
+        assert_eq!(set.size(), 0);
+        assert_eq!(set.find(&0), None);
+    }
+
+    #[test]
+    fn skiplist_sset() {
+        sset_conformance(SkiplistSSet::with_seed(0));
+    }
+
+    #[test]
+    fn binary_search_tree() {
+        sset_conformance(BinarySearchTree::new());
+    }
+
+    #[test]
+    fn red_black_tree() {
+        sset_conformance(RedBlackTree::new());
+    }
+
+    #[test]
+    fn scapegoat_tree() {
+        sset_conformance(ScapegoatTree::new());
+    }
+
+    #[test]
+    fn treap() {
+        sset_conformance(Treap::with_seed(0));
+    }
+
+    fn uset_conformance<S: USet<i32>>(mut set: S) {
+        assert_eq!(set.size(), 0);
+        assert_eq!(set.find(&0), None);
+        assert_eq!(set.remove(&0), None);
+
+        for x in 0..100 {
+            assert!(set.add(x * 7));
+        }
+        assert!(!set.add(21));
+        assert_eq!(set.size(), 100);
+
+        for x in 0..700 {
+            let expected = (x % 7 == 0).then_some(x);
+            assert_eq!(set.find(&x), expected.as_ref());
+        }
+
+        for x in (0..100).rev() {
+            assert_eq!(set.remove(&(x * 7)), Some(x * 7));
+            assert_eq!(set.remove(&(x * 7)), None);
+        }
+        assert_eq!(set.size(), 0);
+        assert_eq!(set.find(&0), None);
+    }
+
+    #[test]
+    fn chained_hash_table() {
+        uset_conformance(ChainedHashTable::with_seed(0));
+    }
+
+    #[test]
+    fn linear_hash_table() {
+        uset_conformance(LinearHashTable::with_seed(0));
+    }
+
+    fn graph_conformance<G: Graph>(mut g: G) {
+        assert_eq!(g.n_vertices(), 4);
+        for (i, j) in [(0, 1), (0, 2), (1, 2), (2, 0), (3, 2)] {
+            g.add_edge(i, j);
+        }
+        assert!(g.has_edge(0, 1));
+        assert!(!g.has_edge(1, 0));
+        assert!(g.out_edges(0).iter().eq(&[1, 2]));
+        assert!(g.in_edges(2).iter().eq(&[0, 1, 3]));
+
+        g.remove_edge(0, 2);
+        g.remove_edge(1, 3);
+        assert!(!g.has_edge(0, 2));
+        assert!(g.out_edges(0).iter().eq(&[1]));
+        assert!(g.in_edges(2).iter().eq(&[1, 3]));
+        assert_eq!(g.out_edges(3).size(), 1);
+        assert_eq!(g.in_edges(3).size(), 0);
+    }
+
+    #[test]
+    fn adjacency_matrix() {
+        graph_conformance(AdjacencyMatrix::new(4));
+    }
+
+    #[test]
+    fn adjacency_lists() {
+        graph_conformance(AdjacencyLists::new(4));
+    }
+}
@@ -0,0 +1,215 @@
+use crate::{ArrayQueue, Graph, SLList};
+
+// The vertices a search reached, in the order it reached them, and the edge
+// it reached each one by. The root and unreached vertices have no parent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Traversal {
+    pub order: Vec<usize>,
+    pub parent: Box<[Option<usize>]>,
+}
+
+impl Traversal {
+    // Panics like the graphs do if the root `r` is not one of the `n`
+    // vertices.
+    fn new(n: usize, r: usize) -> Self {
+        assert!(r < n, "vertex out of range");
+        Self {
+            order: Vec::new(),
+            parent: vec![None; n].into_boxed_slice(),
+        }
+    }
+
+    fn visit(&mut self, i: usize, parent: Option<usize>) {
+        self.order.push(i);
+        self.parent[i] = parent;
+    }
+
+    // The path from the root to `i`, or `None` if the search never got there.
+    pub fn path_to(&self, mut i: usize) -> Option<Vec<usize>> {
+        // Only the root is reached without a parent.
+        let reached = matches!(self.parent.get(i), Some(Some(_))) || self.order.first() == Some(&i);
+        if !reached {
+            return None;
+        }
+        let mut path = vec![i];
+        while let Some(p) = self.parent[i] {
+            path.push(p);
+            i = p;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+pub fn bfs<G: Graph + ?Sized>(g: &G, r: usize) -> Traversal {
+    let mut t = Traversal::new(g.n_vertices(), r);
+    let mut seen = vec![false; g.n_vertices()];
+    let mut q = ArrayQueue::new(0);
+    seen[r] = true;
+    t.visit(r, None);
+    q.add(r);
+    while let Some(i) = q.remove() {
+        for &j in g.out_edges(i).iter() {
+            if !seen[j] {
+                seen[j] = true;
+                t.visit(j, Some(i));
+                q.add(j);
+            }
+        }
+    }
+    t
+}
+
+pub fn dfs<G: Graph + ?Sized>(g: &G, r: usize) -> Traversal {
+    fn visit<G: Graph + ?Sized>(g: &G, i: usize, seen: &mut [bool], t: &mut Traversal) {
+        seen[i] = true;
+        for &j in g.out_edges(i).iter() {
+            if !seen[j] {
+                t.visit(j, Some(i));
+                visit(g, j, seen, t);
+            }
+        }
+    }
+
+    let mut t = Traversal::new(g.n_vertices(), r);
+    let mut seen = vec![false; g.n_vertices()];
+    t.visit(r, None);
+    visit(g, r, &mut seen, &mut t);
+    t
+}
+
+// Visits the vertices in the same order as `dfs`, but keeps the pending
+// edges on an explicit stack so that deep graphs cannot overflow the call
+// stack.
+pub fn dfs_stack<G: Graph + ?Sized>(g: &G, r: usize) -> Traversal {
+    let mut t = Traversal::new(g.n_vertices(), r);
+    let mut seen = vec![false; g.n_vertices()];
+    let mut s = SLList::new();
+    s.push((r, None));
+    while let Some((i, p)) = s.pop() {
+        if seen[i] {
+            continue;
+        }
+        seen[i] = true;
+        t.visit(i, p);
+        for &j in g.out_edges(i).iter().rev() {
+            if !seen[j] {
+                s.push((j, Some(i)));
+            }
+        }
+    }
+    t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AdjacencyLists, AdjacencyMatrix, util::Rng};
+
+    //  0 → 1 → 3
+    //  ↓   ↓   ↑
+    //  2 → 4 ──┘   5 → 0
+    fn create<G: Graph>(mut g: G) -> G {
+        for (i, j) in [(0, 1), (0, 2), (1, 3), (1, 4), (2, 4), (4, 3), (5, 0)] {
+            g.add_edge(i, j);
+        }
+        g
+    }
+
+    #[test]
+    fn bfs_order() {
+        let t = bfs(&create(AdjacencyLists::new(6)), 0);
+        assert_eq!(t.order, [0, 1, 2, 3, 4]);
+        assert_eq!(*t.parent, [None, Some(0), Some(0), Some(1), Some(1), None]);
+        assert_eq!(t.path_to(3), Some(vec![0, 1, 3]));
+        assert_eq!(t.path_to(5), None);
+        assert_eq!(t.path_to(0), Some(vec![0]));
+        assert_eq!(t.path_to(6), None);
+        assert_eq!(t, bfs(&create(AdjacencyMatrix::new(6)), 0));
+    }
+
+    #[test]
+    fn dfs_order() {
+        let g = create(AdjacencyMatrix::new(6));
+        let t = dfs(&g, 0);
+        assert_eq!(t.order, [0, 1, 3, 4, 2]);
+        assert_eq!(*t.parent, [None, Some(0), Some(0), Some(1), Some(1), None]);
+        assert_eq!(t.path_to(2), Some(vec![0, 2]));
+        assert_eq!(t, dfs_stack(&g, 0));
+
+        let t = dfs(&g, 5);
+        assert_eq!(t.order, [5, 0, 1, 3, 4, 2]);
+        assert_eq!(t.path_to(3), Some(vec![5, 0, 1, 3]));
+    }
+
+    #[test]
+    fn random_graphs() {
+        let mut rng = Rng::with_seed(1);
+        for _ in 0..20 {
+            let n = 50;
+            let mut lists = AdjacencyLists::new(n);
+            let mut matrix = AdjacencyMatrix::new(n);
+            for _ in 0..100 {
+                let (i, j) = (
+                    (rng.next_u64() % 50) as usize,
+                    (rng.next_u64() % 50) as usize,
+                );
+                lists.add_edge(i, j);
+                matrix.add_edge(i, j);
+            }
+            // Repeated edges were only added once.
+            let mut copy = AdjacencyLists::new(n);
+            for i in 0..n {
+                for &j in matrix.out_edges(i).iter() {
+                    copy.add_edge(i, j);
+                }
+            }
+            assert_eq!(lists, copy);
+            let r = (rng.next_u64() % 50) as usize;
+            let (b, d) = (bfs(&lists, r), dfs(&lists, r));
+            assert_eq!(dfs_stack(&lists, r), d);
+            assert_eq!(dfs(&matrix, r).order.len(), d.order.len());
+
+            // Both reach the same vertices, along edges of the graph, and a
+            // breadth-first search finds shortest paths.
+            let mut reached = d.order.clone();
+            reached.sort();
+            let mut expected = b.order.clone();
+            expected.sort();
+            assert_eq!(reached, expected);
+            for t in [&b, &d] {
+                for &i in &t.order[1..] {
+                    assert!(lists.has_edge(t.parent[i].unwrap(), i));
+                }
+            }
+            let dist = |i| b.path_to(i).map(|path| path.len() - 1);
+            for &i in &b.order {
+                for &j in lists.out_edges(i).iter() {
+                    assert!(dist(j).unwrap() <= dist(i).unwrap() + 1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn deep_graph() {
+        let n = 100_000;
+        let mut g = AdjacencyLists::new(n);
+        for i in 1..n {
+            g.add_edge(i - 1, i);
+        }
+        let t = dfs_stack(&g, 0);
+        assert_eq!(t.order.len(), n);
+        assert_eq!(t.parent[n - 1], Some(n - 2));
+    }
+
+    #[test]
+    fn root_out_of_range() {
+        let g = create(AdjacencyLists::new(6));
+        for search in [bfs, dfs, dfs_stack] {
+            let r = std::panic::catch_unwind(|| search(&g, 6));
+            let e = r.unwrap_err();
+            assert_eq!(e.downcast_ref::<&str>(), Some(&"vertex out of range"));
+        }
+    }
+}
@@ -0,0 +1,608 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ptr::NonNull,
+};
+
+use crate::{
+    BinaryTree,
+    binary_tree::{self, Link, Node},
+    util::Rng,
+};
+
+// Nodes keep their subtree size next to the priority so that `split` and
+// `merge` can tell the size of each part without walking it.
+#[derive(Clone)]
+pub(crate) struct Meta {
+    p: u64,
+    size: usize,
+}
+
+#[derive(Clone)]
+pub struct Treap<T> {
+    tree: BinaryTree<T, Meta>,
+    rng: Rng,
+}
+
+// SAFETY (for the helpers below): every node passed in must be live and
+// belong to a tree that is borrowed exclusively.
+unsafe fn size<T>(u: Link<T, Meta>) -> usize {
+    u.map_or(0, |u| unsafe { Node::extra(u).size })
+}
+
+unsafe fn fix_size<T>(u: NonNull<Node<T, Meta>>) {
+    unsafe {
+        Node::extra_mut(u).size = 1 + size(Node::left(u)) + size(Node::right(u));
+    }
+}
+
+unsafe fn set_left<T>(u: NonNull<Node<T, Meta>>, v: Link<T, Meta>) {
+    unsafe {
+        Node::set_left(u, v);
+        if let Some(v) = v {
+            Node::set_parent(v, Some(u));
+        }
+        fix_size(u);
+    }
+}
+
+unsafe fn set_right<T>(u: NonNull<Node<T, Meta>>, v: Link<T, Meta>) {
+    unsafe {
+        Node::set_right(u, v);
+        if let Some(v) = v {
+            Node::set_parent(v, Some(u));
+        }
+        fix_size(u);
+    }
+}
+
+// Splits the subtree at `u` into the elements up to `x` and those after it.
+unsafe fn split<T: Ord>(u: Link<T, Meta>, x: &T) -> (Link<T, Meta>, Link<T, Meta>) {
+    let Some(u) = u else {
+        return (None, None);
+    };
+    unsafe {
+        if Node::x(u) <= x {
+            let (l, r) = split(Node::right(u), x);
+            set_right(u, l);
+            (Some(u), r)
+        } else {
+            let (l, r) = split(Node::left(u), x);
+            set_left(u, r);
+            (l, Some(u))
+        }
+    }
+}
+
+// Joins two subtrees where every element of `a` is less than every element of
+// `b`.
+unsafe fn merge<T>(a: Link<T, Meta>, b: Link<T, Meta>) -> Link<T, Meta> {
+    match (a, b) {
+        (None, w) | (w, None) => w,
+        (Some(a), Some(b)) => unsafe {
+            if Node::extra(a).p < Node::extra(b).p {
+                set_right(a, merge(Node::right(a), Some(b)));
+                Some(a)
+            } else {
+                set_left(b, merge(Some(a), Node::left(b)));
+                Some(b)
+            }
+        },
+    }
+}
+
+impl<T> Default for Treap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Treap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Treap<T> {
+    pub fn new() -> Self {
+        Self::with_rng(Rng::new())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(Rng::with_seed(seed))
+    }
+
+    fn with_rng(rng: Rng) -> Self {
+        Self {
+            tree: BinaryTree::new(),
+            rng,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        // SAFETY: the root belongs to the tree.
+        unsafe { size(self.tree.root()) }
+    }
+
+    pub fn clear(&mut self) {
+        self.tree = BinaryTree::new();
+    }
+
+    pub fn as_tree(&self) -> &BinaryTree<T, impl Sized> {
+        &self.tree
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.tree.iter(),
+            len: self.size(),
+        }
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        // SAFETY: the node belongs to the tree, which is borrowed shared.
+        self.tree.first_node().map(|u| unsafe { Node::x(u) })
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        // SAFETY: the node belongs to the tree, which is borrowed shared.
+        self.tree.last_node().map(|u| unsafe { Node::x(u) })
+    }
+
+    // SAFETY: the caller must guarantee that `u` belongs to this treap.
+    unsafe fn rotate_left(&mut self, u: NonNull<Node<T, Meta>>) {
+        unsafe {
+            self.tree.rotate_left(u);
+            fix_size(u);
+            fix_size(Node::parent(u).unwrap());
+        }
+    }
+
+    // SAFETY: the caller must guarantee that `u` belongs to this treap.
+    unsafe fn rotate_right(&mut self, u: NonNull<Node<T, Meta>>) {
+        unsafe {
+            self.tree.rotate_right(u);
+            fix_size(u);
+            fix_size(Node::parent(u).unwrap());
+        }
+    }
+
+    // Rotates `u` up while its priority is smaller than its parent's.
+    // SAFETY: the caller must guarantee that `u` belongs to this treap.
+    unsafe fn bubble_up(&mut self, u: NonNull<Node<T, Meta>>) {
+        unsafe {
+            while let Some(p) = Node::parent(u) {
+                if Node::extra(p).p <= Node::extra(u).p {
+                    break;
+                }
+                if Node::right(p) == Some(u) {
+                    self.rotate_left(p);
+                } else {
+                    self.rotate_right(p);
+                }
+            }
+        }
+    }
+
+    // Rotates `u` down until it is a leaf.
+    // SAFETY: the caller must guarantee that `u` belongs to this treap.
+    unsafe fn trickle_down(&mut self, u: NonNull<Node<T, Meta>>) {
+        unsafe {
+            loop {
+                match (Node::left(u), Node::right(u)) {
+                    (None, None) => break,
+                    (None, Some(_)) => self.rotate_left(u),
+                    (Some(_), None) => self.rotate_right(u),
+                    (Some(l), Some(r)) => {
+                        if Node::extra(l).p < Node::extra(r).p {
+                            self.rotate_right(u);
+                        } else {
+                            self.rotate_left(u);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T: Ord> Treap<T> {
+    pub fn find(&self, x: &T) -> Option<&T> {
+        self.tree.find(x)
+    }
+
+    pub fn contains(&self, x: &T) -> bool {
+        // SAFETY: the node belongs to the tree, which is borrowed shared.
+        self.tree
+            .find_last(x)
+            .is_some_and(|u| unsafe { Node::x(u) } == x)
+    }
+
+    pub fn add(&mut self, x: T) -> bool {
+        let p = self.tree.find_last(&x);
+        // SAFETY: `p` belongs to the tree and the new node is linked in below
+        // it before any sizes or rotations touch it.
+        unsafe {
+            if p.is_some_and(|p| Node::x(p) == &x) {
+                return false;
+            }
+            let meta = Meta {
+                p: self.rng.next_u64(),
+                size: 1,
+            };
+            let u = Node::alloc(x, meta);
+            self.tree.add_child(p, u);
+            let mut w = p;
+            while let Some(v) = w {
+                Node::extra_mut(v).size += 1;
+                w = Node::parent(v);
+            }
+            self.bubble_up(u);
+        }
+        true
+    }
+
+    pub fn remove(&mut self, x: &T) -> Option<T> {
+        let u = self.tree.find_last(x)?;
+        // SAFETY: `u` belongs to the tree, is unlinked once it is a leaf, and
+        // is freed exactly once.
+        unsafe {
+            if Node::x(u) != x {
+                return None;
+            }
+            self.trickle_down(u);
+            let mut w = Node::parent(u);
+            self.tree.splice(u);
+            while let Some(v) = w {
+                Node::extra_mut(v).size -= 1;
+                w = Node::parent(v);
+            }
+            Some(Node::into_x(u))
+        }
+    }
+
+    // Moves every element greater than `x` into a new treap, which is
+    // returned.
+    pub fn split(&mut self, x: &T) -> Self {
+        let mut other = Self::with_rng(Rng::with_seed(self.rng.next_u64()));
+        // SAFETY: the two halves partition this treap's nodes, and each is
+        // handed to exactly one owner.
+        unsafe {
+            let (l, r) = split(self.tree.root(), x);
+            self.tree.set_root(l);
+            other.tree.set_root(r);
+        }
+        other
+    }
+
+    // Moves every element of `other` into this treap. All elements of one
+    // treap must be less than all elements of the other.
+    pub fn merge(&mut self, mut other: Self) {
+        let (a, b) = match (self.last(), other.first(), other.last(), self.first()) {
+            (None, ..) | (_, None, ..) => (self.tree.root(), other.tree.root()),
+            (Some(x), Some(y), ..) if x < y => (self.tree.root(), other.tree.root()),
+            (_, _, Some(x), Some(y)) if x < y => (other.tree.root(), self.tree.root()),
+            _ => panic!("merged treaps must not overlap"),
+        };
+        // SAFETY: the nodes of both treaps are moved into this one, and
+        // `other` is emptied before it is dropped.
+        unsafe {
+            other.tree.set_root(None);
+            self.tree.set_root(merge(a, b));
+        }
+    }
+
+    #[cfg(test)]
+    fn check(&self) {
+        binary_tree::check_links(&self.tree);
+        // SAFETY: test-only walk over live nodes.
+        unsafe fn check<T: Ord>(u: NonNull<Node<T, Meta>>) -> usize {
+            unsafe {
+                let mut n = 1;
+                for w in [Node::left(u), Node::right(u)].into_iter().flatten() {
+                    assert!(Node::extra(u).p <= Node::extra(w).p);
+                    n += check(w);
+                }
+                assert_eq!(Node::extra(u).size, n);
+                n
+            }
+        }
+        if let Some(r) = self.tree.root() {
+            unsafe { check(r) };
+        }
+        assert!(self.iter().zip(self.iter().skip(1)).all(|(x, y)| x < y));
+    }
+}
+
+impl<T: PartialEq> PartialEq for Treap<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for Treap<T> {}
+
+impl<T: PartialOrd> PartialOrd for Treap<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for Treap<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for Treap<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.size());
+        self.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl<T: Ord> FromIterator<T> for Treap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut c = Self::new();
+        c.extend(iter);
+        c
+    }
+}
+
+impl<T: Ord> Extend<T> for Treap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.add(x);
+        }
+    }
+}
+
+impl<T: Ord> crate::SSet<T> for Treap<T> {
+    fn size(&self) -> usize {
+        Treap::size(self)
+    }
+
+    fn add(&mut self, x: T) -> bool {
+        Treap::add(self, x)
+    }
+
+    fn remove(&mut self, x: &T) -> Option<T> {
+        Treap::remove(self, x)
+    }
+
+    fn find(&self, x: &T) -> Option<&T> {
+        Treap::find(self, x)
+    }
+}
+
+pub struct Iter<'a, T> {
+    inner: binary_tree::Iter<'a, T, Meta>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.inner.next()?;
+        self.len -= 1;
+        Some(x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IntoIter<T> {
+    inner: binary_tree::IntoIter<T, Meta>,
+    len: usize,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.inner.next()?;
+        self.len -= 1;
+        Some(x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for Treap<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let len = self.size();
+        IntoIter {
+            inner: self.tree.into_iter(),
+            len,
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Treap<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn create(seed: u64, xs: impl IntoIterator<Item = i32>) -> Treap<i32> {
+        let mut treap = Treap::with_seed(seed);
+        for x in xs {
+            treap.add(x);
+        }
+        treap
+    }
+
+    fn check(treap: &Treap<i32>, expected: &BTreeSet<i32>) {
+        assert_eq!(treap.size(), expected.len());
+        assert!(treap.iter().eq(expected.iter()));
+        treap.check();
+    }
+
+    #[test]
+    fn scenario() {
+        let mut treap = Treap::with_seed(0);
+        assert_eq!(treap.find(&0), None);
+        assert_eq!(treap.first(), None);
+
+        for x in [5, 2, 8, 1, 3, 7, 9] {
+            assert!(treap.add(x));
+        }
+        assert!(!treap.add(3));
+        assert_eq!(treap.size(), 7);
+
+        assert_eq!(treap.find(&0), Some(&1));
+        assert_eq!(treap.find(&4), Some(&5));
+        assert_eq!(treap.find(&10), None);
+        assert!(treap.contains(&8));
+        assert!(!treap.contains(&4));
+        assert_eq!(treap.first(), Some(&1));
+        assert_eq!(treap.last(), Some(&9));
+
+        assert_eq!(treap.remove(&5), Some(5));
+        assert_eq!(treap.remove(&5), None);
+        check(&treap, &BTreeSet::from([1, 2, 3, 7, 8, 9]));
+        assert_eq!(format!("{treap:?}"), "{1, 2, 3, 7, 8, 9}");
+    }
+
+    #[test]
+    fn random_operations() {
+        let mut rng = Rng::with_seed(1);
+        let mut treap = Treap::with_seed(2);
+        let mut expected = BTreeSet::new();
+        for _ in 0..3000 {
+            let x = (rng.next_u64() % 300) as i32;
+            match rng.next_u64() % 3 {
+                0 | 1 => assert_eq!(treap.add(x), expected.insert(x)),
+                _ => assert_eq!(treap.remove(&x), expected.take(&x)),
+            }
+            assert_eq!(treap.find(&x), expected.range(x..).next());
+        }
+        check(&treap, &expected);
+    }
+
+    #[test]
+    fn sorted_input_stays_shallow() {
+        let treap = create(3, 0..10_000);
+        let height = treap.as_tree().height().unwrap();
+        assert!(height < 60, "height {height}");
+        treap.check();
+    }
+
+    #[test]
+    fn split() {
+        for x in [-1, 0, 10, 11, 50, 98, 99, 100] {
+            let mut left = create(4, (0..100).filter(|x| x % 3 != 2));
+            let right = left.split(&x);
+            let expected = (0..100).filter(|x| x % 3 != 2);
+            check(&left, &expected.clone().filter(|&y| y <= x).collect());
+            check(&right, &expected.filter(|&y| y > x).collect());
+        }
+    }
+
+    #[test]
+    fn merge() {
+        let mut a = create(5, 0..50);
+        a.merge(create(6, 50..100));
+        check(&a, &(0..100).collect());
+
+        let mut b = create(7, 50..100);
+        b.merge(create(8, 0..50));
+        check(&b, &(0..100).collect());
+
+        let mut c = create(9, 0..10);
+        c.merge(Treap::new());
+        let mut d = Treap::new();
+        d.merge(c);
+        check(&d, &(0..10).collect());
+
+        let mut e = create(10, (0..1000).rev());
+        let f = e.split(&499);
+        e.merge(f);
+        check(&e, &(0..1000).collect());
+        assert!(e.add(1000));
+        check(&e, &(0..=1000).collect());
+    }
+
+    #[test]
+    #[should_panic(expected = "merged treaps must not overlap")]
+    fn merge_overlapping() {
+        let mut a = create(11, [1, 5]);
+        a.merge(create(12, [3]));
+    }
+
+    #[test]
+    fn into_iter() {
+        let treap = create(13, [4, 2, 8, 6]);
+        let mut iter = treap.into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.collect::<Vec<_>>(), [4, 6, 8]);
+    }
+
+    #[test]
+    fn drop_elements() {
+        let x = std::rc::Rc::new(0);
+        let mut treap = Treap::with_seed(14);
+        for i in 0..10 {
+            treap.add((i, x.clone()));
+        }
+        drop(treap.remove(&(3, x.clone())));
+        let other = treap.split(&(5, x.clone()));
+        assert_eq!(std::rc::Rc::strong_count(&x), 10);
+        drop(other);
+        assert_eq!(std::rc::Rc::strong_count(&x), 6);
+        drop(treap);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+    }
+
+    #[test]
+    fn clear() {
+        let x = std::rc::Rc::new(());
+        let mut treap = Treap::with_seed(5);
+        for i in 0..10 {
+            treap.add((i, x.clone()));
+        }
+        treap.clear();
+        assert_eq!(treap.size(), 0);
+        assert_eq!(std::rc::Rc::strong_count(&x), 1);
+        assert!(treap.add((1, x.clone())));
+        assert_eq!(treap.size(), 1);
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a: Treap<char> = "dbfaceg".chars().collect();
+        let mut b = Treap::with_seed(1);
+        b.extend("abcdefg".chars());
+        assert_eq!(a, b);
+        assert_eq!(crate::util::hash_code(&a), crate::util::hash_code(&b));
+        assert_eq!(format!("{a:?}"), "{'a', 'b', 'c', 'd', 'e', 'f', 'g'}");
+
+        let mut c = a.clone();
+        c.remove(&'a');
+        assert!(a.contains(&'a'));
+        assert!(a < c);
+        assert_eq!(c.cmp(&c.clone()), Ordering::Equal);
+    }
+}
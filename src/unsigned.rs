@@ -0,0 +1,58 @@
+use std::{fmt, hash::Hash};
+
+// The key types of the integer structures, which work on the bits of a key
+// widened to a `u64`.
+pub trait Unsigned: Copy + Ord + Hash + fmt::Debug {
+    const BITS: u32;
+    const MAX: Self;
+
+    fn to_u64(self) -> u64;
+    fn from_u64(x: u64) -> Self;
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty)*) => {$(
+        impl Unsigned for $t {
+            const BITS: u32 = <$t>::BITS;
+            const MAX: Self = <$t>::MAX;
+
+            fn to_u64(self) -> u64 {
+                self as u64
+            }
+
+            fn from_u64(x: u64) -> Self {
+                x as $t
+            }
+        }
+    )*};
+}
+
+impl_unsigned!(u8 u16 u32 u64 usize);
+
+// The first `i` bits of the `T`-wide key `x`.
+pub(crate) fn prefix<T: Unsigned>(x: u64, i: u32) -> u64 {
+    x.checked_shr(T::BITS - i).unwrap_or(0)
+}
+
+// The bit of `x` that picks the child at depth `i`.
+pub(crate) fn bit<T: Unsigned>(x: u64, i: u32) -> usize {
+    ((x >> (T::BITS - i - 1)) & 1) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bits() {
+        assert_eq!(prefix::<u8>(0b1011_0010, 0), 0);
+        assert_eq!(prefix::<u8>(0b1011_0010, 3), 0b101);
+        assert_eq!(prefix::<u8>(0b1011_0010, 8), 0b1011_0010);
+        assert_eq!(prefix::<u64>(u64::MAX, 0), 0);
+        assert_eq!(prefix::<u64>(u64::MAX, 64), u64::MAX);
+        assert_eq!(bit::<u8>(0b1000_0001, 0), 1);
+        assert_eq!(bit::<u8>(0b1000_0001, 1), 0);
+        assert_eq!(bit::<u8>(0b1000_0001, 7), 1);
+        assert_eq!(u16::from_u64(u16::MAX.to_u64()), u16::MAX);
+    }
+}
@@ -1,7 +1,54 @@
+use std::{
+    collections::TryReserveError,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
 pub(crate) fn allocate<T>(length: usize) -> Box<[Option<T>]> {
     std::iter::repeat_with(|| None).take(length).collect()
 }
 
+// Like `allocate`, but reports a failed allocation instead of aborting.
+pub(crate) fn try_allocate<T>(length: usize) -> Result<Box<[Option<T>]>, TryReserveError> {
+    let mut v = Vec::new();
+    v.try_reserve_exact(length)?;
+    v.extend(std::iter::repeat_with(|| None).take(length));
+    Ok(v.into_boxed_slice())
+}
+
+// A fixed-key hash standing in for Java's `hashCode`; each table mixes it
+// with its own random parameters.
+pub(crate) fn hash_code<T: Hash + ?Sized>(x: &T) -> u64 {
+    let mut state = DefaultHasher::new();
+    x.hash(&mut state);
+    state.finish()
+}
+
+// SplitMix64, which is good enough for randomized data structures and lets
+// tests reproduce a run from its seed.
+#[derive(Debug, Clone)]
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub(crate) fn new() -> Self {
+        use std::hash::{BuildHasher, RandomState};
+        Self::with_seed(RandomState::new().hash_one(0u64))
+    }
+
+    pub(crate) fn with_seed(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -16,4 +63,30 @@ mod tests {
             assert_eq!(v, None);
         }
     }
+
+    #[test]
+    fn try_allocate_successful() {
+        let a = try_allocate::<u32>(3).unwrap();
+        assert_eq!(a.len(), 3);
+        assert!(a.iter().all(Option::is_none));
+
+        assert!(try_allocate::<u32>(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn hash_code_stable() {
+        assert_eq!(hash_code(&42), hash_code(&42));
+        assert_eq!(hash_code("abc"), hash_code(&String::from("abc")));
+        assert_ne!(hash_code(&1), hash_code(&2));
+    }
+
+    #[test]
+    fn rng_reproducible() {
+        let mut a = Rng::with_seed(42);
+        let mut b = Rng::with_seed(42);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        assert_ne!(Rng::with_seed(1).next_u64(), Rng::with_seed(2).next_u64());
+    }
 }
@@ -0,0 +1,270 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ptr::NonNull,
+};
+
+use crate::{
+    BinaryTrie, LinearHashMap, Unsigned,
+    binary_trie::{self, Node},
+    unsigned::prefix,
+};
+
+// `t[i]` maps the first `i` bits of each key to the trie node at depth `i`,
+// so that `find` can binary search for the deepest node on a key's path.
+pub struct XFastTrie<T> {
+    trie: BinaryTrie<T>,
+    t: Box<[LinearHashMap<u64, NonNull<Node>>]>,
+}
+
+// SAFETY: the table only points into `trie`, which `XFastTrie` owns, so the
+// pointers move with it. It is deliberately not `Sync`.
+unsafe impl<T: Send> Send for XFastTrie<T> {}
+
+impl<T: Unsigned> Default for XFastTrie<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Unsigned> fmt::Debug for XFastTrie<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Unsigned> XFastTrie<T> {
+    pub fn new() -> Self {
+        let trie = BinaryTrie::new();
+        let mut t = (0..=T::BITS)
+            .map(|_| LinearHashMap::new())
+            .collect::<Box<[_]>>();
+        t[0].insert(0, trie.root());
+        Self { trie, t }
+    }
+
+    pub fn size(&self) -> usize {
+        self.trie.size()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    pub fn as_trie(&self) -> &BinaryTrie<T> {
+        &self.trie
+    }
+
+    pub fn iter(&self) -> binary_trie::Iter<'_, T> {
+        self.trie.iter()
+    }
+
+    // The smallest element that is not less than `x`, in O(log w) expected
+    // time for w-bit keys.
+    pub fn find(&self, x: T) -> Option<T> {
+        let x = x.to_u64();
+        let (mut l, mut h, mut u) = (0, T::BITS + 1, self.trie.root());
+        while h - l > 1 {
+            let i = (l + h) / 2;
+            match self.t[i as usize].get(&prefix::<T>(x, i)) {
+                Some(&v) => (l, u) = (i, v),
+                None => h = i,
+            }
+        }
+        // SAFETY: `u` is the deepest node on the path to `x`, at depth `l`.
+        unsafe { self.trie.successor_from(u, l, x) }.map(T::from_u64)
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        self.t[T::BITS as usize].contains_key(&x.to_u64())
+    }
+
+    pub fn add(&mut self, x: T) -> bool {
+        let x = x.to_u64();
+        let Some(mut u) = self.trie.add_leaf(x) else {
+            return false;
+        };
+        // The new nodes are the ones below the deepest existing prefix.
+        let mut i = T::BITS;
+        while self.t[i as usize].insert(prefix::<T>(x, i), u).is_none() {
+            i -= 1;
+            // SAFETY: `u` is a live node below the root.
+            u = unsafe { Node::parent(u) }.unwrap();
+        }
+        true
+    }
+
+    pub fn remove(&mut self, x: T) -> bool {
+        let x = x.to_u64();
+        let Some(d) = self.trie.remove_leaf(x) else {
+            return false;
+        };
+        for i in d + 1..=T::BITS {
+            self.t[i as usize].remove(&prefix::<T>(x, i));
+        }
+        true
+    }
+
+    #[cfg(test)]
+    pub(crate) fn check(&self) {
+        self.trie.check();
+        assert_eq!(self.t[T::BITS as usize].size(), self.size());
+        let nodes = self.t.iter().map(LinearHashMap::size).sum::<usize>();
+        assert_eq!(nodes, self.trie.node_count());
+        for x in self.iter() {
+            for i in 0..=T::BITS {
+                assert!(self.t[i as usize].contains_key(&prefix::<T>(x.to_u64(), i)));
+            }
+        }
+    }
+}
+
+impl<T: Unsigned> Clone for XFastTrie<T> {
+    fn clone(&self) -> Self {
+        self.iter().collect()
+    }
+}
+
+impl<T: Unsigned> PartialEq for XFastTrie<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Unsigned> Eq for XFastTrie<T> {}
+
+impl<T: Unsigned> PartialOrd for XFastTrie<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Unsigned> Ord for XFastTrie<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Unsigned> Hash for XFastTrie<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.size());
+        self.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl<T: Unsigned> FromIterator<T> for XFastTrie<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut c = Self::new();
+        c.extend(iter);
+        c
+    }
+}
+
+impl<T: Unsigned> Extend<T> for XFastTrie<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.add(x);
+        }
+    }
+}
+
+impl<'a, T: Unsigned> IntoIterator for &'a XFastTrie<T> {
+    type Item = T;
+    type IntoIter = binary_trie::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::Rng;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn scenario() {
+        let mut trie = XFastTrie::<u16>::new();
+        assert_eq!(trie.find(0), None);
+
+        for x in [300, 7, 65535, 0, 1024] {
+            assert!(trie.add(x));
+            trie.check();
+        }
+        assert!(!trie.add(7));
+        assert_eq!(trie.size(), 5);
+
+        assert_eq!(trie.find(1), Some(7));
+        assert_eq!(trie.find(300), Some(300));
+        assert_eq!(trie.find(301), Some(1024));
+        assert_eq!(trie.find(1025), Some(65535));
+        assert!(trie.contains(0));
+        assert!(!trie.contains(8));
+
+        assert!(trie.remove(65535));
+        assert!(!trie.remove(65535));
+        assert_eq!(trie.find(1025), None);
+        trie.check();
+        assert_eq!(format!("{trie:?}"), "{0, 7, 300, 1024}");
+    }
+
+    fn random_operations<T: Unsigned>(seed: u64, range: u64) {
+        let mut rng = Rng::with_seed(seed);
+        let mut trie = XFastTrie::<T>::new();
+        let mut expected = BTreeSet::new();
+        for _ in 0..2000 {
+            let x = T::from_u64(rng.next_u64() % range);
+            match rng.next_u64() % 3 {
+                0 | 1 => assert_eq!(trie.add(x), expected.insert(x)),
+                _ => assert_eq!(trie.remove(x), expected.remove(&x)),
+            }
+            let y = T::from_u64(rng.next_u64() % range);
+            assert_eq!(trie.find(y), expected.range(y..).next().copied());
+        }
+        trie.check();
+        assert!(trie.iter().eq(expected.iter().copied()));
+    }
+
+    #[test]
+    fn random_operations_u8() {
+        random_operations::<u8>(1, 256);
+    }
+
+    #[test]
+    fn random_operations_u64() {
+        random_operations::<u64>(2, u64::MAX);
+        random_operations::<u64>(3, 1000);
+    }
+
+    #[test]
+    fn clear() {
+        let mut trie = XFastTrie::<u16>::new();
+        for x in [3, 2000, 17] {
+            trie.add(x);
+        }
+        trie.clear();
+        assert_eq!(trie.find(0), None);
+        trie.add(9);
+        trie.check();
+        assert_eq!(trie.find(0), Some(9));
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a: XFastTrie<u32> = [9, 3, 7, 1].into_iter().collect();
+        let mut b = XFastTrie::new();
+        b.extend([1, 3, 7, 9]);
+        assert_eq!(a, b);
+        assert_eq!(crate::util::hash_code(&a), crate::util::hash_code(&b));
+        assert_eq!(format!("{a:?}"), "{1, 3, 7, 9}");
+
+        let mut c = a.clone();
+        c.remove(1);
+        c.add(2);
+        assert!(a.contains(1));
+        assert!(c.iter().eq([2, 3, 7, 9]));
+        assert!(a < c);
+    }
+}
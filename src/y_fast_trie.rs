@@ -0,0 +1,328 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use crate::{LinearHashMap, Treap, Unsigned, XFastTrie, binary_trie, treap, util::Rng};
+
+// The elements are split into blocks of about w each, for w-bit keys. Every
+// block is a treap, filed under its largest element in an x-fast trie, so a
+// search only has to look at one small treap. `T::MAX` is always filed, even
+// when it is not an element, so that every key falls in some block.
+pub struct YFastTrie<T> {
+    xft: XFastTrie<T>,
+    treaps: LinearHashMap<T, Treap<T>>,
+    n: usize,
+    rng: Rng,
+}
+
+impl<T: Unsigned> Default for YFastTrie<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Unsigned> fmt::Debug for YFastTrie<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Unsigned> YFastTrie<T> {
+    pub fn new() -> Self {
+        Self::with_rng(Rng::new())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(Rng::with_seed(seed))
+    }
+
+    fn with_rng(mut rng: Rng) -> Self {
+        let mut xft = XFastTrie::new();
+        let mut treaps = LinearHashMap::with_seed(rng.next_u64());
+        xft.add(T::MAX);
+        treaps.insert(T::MAX, Treap::with_seed(rng.next_u64()));
+        Self {
+            xft,
+            treaps,
+            n: 0,
+            rng,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::with_rng(self.rng.clone());
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            reps: self.xft.iter(),
+            treaps: &self.treaps,
+            block: None,
+            len: self.n,
+        }
+    }
+
+    // The largest element of the block that `x` falls in.
+    fn block(&self, x: T) -> T {
+        self.xft.find(x).unwrap()
+    }
+
+    pub fn find(&self, x: T) -> Option<T> {
+        self.treaps.get(&self.block(x)).unwrap().find(&x).copied()
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        self.treaps.get(&self.block(x)).unwrap().contains(&x)
+    }
+
+    pub fn add(&mut self, x: T) -> bool {
+        let y = self.block(x);
+        let t = self.treaps.get_mut(&y).unwrap();
+        if !t.add(x) {
+            return false;
+        }
+        self.n += 1;
+        // With probability 1/w, `x` starts a new block of the elements of
+        // this one up to `x`.
+        if x != y && self.rng.next_u64().is_multiple_of(T::BITS as u64) {
+            let upper = t.split(&x);
+            let lower = std::mem::replace(t, upper);
+            self.treaps.insert(x, lower);
+            self.xft.add(x);
+        }
+        true
+    }
+
+    pub fn remove(&mut self, x: T) -> bool {
+        let y = self.block(x);
+        if self.treaps.get_mut(&y).unwrap().remove(&x).is_none() {
+            return false;
+        }
+        self.n -= 1;
+        // A block filed under `x` joins the next one.
+        if x == y && x != T::MAX {
+            let lower = self.treaps.remove(&x).unwrap();
+            self.xft.remove(x);
+            let z = self.block(x);
+            self.treaps.get_mut(&z).unwrap().merge(lower);
+        }
+        true
+    }
+
+    #[cfg(test)]
+    fn check(&self) {
+        self.xft.check();
+        assert_eq!(self.treaps.size(), self.xft.size());
+        let mut lo = None;
+        for y in self.xft.iter() {
+            let t = self.treaps.get(&y).unwrap();
+            assert!(y == T::MAX || t.last() == Some(&y));
+            assert!(t.first().is_none_or(|&x| lo.is_none_or(|lo| lo < x)));
+            lo = Some(y);
+        }
+        assert_eq!(self.iter().count(), self.n);
+        assert!(self.iter().is_sorted_by(|a, b| a < b));
+    }
+}
+
+impl<T: Unsigned> Clone for YFastTrie<T> {
+    fn clone(&self) -> Self {
+        let mut c = Self::with_rng(self.rng.clone());
+        c.extend(self.iter());
+        c
+    }
+}
+
+impl<T: Unsigned> PartialEq for YFastTrie<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Unsigned> Eq for YFastTrie<T> {}
+
+impl<T: Unsigned> PartialOrd for YFastTrie<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Unsigned> Ord for YFastTrie<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Unsigned> Hash for YFastTrie<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.size());
+        self.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl<T: Unsigned> FromIterator<T> for YFastTrie<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut c = Self::new();
+        c.extend(iter);
+        c
+    }
+}
+
+impl<T: Unsigned> Extend<T> for YFastTrie<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.add(x);
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    reps: binary_trie::Iter<'a, T>,
+    treaps: &'a LinearHashMap<T, Treap<T>>,
+    block: Option<treap::Iter<'a, T>>,
+    len: usize,
+}
+
+impl<T: Unsigned> Iterator for Iter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(&x) = self.block.as_mut().and_then(Iterator::next) {
+                self.len -= 1;
+                return Some(x);
+            }
+            let y = self.reps.next()?;
+            self.block = Some(self.treaps.get(&y).unwrap().iter());
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T: Unsigned> ExactSizeIterator for Iter<'_, T> {}
+
+impl<'a, T: Unsigned> IntoIterator for &'a YFastTrie<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn scenario() {
+        let mut trie = YFastTrie::<u32>::with_seed(0);
+        assert_eq!(trie.find(0), None);
+        assert!(!trie.remove(u32::MAX));
+
+        for x in [40, 7, u32::MAX, 0, 1 << 20] {
+            assert!(trie.add(x));
+        }
+        assert!(!trie.add(7));
+        assert_eq!(trie.size(), 5);
+
+        assert_eq!(trie.find(1), Some(7));
+        assert_eq!(trie.find(40), Some(40));
+        assert_eq!(trie.find(41), Some(1 << 20));
+        assert_eq!(trie.find((1 << 20) + 1), Some(u32::MAX));
+        assert!(trie.contains(0));
+        assert!(!trie.contains(8));
+
+        assert!(trie.remove(u32::MAX));
+        assert!(!trie.contains(u32::MAX));
+        assert_eq!(trie.find((1 << 20) + 1), None);
+        trie.check();
+        assert_eq!(format!("{trie:?}"), "{0, 7, 40, 1048576}");
+    }
+
+    fn random_operations<T: Unsigned>(seed: u64, range: u64) {
+        let mut rng = Rng::with_seed(seed);
+        let mut trie = YFastTrie::<T>::with_seed(seed);
+        let mut expected = BTreeSet::new();
+        for _ in 0..3000 {
+            let x = T::from_u64(rng.next_u64() % range);
+            match rng.next_u64() % 3 {
+                0 | 1 => assert_eq!(trie.add(x), expected.insert(x)),
+                _ => assert_eq!(trie.remove(x), expected.remove(&x)),
+            }
+            let y = T::from_u64(rng.next_u64() % range);
+            assert_eq!(trie.find(y), expected.range(y..).next().copied());
+            assert_eq!(trie.size(), expected.len());
+        }
+        trie.check();
+        assert!(trie.iter().eq(expected.iter().copied()));
+    }
+
+    #[test]
+    fn random_operations_u8() {
+        random_operations::<u8>(1, 256);
+    }
+
+    #[test]
+    fn random_operations_u64() {
+        random_operations::<u64>(2, u64::MAX);
+        random_operations::<u64>(3, 2000);
+    }
+
+    #[test]
+    fn blocks() {
+        let mut trie = YFastTrie::<u16>::with_seed(4);
+        for x in 0..5000 {
+            trie.add(x);
+        }
+        trie.check();
+        // About one element in 16 is filed in the x-fast trie.
+        assert!((150..500).contains(&trie.xft.size()));
+        for x in (0..5000).step_by(2) {
+            assert!(trie.remove(x));
+        }
+        trie.check();
+        assert!(trie.iter().eq((1..5000).step_by(2)));
+    }
+
+    #[test]
+    fn clear() {
+        let mut trie = YFastTrie::<u16>::with_seed(5);
+        for x in 0..1000 {
+            trie.add(x);
+        }
+        trie.clear();
+        assert_eq!(trie.size(), 0);
+        assert_eq!(trie.find(0), None);
+        trie.add(9);
+        trie.check();
+        assert_eq!(trie.find(0), Some(9));
+    }
+
+    #[test]
+    fn standard_traits() {
+        let a: YFastTrie<u32> = [9, 3, 7, 1].into_iter().collect();
+        let mut b = YFastTrie::with_seed(1);
+        b.extend([1, 3, 7, 9]);
+        assert_eq!(a, b);
+        assert_eq!(crate::util::hash_code(&a), crate::util::hash_code(&b));
+        assert_eq!(format!("{a:?}"), "{1, 3, 7, 9}");
+
+        let mut c = a.clone();
+        c.remove(1);
+        c.add(2);
+        assert!(a.contains(1));
+        assert!(c.iter().eq([2, 3, 7, 9]));
+        assert!(a < c);
+    }
+}